@@ -0,0 +1,73 @@
+//! Motor evaluation throughput: `cargo bench --bench motors`
+//!
+//! Only `motors_update` is measured; the texture output path writes texels on one thread and isn't covered.
+
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use spector_id::bench::{
+    motors_update, GridCell, Motor, MotorChannel, MotorFadeSettings, MotorOutput, MotorTarget, Motors, SyncGroups,
+    Transport, Waveform,
+};
+
+const FRAME: f64 = 1.0 / 60.0;
+
+fn motored_app(cells: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins(TaskPoolPlugin::default())
+        .init_resource::<Transport>()
+        .init_resource::<MotorOutput>()
+        .init_resource::<MotorFadeSettings>()
+        .init_resource::<SyncGroups>()
+        .add_systems(Update, motors_update);
+
+    let side = (cells as f64).sqrt().ceil() as usize;
+    app.world_mut().spawn_batch((0..cells).map(move |i| {
+        let motors = Motors {
+            channels: vec![
+                MotorChannel::new("hue", Motor::new(0.05 + (i % 7) as f64 * 0.01), MotorTarget::Hue),
+                MotorChannel::new(
+                    "light",
+                    Motor {
+                        waveform: Waveform::Triangle,
+                        ..Motor::new(0.15)
+                    },
+                    MotorTarget::Lightness,
+                ),
+                MotorChannel::new("scale", Motor::new(0.3), MotorTarget::Scale),
+            ],
+        };
+        (
+            motors,
+            Sprite::from_color(Color::WHITE, Vec2::splat(4.0)),
+            Transform::default(),
+            GridCell { row: i / side, col: i % side },
+        )
+    }));
+    // First update evaluates everything once, like the frame the cells were spawned on
+    app.update();
+    app
+}
+
+fn motors_update_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("motors_update");
+    group.sample_size(20);
+    for cells in [10_000, 100_000] {
+        group.bench_with_input(BenchmarkId::new("playing", cells), &cells, |b, &cells| {
+            let mut app = motored_app(cells);
+            b.iter(|| {
+                app.world_mut().resource_mut::<Transport>().advance(FRAME);
+                app.update();
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("paused", cells), &cells, |b, &cells| {
+            let mut app = motored_app(cells);
+            app.world_mut().resource_mut::<Transport>().pause();
+            app.update();
+            b.iter(|| app.update());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, motors_update_bench);
+criterion_main!(benches);
@@ -5,9 +5,19 @@ mod tools;
 mod spaces;
 mod systems;
 
+/// The motor pieces `benches/motors.rs` drives directly, everything else stays private to the app
+pub mod bench {
+    pub use crate::tools::motors::{
+        motors_update, Motor, MotorChannel, MotorFadeSettings, MotorOutput, MotorTarget, Motors, SyncGroups, Waveform,
+    };
+    pub use crate::tools::tile_map_grid::components::GridCell;
+    pub use crate::tools::transport::Transport;
+}
+
 use crate::spaces::{GridSpacePlugin, GridAndMotorsSpacePlugin, FlexerSpacePlugin};
 use crate::ui::{StartupMenuPlugin, UiAssetsPlugin, UiPlugin}; // DrawingMenuPlugin removed due to camera conflicts
 use crate::systems::LoadingPlugin;
+use crate::tools::TransportPlugin;
 
 use bevy::app::App;
 use bevy::prelude::*;
@@ -39,6 +49,7 @@ impl Plugin for GamePlugin {
             StartupMenuPlugin,
             // DrawingMenuPlugin, // Disabled - causes camera conflicts with Motors state
             TilemapPlugin,
+            TransportPlugin,
             GridSpacePlugin,
             GridAndMotorsSpacePlugin,
             FlexerSpacePlugin
@@ -2,7 +2,7 @@ mod events;
 mod observers;
 
 use bevy::prelude::*;
-use crate::{GameState, spaces::grid_and_motors::{events::BackButtonPressed, observers::back_button_pressed_observer}, tools::{MotorsPlugin, TileMapGridPlugin}, ui::components::spawn_back_button, systems::loading::FontAssets};
+use crate::{GameState, spaces::grid_and_motors::{events::BackButtonPressed, observers::back_button_pressed_observer}, tools::{MotorAudioPlugin, MotorExportPlugin, MotorGraphPlugin, MotorInspectorPlugin, MotorScopePlugin, MotorsPlugin, OscPlugin, TileMapGridPlugin}, ui::components::spawn_back_button, systems::loading::FontAssets, tools::transport::spawn_transport_bar};
 
 #[derive(Component)]
 struct GridAndMotorsSpaceEntity;
@@ -14,7 +14,13 @@ impl Plugin for GridAndMotorsSpacePlugin {
         app
             .add_plugins((
                 TileMapGridPlugin,
-                // MotorsPlugin,
+                MotorsPlugin,
+                MotorGraphPlugin,
+                MotorInspectorPlugin,
+                MotorExportPlugin,
+                MotorAudioPlugin,
+                MotorScopePlugin,
+                OscPlugin,
             ))
             .add_event::<BackButtonPressed>()
             .add_systems(
@@ -35,6 +41,9 @@ fn setup_grid_and_motors_space(mut commands: Commands, asset_server: Res<AssetSe
     
     // Spawn UI back button
     spawn_back_button(&mut commands, &asset_server, &fonts);
+
+    let transport_bar = spawn_transport_bar(&mut commands, &fonts);
+    commands.entity(transport_bar).insert(GridAndMotorsSpaceEntity);
 }
 
 fn startup(commands: Commands, asset_server: Res<AssetServer>, fonts: Res<FontAssets>) {
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::tools::flex_grid::document::{Align, BoxId, Direction, FlexProp, Justify, LayoutMode};
+use crate::tools::flex_grid::export::FlexExportFormat;
+use crate::tools::flex_grid::motor::MotorParam;
+use crate::tools::flex_grid::resources::TextField;
+
+#[derive(Component)]
+pub struct FlexGridEntity;
+
+/// Rectangle drawn for a box
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlexBoxView(pub BoxId);
+
+/// Name and size label of a box
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlexBoxLabel(pub BoxId);
+
+/// Drag handle on the boundary between two neighbouring boxes, in visual order
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct FlexSplitter {
+    pub before: BoxId,
+    pub after: BoxId,
+    /// Whether the parent lays its children out in a row
+    pub row: bool,
+}
+
+/// Numbers shown next to a splitter while it is dragged
+#[derive(Component)]
+pub struct SplitterReadout;
+
+/// Container the property rows are rebuilt into
+#[derive(Component)]
+pub struct FlexPanelContent;
+
+#[derive(Component)]
+pub struct FlexPanelTitle;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlexValueText(pub FlexProp);
+
+/// Setting of the selected box's motor at `index`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MotorValueText {
+    pub index: usize,
+    pub param: MotorParam,
+}
+
+/// Text of a grid property or constraint, or the text being typed into it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FlexFieldText(pub TextField);
+
+/// Diagnosis under a constraint, `None` for the line listing values nothing constrains
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ConstraintStatusText(pub Option<usize>);
+
+/// Name of a grid area, drawn over its cells
+#[derive(Component)]
+pub struct GridAreaLabel;
+
+/// Handle on the root's right edge, dragging it resizes the container
+#[derive(Component)]
+pub struct ViewportEdge;
+
+/// Name and width above a breakpoint preview frame
+#[derive(Component)]
+pub struct PreviewLabel;
+
+/// Container width and the breakpoint being edited
+#[derive(Component)]
+pub struct FlexBreakpointText;
+
+/// Property panel action on the selected box
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum FlexButton {
+    Mode(LayoutMode),
+    Direction(Direction),
+    Justify(Justify),
+    Align(Align),
+    Step { prop: FlexProp, delta: f32 },
+    /// Switches an optional property between auto and a fixed value
+    Auto(FlexProp),
+    /// Starts typing into a grid property or constraint
+    Edit(TextField),
+    /// Cycles the priority of the selected box's constraint at this index
+    Priority(usize),
+    AddChild,
+    Remove,
+    /// Writes the whole layout to the exports folder
+    Export(FlexExportFormat),
+    /// Lays the root out at a preset width, `None` going back to its own width
+    Viewport(Option<f32>),
+    /// Drops the selected box's override in the breakpoint being edited
+    ClearOverride,
+    AddMotor(FlexProp),
+    /// Changes the selected box's motor at `index`
+    Motor { index: usize, action: MotorAction },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MotorAction {
+    Waveform,
+    Step { param: MotorParam, delta: f32 },
+    Remove,
+}
+
+/// Container the outline rows are rebuilt into
+#[derive(Component)]
+pub struct OutlineContent;
+
+/// Row of a box in the outline, clicking selects it and dragging moves it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OutlineRow(pub BoxId);
+
+/// Name of a box in the outline, or the name being typed
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OutlineName(pub BoxId);
+
+/// Line above or below a row showing where a dragged box would land
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OutlineDropLine {
+    pub id: BoxId,
+    pub after: bool,
+}
+
+/// Outline action on one box
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum OutlineButton {
+    /// Expands or collapses the box's children
+    Toggle(BoxId),
+    Visibility(BoxId),
+}
+
+/// Container the layout state buttons are rebuilt into
+#[derive(Component)]
+pub struct StateContent;
+
+/// Duration and easing of state switches
+#[derive(Component)]
+pub struct TransitionText;
+
+/// Layout state action, these act on the whole document rather than the selected box
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum StateButton {
+    Switch(usize),
+    Add,
+    /// Removes the active state
+    Remove,
+    /// Cycles the easing of state switches
+    Easing,
+    Duration(f32),
+    /// Writes the switch from the active state to the next as SVG frames
+    ExportFrames,
+}
@@ -0,0 +1,813 @@
+//! Linear constraints between the children of a constraint-mode box, solved with Cassowary
+//!
+//! A constraint reads like `A.right + 16 = B.left` or `A.width >= 0.3 * parent.width`. Boxes are named by their
+//! name, in brackets when it isn't a plain identifier (`[box 1].left`); `parent` is the container's content box.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use cassowary::strength::{MEDIUM, REQUIRED, STRONG, WEAK};
+use cassowary::{Constraint, Expression, RelationalOperator, Solver, Term, Variable};
+use serde::{Deserialize, Serialize};
+
+use crate::tools::flex_grid::document::{BoxId, FlexStyle};
+
+/// Strength of the defaults every child falls back to, weaker than any constraint a user can write
+const PREFERENCE: f64 = WEAK * 0.001;
+/// How far the defaults are moved to find out which values only they decide
+const PROBE_SHIFT: f64 = 1000.0;
+/// Pixels a constraint may be off by and still count as satisfied
+const TOLERANCE: f64 = 0.5;
+
+/// How hard the solver tries to satisfy a constraint; required ones may never be broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Priority {
+    #[default]
+    Required,
+    Strong,
+    Medium,
+    Weak,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [Priority::Required, Priority::Strong, Priority::Medium, Priority::Weak];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Priority::Required => "required",
+            Priority::Strong => "strong",
+            Priority::Medium => "medium",
+            Priority::Weak => "weak",
+        }
+    }
+
+    fn strength(self) -> f64 {
+        match self {
+            Priority::Required => REQUIRED,
+            Priority::Strong => STRONG,
+            Priority::Medium => MEDIUM,
+            Priority::Weak => WEAK,
+        }
+    }
+
+    /// Next weaker priority, wrapping back to required
+    pub fn next(self) -> Priority {
+        let index = Priority::ALL.iter().position(|priority| *priority == self).unwrap_or(0);
+        Priority::ALL[(index + 1) % Priority::ALL.len()]
+    }
+}
+
+/// One constraint as the user wrote it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutConstraint {
+    pub text: String,
+    pub priority: Priority,
+}
+
+impl LayoutConstraint {
+    /// Reads a typed constraint, an optional `@strong`, `@medium` or `@weak` suffix setting its priority
+    pub fn parse(text: &str) -> Result<LayoutConstraint, String> {
+        let (text, priority) = match text.rsplit_once('@') {
+            Some((text, suffix)) => {
+                let suffix = suffix.trim();
+                let priority = Priority::ALL
+                    .into_iter()
+                    .find(|priority| priority.label() == suffix)
+                    .ok_or_else(|| format!("unknown priority '{suffix}'"))?;
+                (text.trim(), priority)
+            }
+            None => (text.trim(), Priority::Required),
+        };
+        parse_relation(text)?;
+        Ok(LayoutConstraint {
+            text: text.to_string(),
+            priority,
+        })
+    }
+}
+
+/// Edge or size of a box a constraint can refer to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Width,
+    Height,
+    CenterX,
+    CenterY,
+}
+
+impl Attribute {
+    fn parse(name: &str) -> Option<Attribute> {
+        Some(match name {
+            "left" | "x" => Attribute::Left,
+            "right" => Attribute::Right,
+            "top" | "y" => Attribute::Top,
+            "bottom" => Attribute::Bottom,
+            "width" => Attribute::Width,
+            "height" => Attribute::Height,
+            "centerX" | "center_x" => Attribute::CenterX,
+            "centerY" | "center_y" => Attribute::CenterY,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Attribute::Left => "left",
+            Attribute::Right => "right",
+            Attribute::Top => "top",
+            Attribute::Bottom => "bottom",
+            Attribute::Width => "width",
+            Attribute::Height => "height",
+            Attribute::CenterX => "centerX",
+            Attribute::CenterY => "centerY",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Subject {
+    Parent,
+    Box(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Reference {
+    subject: Subject,
+    attribute: Attribute,
+}
+
+/// Sum of weighted references plus a constant
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Linear {
+    terms: Vec<(Reference, f64)>,
+    constant: f64,
+}
+
+impl Linear {
+    fn scaled(mut self, factor: f64) -> Linear {
+        for (_, coefficient) in self.terms.iter_mut() {
+            *coefficient *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+
+    fn plus(mut self, other: Linear) -> Linear {
+        self.terms.extend(other.terms);
+        self.constant += other.constant;
+        self
+    }
+}
+
+/// `expression relation 0`, the right-hand side moved over
+#[derive(Debug, Clone, PartialEq)]
+struct Relation {
+    expression: Linear,
+    operator: RelationalOperator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Dot,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Relation(RelationalOperator),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => {}
+            '.' => tokens.push(Token::Dot),
+            '+' => tokens.push(Token::Plus),
+            '-' => tokens.push(Token::Minus),
+            '*' => tokens.push(Token::Star),
+            '/' => tokens.push(Token::Slash),
+            '=' => {
+                chars.next_if_eq(&'=');
+                tokens.push(Token::Relation(RelationalOperator::Equal));
+            }
+            '<' | '>' => {
+                if chars.next_if_eq(&'=').is_none() {
+                    return Err(format!("expected '{c}='"));
+                }
+                tokens.push(Token::Relation(if c == '<' {
+                    RelationalOperator::LessOrEqual
+                } else {
+                    RelationalOperator::GreaterOrEqual
+                }));
+            }
+            '[' => {
+                let name: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                tokens.push(Token::Name(name.trim().to_string()));
+            }
+            c if c.is_ascii_digit() => {
+                let mut number = c.to_string();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(digit);
+                }
+                let mut value: f64 = number.parse().map_err(|_| format!("bad number '{number}'"))?;
+                if chars.next_if_eq(&'%').is_some() {
+                    value /= 100.0;
+                }
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(next) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    name.push(next);
+                }
+                tokens.push(Token::Name(name));
+            }
+            _ => return Err(format!("unexpected '{c}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expression(&mut self) -> Result<Linear, String> {
+        let mut sum = if self.peek() == Some(&Token::Minus) {
+            self.position += 1;
+            self.product()?.scaled(-1.0)
+        } else {
+            self.product()?
+        };
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.position += 1;
+                    sum = sum.plus(self.product()?);
+                }
+                Some(Token::Minus) => {
+                    self.position += 1;
+                    sum = sum.plus(self.product()?.scaled(-1.0));
+                }
+                _ => return Ok(sum),
+            }
+        }
+    }
+
+    /// Factors multiplied together; at most one of them may be a reference, keeping it linear
+    fn product(&mut self) -> Result<Linear, String> {
+        let mut product = self.factor()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.position += 1;
+                    let factor = self.factor()?;
+                    product = match (product.terms.is_empty(), factor.terms.is_empty()) {
+                        (_, true) => product.scaled(factor.constant),
+                        (true, false) => factor.scaled(product.constant),
+                        (false, false) => return Err("two references multiplied, constraints must be linear".to_string()),
+                    };
+                }
+                Some(Token::Slash) => {
+                    self.position += 1;
+                    let divisor = self.factor()?;
+                    if !divisor.terms.is_empty() || divisor.constant == 0.0 {
+                        return Err("can only divide by a non-zero number".to_string());
+                    }
+                    product = product.scaled(1.0 / divisor.constant);
+                }
+                _ => return Ok(product),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<Linear, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Linear {
+                terms: Vec::new(),
+                constant: value,
+            }),
+            Some(Token::Name(name)) => {
+                if self.next() != Some(Token::Dot) {
+                    return Err(format!("expected '.' and a property after '{name}'"));
+                }
+                let Some(Token::Name(attribute)) = self.next() else {
+                    return Err(format!("expected a property after '{name}.'"));
+                };
+                let attribute = Attribute::parse(&attribute).ok_or_else(|| format!("unknown property '{attribute}'"))?;
+                let subject = if name == "parent" { Subject::Parent } else { Subject::Box(name) };
+                Ok(Linear {
+                    terms: vec![(Reference { subject, attribute }, 1.0)],
+                    constant: 0.0,
+                })
+            }
+            Some(token) => Err(format!("unexpected {token:?}")),
+            None => Err("expression ends too early".to_string()),
+        }
+    }
+}
+
+fn parse_relation(text: &str) -> Result<Relation, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+    };
+    let left = parser.expression()?;
+    let Some(Token::Relation(operator)) = parser.next() else {
+        return Err("expected '=', '<=' or '>='".to_string());
+    };
+    let right = parser.expression()?;
+    if let Some(token) = parser.next() {
+        return Err(format!("unexpected {token:?} after the constraint"));
+    }
+    Ok(Relation {
+        expression: left.plus(right.scaled(-1.0)),
+        operator,
+    })
+}
+
+/// How a box is written in a constraint: bare when it reads as a name, in brackets otherwise
+pub fn reference_name(name: &str) -> String {
+    let bare = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if bare {
+        name.to_string()
+    } else {
+        format!("[{name}]")
+    }
+}
+
+/// `text` with the references to box `from` pointing at `to`, everything else left as written
+pub fn rename_references(text: &str, from: &str, to: &str) -> String {
+    map_references(text, |name| (name == from).then(|| reference_name(to)))
+}
+
+/// Whether `text` refers to box `name`
+pub fn mentions(text: &str, name: &str) -> bool {
+    let mut found = false;
+    map_references(text, |reference| {
+        found |= reference == name;
+        None
+    });
+    found
+}
+
+/// `text` with each box reference `replace` returns something for written as that
+fn map_references(text: &str, mut replace: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    // Names right after a dot are properties, not boxes
+    let mut after_dot = false;
+    while let Some(c) = rest.chars().next() {
+        let (len, name) = if c == '[' {
+            let end = rest.find(']').unwrap_or(rest.len());
+            ((end + 1).min(rest.len()), Some(rest[1..end].trim()))
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            (end, Some(&rest[..end]))
+        } else if c.is_ascii_digit() {
+            // Numbers keep their decimal point
+            (rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len()), None)
+        } else {
+            (c.len_utf8(), None)
+        };
+        match name.filter(|_| !after_dot).and_then(&mut replace) {
+            Some(replacement) => out.push_str(&replacement),
+            None => out.push_str(&rest[..len]),
+        }
+        if !c.is_whitespace() {
+            after_dot = c == '.';
+        }
+        rest = &rest[len..];
+    }
+    out
+}
+
+/// Why a constraint isn't reflected in the layout
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintIssue {
+    /// Doesn't parse or names a box that isn't a child
+    Invalid(String),
+    /// Required, but can't hold together with the other required constraints marked the same
+    Conflict,
+    /// Broken in favour of stronger constraints
+    Unsatisfied,
+}
+
+impl fmt::Display for ConstraintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintIssue::Invalid(err) => write!(f, "invalid: {err}"),
+            ConstraintIssue::Conflict => write!(f, "conflicts with other required constraints"),
+            ConstraintIssue::Unsatisfied => write!(f, "not satisfied, stronger constraints win"),
+        }
+    }
+}
+
+/// Diagnosis of a constraint container, indices matching its constraint list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConstraintReport {
+    pub issues: Vec<Option<ConstraintIssue>>,
+    /// Children each constraint mentions
+    pub boxes: Vec<Vec<BoxId>>,
+    /// Values no constraint decides, left at their defaults
+    pub loose: Vec<(BoxId, Attribute)>,
+}
+
+/// Child of a constraint container, with the size it takes when nothing constrains it
+pub struct ConstrainedChild<'a> {
+    pub id: BoxId,
+    pub name: &'a str,
+    pub preferred: Vec2,
+}
+
+#[derive(Clone, Copy)]
+struct Variables {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable,
+}
+
+/// Solver input shared by every solve of one container
+struct System<'a> {
+    children: &'a [ConstrainedChild<'a>],
+    variables: Vec<Variables>,
+    /// Non-negative sizes, always required
+    implicit: Vec<Constraint>,
+}
+
+impl System<'_> {
+    /// Adds the variables behind `name.attribute` to `out`, returning the child named
+    fn push_terms(&self, name: &str, attribute: Attribute, coefficient: f64, out: &mut Expression) -> Result<BoxId, String> {
+        let index = self
+            .children
+            .iter()
+            .position(|child| child.name == name)
+            .ok_or_else(|| format!("no visible child named '{name}'"))?;
+        let variables = self.variables[index];
+        let mut push = |variable: Variable, factor: f64| {
+            out.terms.push(Term {
+                variable,
+                coefficient: coefficient * factor,
+            });
+        };
+        match attribute {
+            Attribute::Left => push(variables.left, 1.0),
+            Attribute::Top => push(variables.top, 1.0),
+            Attribute::Width => push(variables.width, 1.0),
+            Attribute::Height => push(variables.height, 1.0),
+            Attribute::Right => {
+                push(variables.left, 1.0);
+                push(variables.width, 1.0);
+            }
+            Attribute::Bottom => {
+                push(variables.top, 1.0);
+                push(variables.height, 1.0);
+            }
+            Attribute::CenterX => {
+                push(variables.left, 1.0);
+                push(variables.width, 0.5);
+            }
+            Attribute::CenterY => {
+                push(variables.top, 1.0);
+                push(variables.height, 0.5);
+            }
+        }
+        Ok(self.children[index].id)
+    }
+
+    /// Cassowary constraint and the children it mentions
+    fn resolve(&self, constraint: &LayoutConstraint, size: Vec2) -> Result<(Constraint, Vec<BoxId>), String> {
+        let relation = parse_relation(&constraint.text)?;
+        let mut expression = Expression::from_constant(relation.expression.constant);
+        let mut boxes = Vec::new();
+        let (width, height) = (f64::from(size.x), f64::from(size.y));
+        for (reference, coefficient) in &relation.expression.terms {
+            // The container's edges are known, they fold into the constant
+            let Subject::Box(name) = &reference.subject else {
+                expression.constant += coefficient
+                    * match reference.attribute {
+                        Attribute::Left | Attribute::Top => 0.0,
+                        Attribute::Right | Attribute::Width => width,
+                        Attribute::Bottom | Attribute::Height => height,
+                        Attribute::CenterX => width * 0.5,
+                        Attribute::CenterY => height * 0.5,
+                    };
+                continue;
+            };
+            let id = self.push_terms(name, reference.attribute, *coefficient, &mut expression)?;
+            if !boxes.contains(&id) {
+                boxes.push(id);
+            }
+        }
+        Ok((Constraint::new(expression, relation.operator, constraint.priority.strength()), boxes))
+    }
+
+    /// Solver holding the implicit constraints and the defaults, moved by `shift`
+    fn solver(&self, shift: f64) -> Solver {
+        let mut solver = Solver::new();
+        let _ = solver.add_constraints(&self.implicit);
+        for (child, variables) in self.children.iter().zip(&self.variables) {
+            let defaults = [
+                (variables.left, 0.0),
+                (variables.top, 0.0),
+                (variables.width, f64::from(child.preferred.x)),
+                (variables.height, f64::from(child.preferred.y)),
+            ];
+            for (variable, value) in defaults {
+                let expression = Expression::new(
+                    vec![Term {
+                        variable,
+                        coefficient: 1.0,
+                    }],
+                    -(value + shift),
+                );
+                let _ = solver.add_constraint(Constraint::new(expression, RelationalOperator::Equal, PREFERENCE));
+            }
+        }
+        solver
+    }
+
+    fn feasible(&self, constraints: &[&Constraint]) -> bool {
+        let mut solver = self.solver(0.0);
+        constraints.iter().all(|constraint| solver.add_constraint((*constraint).clone()).is_ok())
+    }
+}
+
+fn violated(constraint: &Constraint, solver: &Solver) -> bool {
+    let expression = constraint.expr();
+    let value = expression.constant
+        + expression
+            .terms
+            .iter()
+            .map(|term| term.coefficient * solver.get_value(term.variable))
+            .sum::<f64>();
+    match constraint.op() {
+        RelationalOperator::Equal => value.abs() > TOLERANCE,
+        RelationalOperator::GreaterOrEqual => value < -TOLERANCE,
+        RelationalOperator::LessOrEqual => value > TOLERANCE,
+    }
+}
+
+/// Child rectangles relative to the container's content box, which is `size` big
+pub fn solve_constraints(
+    children: &[ConstrainedChild],
+    constraints: &[LayoutConstraint],
+    size: Vec2,
+) -> (HashMap<BoxId, Rect>, ConstraintReport) {
+    let variables: Vec<Variables> = children
+        .iter()
+        .map(|_| Variables {
+            left: Variable::new(),
+            top: Variable::new(),
+            width: Variable::new(),
+            height: Variable::new(),
+        })
+        .collect();
+    let implicit = variables
+        .iter()
+        .flat_map(|variables| [variables.width, variables.height])
+        .map(|variable| {
+            let expression = Expression::from_term(Term {
+                variable,
+                coefficient: 1.0,
+            });
+            Constraint::new(expression, RelationalOperator::GreaterOrEqual, REQUIRED)
+        })
+        .collect();
+    let system = System {
+        children,
+        variables,
+        implicit,
+    };
+
+    let mut report = ConstraintReport {
+        issues: vec![None; constraints.len()],
+        boxes: vec![Vec::new(); constraints.len()],
+        loose: Vec::new(),
+    };
+    let mut solver = system.solver(0.0);
+    let mut accepted: Vec<(usize, Constraint)> = Vec::new();
+    for (index, constraint) in constraints.iter().enumerate() {
+        let (resolved, boxes) = match system.resolve(constraint, size) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                report.issues[index] = Some(ConstraintIssue::Invalid(err));
+                continue;
+            }
+        };
+        report.boxes[index] = boxes;
+        if solver.add_constraint(resolved.clone()).is_ok() {
+            accepted.push((index, resolved));
+            continue;
+        }
+        // Shrink the required constraints accepted so far to the few this one can't hold together with
+        let mut culprits: Vec<&(usize, Constraint)> =
+            accepted.iter().filter(|(_, accepted)| accepted.strength() >= REQUIRED).collect();
+        let mut candidate = 0;
+        while candidate < culprits.len() {
+            let without: Vec<&Constraint> = culprits
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != candidate)
+                .map(|(_, (_, constraint))| constraint)
+                .chain([&resolved])
+                .collect();
+            if system.feasible(&without) {
+                candidate += 1;
+            } else {
+                culprits.remove(candidate);
+            }
+        }
+        for (culprit, _) in culprits {
+            report.issues[*culprit].get_or_insert(ConstraintIssue::Conflict);
+        }
+        report.issues[index] = Some(ConstraintIssue::Conflict);
+    }
+
+    for (index, constraint) in accepted.iter() {
+        if constraint.strength() < REQUIRED && violated(constraint, &solver) {
+            report.issues[*index].get_or_insert(ConstraintIssue::Unsatisfied);
+        }
+    }
+
+    // Whatever moves along with the defaults is decided by nothing else
+    let mut probe = system.solver(PROBE_SHIFT);
+    for (_, constraint) in accepted.iter() {
+        let _ = probe.add_constraint(constraint.clone());
+    }
+    let mut rects = HashMap::new();
+    for (child, variables) in children.iter().zip(&system.variables) {
+        let values = [
+            (Attribute::Left, variables.left),
+            (Attribute::Top, variables.top),
+            (Attribute::Width, variables.width),
+            (Attribute::Height, variables.height),
+        ];
+        for (attribute, variable) in values {
+            if (solver.get_value(variable) - probe.get_value(variable)).abs() > TOLERANCE {
+                report.loose.push((child.id, attribute));
+            }
+        }
+        let value = |variable: Variable| solver.get_value(variable) as f32;
+        let min = Vec2::new(value(variables.left), value(variables.top));
+        let size = Vec2::new(value(variables.width), value(variables.height)).max(Vec2::ZERO);
+        rects.insert(child.id, Rect::from_corners(min, min + size));
+    }
+    (rects, report)
+}
+
+/// Constraint being typed in the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintField {
+    /// Rewrites the constraint at this index, emptying it removes it
+    Existing(usize),
+    /// Appends a constraint
+    New,
+}
+
+impl ConstraintField {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConstraintField::Existing(_) => "constraint",
+            ConstraintField::New => "add",
+        }
+    }
+
+    pub fn get(self, style: &FlexStyle) -> String {
+        match self {
+            ConstraintField::Existing(index) => style
+                .constraints
+                .get(index)
+                .map(|constraint| constraint.text.clone())
+                .unwrap_or_default(),
+            ConstraintField::New => String::new(),
+        }
+    }
+
+    /// Parses `text` into the constraint list, leaving the style untouched on error
+    pub fn set(self, style: &mut FlexStyle, text: &str) -> Result<(), String> {
+        match self {
+            ConstraintField::Existing(index) if text.trim().is_empty() => {
+                if index < style.constraints.len() {
+                    style.constraints.remove(index);
+                }
+            }
+            ConstraintField::Existing(index) => {
+                let parsed = LayoutConstraint::parse(text)?;
+                let Some(constraint) = style.constraints.get_mut(index) else {
+                    return Err("constraint no longer exists".to_string());
+                };
+                constraint.text = parsed.text;
+                // Keeps the priority picked with the button unless the text names one
+                if text.contains('@') {
+                    constraint.priority = parsed.priority;
+                }
+            }
+            ConstraintField::New if text.trim().is_empty() => {}
+            ConstraintField::New => style.constraints.push(LayoutConstraint::parse(text)?),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renaming_rewrites_box_references_only() {
+        let text = "sidebar.width == 0.5 * content.width + sidebar.left";
+        assert_eq!(
+            rename_references(text, "sidebar", "nav"),
+            "nav.width == 0.5 * content.width + nav.left"
+        );
+        // Properties and other boxes sharing the name stay put
+        assert_eq!(rename_references("width.width == 10", "width", "w"), "w.width == 10");
+        assert_eq!(rename_references("sidebars.left == 0", "sidebar", "nav"), "sidebars.left == 0");
+        assert_eq!(
+            rename_references("[main area].top >= 12.5", "main area", "content"),
+            "content.top >= 12.5"
+        );
+        assert_eq!(rename_references("nav.top == 0", "nav", "side bar"), "[side bar].top == 0");
+        assert!(mentions(text, "content") && mentions("[main area].top >= 0", "main area"));
+        assert!(!mentions(text, "width") && !mentions(text, "nav"));
+    }
+
+    #[test]
+    fn priority_comes_from_the_suffix() {
+        let constraint = LayoutConstraint::parse(" a.left == 0 @weak").unwrap();
+        assert_eq!(constraint.text, "a.left == 0");
+        assert_eq!(constraint.priority, Priority::Weak);
+        assert_eq!(LayoutConstraint::parse("a.left >= 0").unwrap().priority, Priority::Required);
+        assert!(LayoutConstraint::parse("a.left == 0 @loud").is_err());
+        assert!(LayoutConstraint::parse("a.left ==").is_err());
+    }
+
+    #[test]
+    fn solving_reports_what_each_constraint_did() {
+        let children = [
+            ConstrainedChild {
+                id: BoxId(1),
+                name: "a",
+                preferred: Vec2::new(100.0, 50.0),
+            },
+            ConstrainedChild {
+                id: BoxId(2),
+                name: "b",
+                preferred: Vec2::new(100.0, 50.0),
+            },
+        ];
+        let constraints: Vec<LayoutConstraint> = [
+            "a.left == 10",
+            "a.left == 20",
+            "b.left == a.right + 8 @strong",
+            "b.width == 200 @strong",
+            "b.width == 50 @weak",
+            "c.top == 0",
+        ]
+        .into_iter()
+        .map(|text| LayoutConstraint::parse(text).unwrap())
+        .collect();
+
+        let (rects, report) = solve_constraints(&children, &constraints, Vec2::new(400.0, 300.0));
+        assert_eq!(report.issues[0], Some(ConstraintIssue::Conflict));
+        assert_eq!(report.issues[1], Some(ConstraintIssue::Conflict));
+        assert_eq!(report.issues[2], None);
+        assert_eq!(report.issues[3], None);
+        assert_eq!(report.issues[4], Some(ConstraintIssue::Unsatisfied));
+        assert!(matches!(report.issues[5], Some(ConstraintIssue::Invalid(_))));
+        assert_eq!(report.boxes[2], [BoxId(2), BoxId(1)]);
+
+        assert_eq!(rects[&BoxId(1)].min.x, 10.0);
+        assert_eq!(rects[&BoxId(2)].min.x, rects[&BoxId(1)].max.x + 8.0);
+        assert_eq!(rects[&BoxId(2)].width(), 200.0);
+
+        // Nothing places the boxes vertically or sizes a, and b sits after a's loose width
+        let loose = |id: u32, attribute: Attribute| report.loose.contains(&(BoxId(id), attribute));
+        assert!(loose(1, Attribute::Top) && loose(1, Attribute::Width) && loose(2, Attribute::Height));
+        assert!(loose(2, Attribute::Left));
+        assert!(!loose(1, Attribute::Left) && !loose(2, Attribute::Width));
+    }
+}
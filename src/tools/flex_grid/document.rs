@@ -0,0 +1,777 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::flex_grid::constraint::{mentions, rename_references, LayoutConstraint};
+use crate::tools::flex_grid::grid::{GridItem, GridTemplate};
+use crate::tools::flex_grid::motor::LayoutMotor;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BoxId(pub u32);
+
+/// How a box lays out its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LayoutMode {
+    #[default]
+    Flex,
+    Grid,
+    /// Children placed by solving the box's linear constraints
+    Constraint,
+}
+
+impl LayoutMode {
+    pub const ALL: [LayoutMode; 3] = [LayoutMode::Flex, LayoutMode::Grid, LayoutMode::Constraint];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Direction {
+    #[default]
+    Row,
+    Column,
+    RowReverse,
+    ColumnReverse,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Row, Direction::Column, Direction::RowReverse, Direction::ColumnReverse];
+
+    pub fn is_row(self) -> bool {
+        matches!(self, Direction::Row | Direction::RowReverse)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Justify {
+    #[default]
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+impl Justify {
+    pub const ALL: [Justify; 6] = [
+        Justify::Start,
+        Justify::End,
+        Justify::Center,
+        Justify::SpaceBetween,
+        Justify::SpaceAround,
+        Justify::SpaceEvenly,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Align {
+    #[default]
+    Stretch,
+    Start,
+    End,
+    Center,
+}
+
+impl Align {
+    pub const ALL: [Align; 4] = [Align::Stretch, Align::Start, Align::End, Align::Center];
+}
+
+/// Padding or margin per side, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Edges {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Edges {
+    pub const fn all(value: f32) -> Self {
+        Self {
+            top: value,
+            right: value,
+            bottom: value,
+            left: value,
+        }
+    }
+}
+
+/// Flex container and item properties of one box; `None` sizes are `auto`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlexStyle {
+    #[serde(default)]
+    pub mode: LayoutMode,
+    pub direction: Direction,
+    pub justify: Justify,
+    pub align: Align,
+    pub gap: f32,
+    pub grow: f32,
+    pub shrink: f32,
+    pub basis: Option<f32>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub padding: Edges,
+    pub margin: Edges,
+    /// Tracks and areas, used in grid mode
+    #[serde(default)]
+    pub grid: GridTemplate,
+    /// Placement when the parent is a grid
+    #[serde(default)]
+    pub item: GridItem,
+    /// Relations between the children, used in constraint mode
+    #[serde(default)]
+    pub constraints: Vec<LayoutConstraint>,
+}
+
+impl Default for FlexStyle {
+    fn default() -> Self {
+        Self {
+            mode: LayoutMode::Flex,
+            direction: Direction::Row,
+            justify: Justify::Start,
+            align: Align::Stretch,
+            gap: 8.0,
+            grow: 0.0,
+            shrink: 1.0,
+            basis: None,
+            width: None,
+            height: None,
+            padding: Edges::all(8.0),
+            margin: Edges::default(),
+            grid: GridTemplate::default(),
+            item: GridItem::default(),
+            constraints: Vec::new(),
+        }
+    }
+}
+
+impl FlexStyle {
+    /// CSS initial values, what a plain `display: flex` div starts from
+    pub fn initial() -> Self {
+        Self {
+            gap: 0.0,
+            padding: Edges::default(),
+            ..default()
+        }
+    }
+}
+
+/// Numeric style property editable from the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlexProp {
+    Gap,
+    Grow,
+    Shrink,
+    Basis,
+    Width,
+    Height,
+    /// All four sides at once
+    Padding,
+    Margin,
+}
+
+impl FlexProp {
+    pub const ALL: [FlexProp; 8] = [
+        FlexProp::Gap,
+        FlexProp::Grow,
+        FlexProp::Shrink,
+        FlexProp::Basis,
+        FlexProp::Width,
+        FlexProp::Height,
+        FlexProp::Padding,
+        FlexProp::Margin,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FlexProp::Gap => "gap",
+            FlexProp::Grow => "grow",
+            FlexProp::Shrink => "shrink",
+            FlexProp::Basis => "basis",
+            FlexProp::Width => "width",
+            FlexProp::Height => "height",
+            FlexProp::Padding => "padding",
+            FlexProp::Margin => "margin",
+        }
+    }
+
+    /// Whether the property can be `auto`
+    pub fn optional(self) -> bool {
+        matches!(self, FlexProp::Basis | FlexProp::Width | FlexProp::Height)
+    }
+
+    /// Amount the -/+ buttons change the value by
+    pub fn step(self) -> f32 {
+        match self {
+            FlexProp::Grow | FlexProp::Shrink => 0.5,
+            FlexProp::Gap | FlexProp::Padding | FlexProp::Margin => 4.0,
+            FlexProp::Basis | FlexProp::Width | FlexProp::Height => 10.0,
+        }
+    }
+
+    /// Current value, `None` when auto; padding and margin report the top side
+    pub fn get(self, style: &FlexStyle) -> Option<f32> {
+        match self {
+            FlexProp::Gap => Some(style.gap),
+            FlexProp::Grow => Some(style.grow),
+            FlexProp::Shrink => Some(style.shrink),
+            FlexProp::Basis => style.basis,
+            FlexProp::Width => style.width,
+            FlexProp::Height => style.height,
+            FlexProp::Padding => Some(style.padding.top),
+            FlexProp::Margin => Some(style.margin.top),
+        }
+    }
+
+    /// Sets the value, clamped at zero; `None` only applies to optional properties
+    pub fn set(self, style: &mut FlexStyle, value: Option<f32>) {
+        let value = value.map(|value| value.max(0.0));
+        match self {
+            FlexProp::Gap => style.gap = value.unwrap_or(0.0),
+            FlexProp::Grow => style.grow = value.unwrap_or(0.0),
+            FlexProp::Shrink => style.shrink = value.unwrap_or(0.0),
+            FlexProp::Basis => style.basis = value,
+            FlexProp::Width => style.width = value,
+            FlexProp::Height => style.height = value,
+            FlexProp::Padding => style.padding = Edges::all(value.unwrap_or(0.0)),
+            FlexProp::Margin => style.margin = Edges::all(value.unwrap_or(0.0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlexBox {
+    pub id: BoxId,
+    pub name: String,
+    pub style: FlexStyle,
+    /// Complete style replacing `style` inside a breakpoint, keyed by breakpoint name
+    #[serde(default)]
+    pub overrides: BTreeMap<String, FlexStyle>,
+    /// Hidden boxes and everything inside them take no space, like `display: none`
+    #[serde(default)]
+    pub hidden: bool,
+    /// Swing the box's properties over transport time without changing the document
+    #[serde(default)]
+    pub motors: Vec<LayoutMotor>,
+    pub children: Vec<BoxId>,
+}
+
+/// Container widths up to `max_width` get their own styles, like a `max-width` media query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Breakpoint {
+    pub name: String,
+    pub max_width: f32,
+}
+
+fn default_breakpoints() -> Vec<Breakpoint> {
+    vec![
+        Breakpoint {
+            name: "tablet".to_string(),
+            max_width: 1024.0,
+        },
+        Breakpoint {
+            name: "phone".to_string(),
+            max_width: 600.0,
+        },
+    ]
+}
+
+/// Properties a layout state gives one box
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoxStyles {
+    pub style: FlexStyle,
+    #[serde(default)]
+    pub overrides: BTreeMap<String, FlexStyle>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// Named set of properties on the same box tree, e.g. a menu closed and open
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutState {
+    pub name: String,
+    pub styles: BTreeMap<BoxId, BoxStyles>,
+}
+
+/// Tree of boxes edited in the Flexer space
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlexDocument {
+    pub root: BoxId,
+    pub boxes: BTreeMap<BoxId, FlexBox>,
+    /// Widest first
+    #[serde(default = "default_breakpoints")]
+    pub breakpoints: Vec<Breakpoint>,
+    /// Empty until a second state is added; the boxes hold the active state's properties while it is edited
+    #[serde(default)]
+    pub states: Vec<LayoutState>,
+    #[serde(default)]
+    pub active_state: usize,
+    next_id: u32,
+}
+
+impl Default for FlexDocument {
+    /// Page skeleton to start from: header, sidebar and content, footer
+    fn default() -> Self {
+        let mut document = FlexDocument::new("page");
+        let root = document.root;
+        if let Some(page) = document.get_mut(root) {
+            page.style.direction = Direction::Column;
+            // Desktop sized, so the base styles sit above every breakpoint
+            page.style.width = Some(1280.0);
+            page.style.height = Some(720.0);
+        }
+        let header = document.add_child(root, "header");
+        let body = document.add_child(root, "body");
+        let footer = document.add_child(root, "footer");
+        let sidebar = document.add_child(body, "sidebar");
+        let content = document.add_child(body, "content");
+        for (id, height) in [(header, 56.0), (footer, 40.0)] {
+            if let Some(item) = document.get_mut(id) {
+                item.style.height = Some(height);
+            }
+        }
+        if let Some(item) = document.get_mut(body) {
+            item.style.grow = 1.0;
+        }
+        if let Some(item) = document.get_mut(sidebar) {
+            item.style.basis = Some(160.0);
+        }
+        if let Some(item) = document.get_mut(content) {
+            item.style.grow = 1.0;
+        }
+        document
+    }
+}
+
+impl FlexDocument {
+    /// Document holding just a root box
+    pub fn new(root_name: impl Into<String>) -> Self {
+        let root = BoxId(0);
+        let mut boxes = BTreeMap::new();
+        boxes.insert(
+            root,
+            FlexBox {
+                id: root,
+                name: root_name.into(),
+                style: FlexStyle::default(),
+                overrides: BTreeMap::new(),
+                hidden: false,
+                motors: Vec::new(),
+                children: Vec::new(),
+            },
+        );
+        Self {
+            root,
+            boxes,
+            breakpoints: default_breakpoints(),
+            states: Vec::new(),
+            active_state: 0,
+            next_id: 1,
+        }
+    }
+
+    /// Narrowest breakpoint a container `width` wide falls in, `None` above all of them
+    pub fn breakpoint_at(&self, width: f32) -> Option<&Breakpoint> {
+        self.breakpoints
+            .iter()
+            .filter(|breakpoint| width <= breakpoint.max_width)
+            .min_by(|a, b| a.max_width.total_cmp(&b.max_width))
+    }
+
+    /// Style a box has at a container `width`: its override in the narrowest matching breakpoint that has one
+    pub fn style_at(&self, id: BoxId, width: f32) -> Option<&FlexStyle> {
+        let item = self.get(id)?;
+        let mut matching: Vec<&Breakpoint> =
+            self.breakpoints.iter().filter(|breakpoint| width <= breakpoint.max_width).collect();
+        matching.sort_by(|a, b| a.max_width.total_cmp(&b.max_width));
+        Some(
+            matching
+                .into_iter()
+                .find_map(|breakpoint| item.overrides.get(&breakpoint.name))
+                .unwrap_or(&item.style),
+        )
+    }
+
+    /// Style to edit for `breakpoint`, `None` being the base; a missing override starts as what the box shows there
+    pub fn style_mut(&mut self, id: BoxId, breakpoint: Option<&str>) -> Option<&mut FlexStyle> {
+        let Some(name) = breakpoint else {
+            return self.get_mut(id).map(|item| &mut item.style);
+        };
+        let width = self.breakpoints.iter().find(|breakpoint| breakpoint.name == name)?.max_width;
+        let current = self.style_at(id, width)?.clone();
+        let item = self.get_mut(id)?;
+        Some(item.overrides.entry(name.to_string()).or_insert(current))
+    }
+
+    pub fn get(&self, id: BoxId) -> Option<&FlexBox> {
+        self.boxes.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: BoxId) -> Option<&mut FlexBox> {
+        self.boxes.get_mut(&id)
+    }
+
+    pub fn parent(&self, id: BoxId) -> Option<BoxId> {
+        self.boxes.values().find(|item| item.children.contains(&id)).map(|item| item.id)
+    }
+
+    /// Appends a new box with default style to `parent`
+    pub fn add_child(&mut self, parent: BoxId, name: impl Into<String>) -> BoxId {
+        let id = BoxId(self.next_id);
+        self.next_id += 1;
+        self.boxes.insert(
+            id,
+            FlexBox {
+                id,
+                name: name.into(),
+                style: FlexStyle::default(),
+                overrides: BTreeMap::new(),
+                hidden: false,
+                motors: Vec::new(),
+                children: Vec::new(),
+            },
+        );
+        if let Some(parent) = self.boxes.get_mut(&parent) {
+            parent.children.push(id);
+        }
+        id
+    }
+
+    /// Removes a box and everything inside it; the root stays
+    pub fn remove(&mut self, id: BoxId) {
+        if id == self.root {
+            return;
+        }
+        if let Some(parent) = self.parent(id) {
+            if let Some(parent) = self.boxes.get_mut(&parent) {
+                parent.children.retain(|child| *child != id);
+            }
+        }
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(removed) = self.boxes.remove(&id) {
+                for state in &mut self.states {
+                    state.styles.remove(&id);
+                }
+                stack.extend(removed.children);
+            }
+        }
+    }
+
+    /// Whether `id` is `ancestor` or somewhere inside it
+    pub fn is_within(&self, id: BoxId, ancestor: BoxId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.parent(id);
+        }
+        false
+    }
+
+    /// Moves a box to `index` among `parent`'s children, `index` counted before the box leaves its old place
+    ///
+    /// Refuses to move the root, to move a box into itself, next to a sibling with the same name, or out of a
+    /// container whose constraints still refer to it.
+    pub fn move_box(&mut self, id: BoxId, parent: BoxId, index: usize) -> Result<(), String> {
+        if id == self.root {
+            return Err("the root box can't be moved".to_string());
+        }
+        if self.is_within(parent, id) {
+            return Err("a box can't move into itself".to_string());
+        }
+        let (Some(old_parent), Some(new_parent)) = (self.parent(id), self.get(parent)) else {
+            return Err("box no longer exists".to_string());
+        };
+        if old_parent != parent {
+            let name = self.get(id).map(|item| item.name.clone()).unwrap_or_default();
+            if self.has_child_named(parent, &name, id) {
+                return Err(format!("{} already holds a box called {name}", new_parent.name));
+            }
+            let referenced = self
+                .container_styles(old_parent)
+                .into_iter()
+                .flat_map(|style| &style.constraints)
+                .any(|constraint| mentions(&constraint.text, &name));
+            if referenced {
+                let old_name = self.get(old_parent).map_or("its parent", |item| item.name.as_str());
+                return Err(format!("constraints in {old_name} still refer to {name}"));
+            }
+        }
+        let mut index = index;
+        if let Some(old) = self.boxes.get_mut(&old_parent) {
+            let position = old.children.iter().position(|child| *child == id);
+            if old_parent == parent && position.is_some_and(|position| position < index) {
+                index -= 1;
+            }
+            old.children.retain(|child| *child != id);
+        }
+        if let Some(new) = self.boxes.get_mut(&parent) {
+            new.children.insert(index.min(new.children.len()), id);
+        }
+        Ok(())
+    }
+
+    /// Whether a child of `parent` other than `except` is called `name`
+    fn has_child_named(&self, parent: BoxId, name: &str, except: BoxId) -> bool {
+        self.get(parent).is_some_and(|item| {
+            item.children
+                .iter()
+                .any(|child| *child != except && self.get(*child).is_some_and(|child| child.name == name))
+        })
+    }
+
+    /// Styles of box `id` in every breakpoint and stored state
+    fn container_styles(&self, id: BoxId) -> Vec<&FlexStyle> {
+        let live = self.get(id).into_iter().flat_map(|item| std::iter::once(&item.style).chain(item.overrides.values()));
+        let stored = self
+            .states
+            .iter()
+            .filter_map(|state| state.styles.get(&id))
+            .flat_map(|styles| std::iter::once(&styles.style).chain(styles.overrides.values()));
+        live.chain(stored).collect()
+    }
+
+    /// Renames a box, the name may not be empty, `parent` or another child's of the same parent
+    ///
+    /// Constraints refer to boxes by name, so the parent's constraints follow the rename in every breakpoint and state.
+    pub fn rename(&mut self, id: BoxId, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("name can't be empty".to_string());
+        }
+        if name == "parent" {
+            return Err("parent means the container in constraints".to_string());
+        }
+        let old = self.get(id).ok_or_else(|| "box no longer exists".to_string())?.name.clone();
+        if old == name {
+            return Ok(());
+        }
+        let parent = self.parent(id);
+        if parent.is_some_and(|parent| self.has_child_named(parent, name, id)) {
+            return Err(format!("another box in this container is called {name}"));
+        }
+        if let Some(item) = self.get_mut(id) {
+            item.name = name.to_string();
+        }
+        let Some(parent) = parent else {
+            return Ok(());
+        };
+        let follow = |style: &mut FlexStyle| {
+            for constraint in &mut style.constraints {
+                constraint.text = rename_references(&constraint.text, &old, name);
+            }
+        };
+        if let Some(item) = self.get_mut(parent) {
+            follow(&mut item.style);
+            item.overrides.values_mut().for_each(follow);
+        }
+        for state in &mut self.states {
+            if let Some(styles) = state.styles.get_mut(&parent) {
+                follow(&mut styles.style);
+                styles.overrides.values_mut().for_each(follow);
+            }
+        }
+        Ok(())
+    }
+
+    /// Every box with its depth, parents before children in document order
+    pub fn walk(&self) -> Vec<(BoxId, usize)> {
+        let mut order = Vec::new();
+        let mut stack = vec![(self.root, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let Some(item) = self.boxes.get(&id) else {
+                continue;
+            };
+            order.push((id, depth));
+            stack.extend(item.children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+        order
+    }
+
+    /// The boxes' current properties as a state
+    fn capture_state(&self, name: String) -> LayoutState {
+        let styles = self
+            .boxes
+            .values()
+            .map(|item| {
+                let styles = BoxStyles {
+                    style: item.style.clone(),
+                    overrides: item.overrides.clone(),
+                    hidden: item.hidden,
+                };
+                (item.id, styles)
+            })
+            .collect();
+        LayoutState { name, styles }
+    }
+
+    /// Writes the boxes' properties back into the active state
+    fn store_state(&mut self) {
+        let Some(name) = self.states.get(self.active_state).map(|state| state.name.clone()) else {
+            return;
+        };
+        self.states[self.active_state] = self.capture_state(name);
+    }
+
+    /// Gives the boxes a state's properties, boxes added since it was stored keep theirs
+    fn apply_state(&mut self, index: usize) {
+        let Some(state) = self.states.get(index) else {
+            return;
+        };
+        for (id, styles) in &state.styles {
+            if let Some(item) = self.boxes.get_mut(id) {
+                item.style = styles.style.clone();
+                item.overrides = styles.overrides.clone();
+                item.hidden = styles.hidden;
+            }
+        }
+        self.active_state = index;
+    }
+
+    /// Adds a state starting as a copy of the current one and switches to it; the first call also keeps the current
+    /// properties as `state 1`
+    pub fn add_state(&mut self) -> usize {
+        if self.states.is_empty() {
+            self.states.push(self.capture_state("state 1".to_string()));
+            self.active_state = 0;
+        }
+        self.store_state();
+        let name = (1..)
+            .map(|n| format!("state {n}"))
+            .find(|name| self.states.iter().all(|state| &state.name != name))
+            .unwrap_or_default();
+        self.states.push(self.capture_state(name));
+        self.active_state = self.states.len() - 1;
+        self.active_state
+    }
+
+    /// Keeps edits in the active state and gives the boxes another one's properties
+    pub fn switch_state(&mut self, index: usize) -> bool {
+        if index == self.active_state || index >= self.states.len() {
+            return false;
+        }
+        self.store_state();
+        self.apply_state(index);
+        true
+    }
+
+    /// Drops the active state and switches to the one before it; the last state can't be removed
+    pub fn remove_state(&mut self) -> bool {
+        if self.states.len() < 2 {
+            return false;
+        }
+        self.states.remove(self.active_state);
+        self.apply_state(self.active_state.saturating_sub(1));
+        true
+    }
+
+    pub fn has_motors(&self) -> bool {
+        self.boxes.values().any(|item| !item.motors.is_empty())
+    }
+
+    /// Next free `box N` name
+    pub fn unique_name(&self) -> String {
+        (1..)
+            .map(|n| format!("box {n}"))
+            .find(|name| self.boxes.values().all(|item| &item.name != name))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+impl FlexDocument {
+    /// First box called `name`, tests find the default skeleton's boxes by their names
+    pub fn named(&self, name: &str) -> BoxId {
+        self.boxes.values().find(|item| item.name == name).map(|item| item.id).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint_texts(style: &FlexStyle) -> Vec<&str> {
+        style.constraints.iter().map(|constraint| constraint.text.as_str()).collect()
+    }
+
+    #[test]
+    fn rename_follows_into_the_parents_constraints() {
+        let mut document = FlexDocument::default();
+        let body = document.named("body");
+        let sidebar = document.named("sidebar");
+        let constraint = LayoutConstraint::parse("sidebar.width == content.width / 2").unwrap();
+        let item = document.get_mut(body).unwrap();
+        item.style.constraints.push(constraint.clone());
+        item.overrides.insert("tablet".to_string(), item.style.clone());
+        document.add_state();
+
+        document.rename(sidebar, "nav").unwrap();
+        let expected = ["nav.width == content.width / 2"];
+        let item = document.get(body).unwrap();
+        assert_eq!(constraint_texts(&item.style), expected);
+        assert_eq!(constraint_texts(&item.overrides["tablet"]), expected);
+        for state in &document.states {
+            assert_eq!(constraint_texts(&state.styles[&body].style), expected);
+        }
+    }
+
+    #[test]
+    fn rename_rejects_a_siblings_name() {
+        let mut document = FlexDocument::default();
+        let sidebar = document.named("sidebar");
+        assert!(document.rename(sidebar, "content").is_err());
+        assert!(document.rename(sidebar, " ").is_err());
+        assert!(document.rename(sidebar, "parent").is_err());
+        // Names only have to differ within a container
+        assert!(document.rename(sidebar, "header").is_ok());
+        assert_eq!(document.get(sidebar).unwrap().name, "header");
+    }
+
+    #[test]
+    fn boxes_are_within_themselves_and_their_ancestors() {
+        let document = FlexDocument::default();
+        let body = document.named("body");
+        let sidebar = document.named("sidebar");
+        assert!(document.is_within(sidebar, sidebar));
+        assert!(document.is_within(sidebar, body));
+        assert!(document.is_within(sidebar, document.root));
+        assert!(!document.is_within(body, sidebar));
+        assert!(!document.is_within(sidebar, document.named("header")));
+    }
+
+    #[test]
+    fn move_box_reorders_and_reparents() {
+        let mut document = FlexDocument::default();
+        let root = document.root;
+        let [header, body, footer, sidebar, content] =
+            ["header", "body", "footer", "sidebar", "content"].map(|name| document.named(name));
+
+        // Indices count the box's old place, so dropping after the next sibling lands past it
+        assert!(document.move_box(header, root, 2).is_ok());
+        assert_eq!(document.get(root).unwrap().children, [body, header, footer]);
+
+        assert!(document.move_box(footer, body, 1).is_ok());
+        assert_eq!(document.get(root).unwrap().children, [body, header]);
+        assert_eq!(document.get(body).unwrap().children, [sidebar, footer, content]);
+        assert_eq!(document.parent(footer), Some(body));
+
+        assert!(document.move_box(body, sidebar, 0).is_err());
+        assert!(document.move_box(body, body, 0).is_err());
+        assert!(document.move_box(root, body, 0).is_err());
+        assert!(document.move_box(sidebar, BoxId(99), 0).is_err());
+        assert_eq!(document.get(body).unwrap().children, [sidebar, footer, content]);
+
+        // Constraints find boxes by name, so names stay unique within a container
+        let other_header = document.add_child(body, "header");
+        assert!(document.move_box(other_header, root, 0).is_err());
+        assert!(document.move_box(other_header, body, 0).is_ok());
+
+        // Nor may a box leave a container whose constraints still name it
+        let constraint = LayoutConstraint::parse("sidebar.width == 200").unwrap();
+        document.get_mut(body).unwrap().style.constraints.push(constraint);
+        assert!(document.move_box(sidebar, root, 0).is_err());
+        assert!(document.move_box(content, root, 0).is_ok());
+    }
+}
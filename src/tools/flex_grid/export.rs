@@ -0,0 +1,422 @@
+//! Writes the box tree out as HTML and CSS a browser lays out like Flexer does, and state transitions as SVG frames
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use bevy::math::Rect;
+
+use crate::tools::flex_grid::document::{Align, BoxId, Direction, Edges, FlexDocument, FlexStyle, Justify, LayoutMode};
+use crate::tools::flex_grid::grid::{format_areas, format_tracks, GridLine};
+use crate::tools::flex_grid::layout::{compute_layout, root_size};
+use crate::tools::flex_grid::resources::{FlexLayout, LayoutTransitionSettings};
+use crate::tools::flex_grid::transition::transition_frames;
+
+/// Frames per second of exported transitions
+const FRAME_RATE: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlexExportFormat {
+    /// Standalone page with the styles inlined in a `<style>` block
+    #[default]
+    Html,
+    /// Just the rules, one class per box
+    Css,
+}
+
+impl FlexExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            FlexExportFormat::Html => "html",
+            FlexExportFormat::Css => "css",
+        }
+    }
+}
+
+/// Class a box is exported under, its name made CSS-safe and suffixed with the id so names may repeat
+pub fn class_name(document: &FlexDocument, id: BoxId) -> String {
+    let name = document.get(id).map_or("box", |item| item.name.as_str());
+    let mut class: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    if !class.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        class.insert_str(0, "box-");
+    }
+    format!("{class}-{}", id.0)
+}
+
+fn px(value: f32) -> String {
+    if value == 0.0 {
+        "0".to_string()
+    } else {
+        format!("{value}px")
+    }
+}
+
+fn edges(edges: &Edges) -> String {
+    format!("{} {} {} {}", px(edges.top), px(edges.right), px(edges.bottom), px(edges.left))
+}
+
+/// Declarations for one box; everything is written out so browser defaults never differ from taffy's
+///
+/// The root fills the page's width, so media queries see the container width Flexer lays it out at, and gets `root_height`.
+/// Children of a constraint box are pinned to `placed`, their solved rectangle relative to the parent's padding edge.
+fn declarations(
+    style: &FlexStyle,
+    parent: Option<&FlexStyle>,
+    root_height: Option<f32>,
+    placed: Option<Rect>,
+) -> Vec<(&'static str, String)> {
+    let display = match style.mode {
+        LayoutMode::Flex => "flex",
+        LayoutMode::Grid => "grid",
+        LayoutMode::Constraint => "block",
+    };
+    let mut out = vec![
+        ("display", display.to_string()),
+        // Taffy sizes boxes border-box
+        ("box-sizing", "border-box".to_string()),
+    ];
+    if style.mode == LayoutMode::Constraint {
+        out.push(("position", "relative".to_string()));
+    } else if style.mode == LayoutMode::Grid {
+        if !style.grid.columns.is_empty() {
+            out.push(("grid-template-columns", format_tracks(&style.grid.columns)));
+        }
+        if !style.grid.rows.is_empty() {
+            out.push(("grid-template-rows", format_tracks(&style.grid.rows)));
+        }
+        if !style.grid.areas.is_empty() {
+            out.push(("grid-template-areas", format_areas(&style.grid.areas)));
+        }
+    } else {
+        out.extend(flex_container(style));
+    }
+    out.extend([
+        (
+            "align-items",
+            match style.align {
+                Align::Stretch => "stretch",
+                Align::Start => "flex-start",
+                Align::End => "flex-end",
+                Align::Center => "center",
+            }
+            .to_string(),
+        ),
+        ("gap", px(style.gap)),
+    ]);
+    if let Some(rect) = placed {
+        out.extend([
+            ("position", "absolute".to_string()),
+            ("left", px(rect.min.x)),
+            ("top", px(rect.min.y)),
+            ("width", px(rect.width())),
+            ("height", px(rect.height())),
+            ("padding", edges(&style.padding)),
+            ("margin", "0".to_string()),
+        ]);
+        return out;
+    }
+    match parent {
+        Some(parent) if parent.mode == LayoutMode::Grid => out.extend(grid_item(style, parent)),
+        _ => out.extend([
+            ("flex-grow", style.grow.to_string()),
+            ("flex-shrink", style.shrink.to_string()),
+            ("flex-basis", style.basis.map_or("auto".to_string(), px)),
+        ]),
+    }
+    let (width, height) = match root_height {
+        Some(height) => (Some("100%".to_string()), Some(style.height.unwrap_or(height))),
+        None => (style.width.map(px), style.height),
+    };
+    if let Some(width) = width {
+        out.push(("width", width));
+    }
+    if let Some(height) = height {
+        out.push(("height", px(height)));
+    }
+    out.push(("padding", edges(&style.padding)));
+    out.push(("margin", edges(&style.margin)));
+    out
+}
+
+/// Placement in a grid parent; areas the parent doesn't define fall back to lines, as they do in Flexer
+fn grid_item(style: &FlexStyle, parent: &FlexStyle) -> Vec<(&'static str, String)> {
+    if let Some(area) = style.item.area.as_ref().filter(|name| parent.grid.area(name).is_some()) {
+        return vec![("grid-area", area.clone())];
+    }
+    let mut out = Vec::new();
+    if style.item.column.start != GridLine::Auto || style.item.column.end != GridLine::Auto {
+        out.push(("grid-column", style.item.column.to_string()));
+    }
+    if style.item.row.start != GridLine::Auto || style.item.row.end != GridLine::Auto {
+        out.push(("grid-row", style.item.row.to_string()));
+    }
+    out
+}
+
+fn flex_container(style: &FlexStyle) -> [(&'static str, String); 2] {
+    [
+        (
+            "flex-direction",
+            match style.direction {
+                Direction::Row => "row",
+                Direction::Column => "column",
+                Direction::RowReverse => "row-reverse",
+                Direction::ColumnReverse => "column-reverse",
+            }
+            .to_string(),
+        ),
+        (
+            "justify-content",
+            match style.justify {
+                Justify::Start => "flex-start",
+                Justify::End => "flex-end",
+                Justify::Center => "center",
+                Justify::SpaceBetween => "space-between",
+                Justify::SpaceAround => "space-around",
+                Justify::SpaceEvenly => "space-evenly",
+            }
+            .to_string(),
+        ),
+    ]
+}
+
+/// Declarations of a box in a container `width` wide, using the styles in effect there and `layout` computed at it
+fn declarations_at(document: &FlexDocument, layout: &FlexLayout, id: BoxId, width: f32) -> Vec<(&'static str, String)> {
+    let Some(style) = document.style_at(id, width) else {
+        return Vec::new();
+    };
+    let parent_id = document.parent(id);
+    let parent = parent_id.and_then(|parent| document.style_at(parent, width));
+    let root_height = (id == document.root).then(|| root_size(document).y);
+    // Solutions depend on the container size, so they go out as the pixels solved at this width
+    let placed = parent_id
+        .filter(|_| parent.is_some_and(|parent| parent.mode == LayoutMode::Constraint))
+        .and_then(|parent| Some((layout.rects.get(&id)?, layout.rects.get(&parent)?)))
+        .map(|(rect, parent)| Rect::from_corners(rect.min - parent.min, rect.max - parent.min));
+    let mut out = declarations(style, parent, root_height, placed);
+    if document.get(id).is_some_and(|item| item.hidden) {
+        out[0].1 = "none".to_string();
+    }
+    out
+}
+
+/// One rule per box, parents before children, then a `max-width` media query per breakpoint, widest first
+///
+/// A breakpoint only restates what differs from the next wider one, so the cascade rebuilds Flexer's styles.
+/// Children are compared too: a parent switching to grid changes how they are placed.
+pub fn export_css(document: &FlexDocument) -> String {
+    // Wide enough for the base styles to apply
+    let base_width = document
+        .breakpoints
+        .iter()
+        .fold(root_size(document).x, |width, breakpoint| width.max(breakpoint.max_width + 1.0));
+    let base = compute_layout(document, base_width);
+    let mut css = String::new();
+    for (id, _) in document.walk() {
+        let _ = writeln!(css, ".{} {{", class_name(document, id));
+        for (property, value) in declarations_at(document, &base, id, f32::INFINITY) {
+            let _ = writeln!(css, "  {property}: {value};");
+        }
+        css.push_str("}\n");
+    }
+
+    let mut breakpoints: Vec<_> = document.breakpoints.iter().collect();
+    breakpoints.sort_by(|a, b| b.max_width.total_cmp(&a.max_width));
+    let (mut wider, mut wider_layout) = (f32::INFINITY, base);
+    for breakpoint in breakpoints {
+        let layout = compute_layout(document, breakpoint.max_width);
+        let mut rules = String::new();
+        for (id, _) in document.walk() {
+            let before = declarations_at(document, &wider_layout, id, wider);
+            let after = declarations_at(document, &layout, id, breakpoint.max_width);
+            let mut changed: Vec<(&str, String)> =
+                after.iter().filter(|declaration| !before.contains(declaration)).cloned().collect();
+            // Properties the narrower style no longer sets go back to their initial value
+            changed.extend(
+                before
+                    .iter()
+                    .filter(|(property, _)| !after.iter().any(|(other, _)| other == property))
+                    .map(|(property, _)| (*property, "initial".to_string())),
+            );
+            if changed.is_empty() {
+                continue;
+            }
+            let _ = writeln!(rules, "  .{} {{", class_name(document, id));
+            for (property, value) in changed {
+                let _ = writeln!(rules, "    {property}: {value};");
+            }
+            rules.push_str("  }\n");
+        }
+        if !rules.is_empty() {
+            let _ = writeln!(css, "/* {} */\n@media (max-width: {}px) {{", breakpoint.name, breakpoint.max_width);
+            css.push_str(&rules);
+            css.push_str("}\n");
+        }
+        (wider, wider_layout) = (breakpoint.max_width, layout);
+    }
+    css
+}
+
+fn write_div(html: &mut String, document: &FlexDocument, id: BoxId, depth: usize) {
+    let Some(item) = document.get(id) else {
+        return;
+    };
+    let indent = "  ".repeat(depth + 1);
+    let class = class_name(document, id);
+    // Empty boxes stay empty: any text inside would change their content size
+    if item.children.is_empty() {
+        let _ = writeln!(html, "{indent}<div class=\"{class}\" title=\"{}\"></div>", escape(&item.name));
+        return;
+    }
+    let _ = writeln!(html, "{indent}<div class=\"{class}\" title=\"{}\">", escape(&item.name));
+    for child in &item.children {
+        write_div(html, document, *child, depth + 1);
+    }
+    let _ = writeln!(html, "{indent}</div>");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Self-contained page showing the layout, outlined like the editor draws it
+pub fn export_html(document: &FlexDocument) -> String {
+    let title = document.get(document.root).map_or("layout", |root| root.name.as_str());
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>{}</title>", escape(title));
+    html.push_str("<style>\n");
+    // No padding, the root's width is the viewport's like the container Flexer lays out
+    html.push_str("body { margin: 0; background: #111; }\n");
+    // Outlines take no space, so they don't disturb the layout
+    html.push_str("div { background: rgba(120, 150, 200, 0.12); outline: 1px solid #8c8c99; }\n");
+    html.push_str(&export_css(document));
+    html.push_str("</style>\n</head>\n<body>\n");
+    write_div(&mut html, document, document.root, 0);
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Writes the layout to `path`, creating missing folders
+pub fn save_flex_export(document: &FlexDocument, format: FlexExportFormat, path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let text = match format {
+        FlexExportFormat::Html => export_html(document),
+        FlexExportFormat::Css => export_css(document),
+    };
+    std::fs::write(path, text)
+}
+
+/// One frame of a transition: every box at its rectangle, nested boxes lighter like in the editor
+pub fn export_svg_frame(document: &FlexDocument, rects: &HashMap<BoxId, Rect>) -> String {
+    let size = rects.get(&document.root).map_or(root_size(document), |root| root.max);
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = size.x.ceil(),
+        h = size.y.ceil()
+    );
+    let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"#111\"/>");
+    for (id, depth) in document.walk() {
+        let (Some(rect), Some(item)) = (rects.get(&id), document.get(id)) else {
+            continue;
+        };
+        let lightness = 12.0 + 7.0 * depth.min(8) as f32;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"hsl(215, 25%, {lightness}%)\" stroke=\"#8c8c99\"/>",
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height()
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"12\" fill=\"white\">{}</text>",
+            rect.min.x + 4.0,
+            rect.min.y + 16.0,
+            escape(&item.name)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes the switch from state `from` to state `to` in a container `width` wide as numbered SVG files in `dir`,
+/// returning how many frames were written
+pub fn save_transition_frames(
+    document: &FlexDocument,
+    from: usize,
+    to: usize,
+    width: f32,
+    settings: &LayoutTransitionSettings,
+    dir: &Path,
+) -> std::io::Result<usize> {
+    let frames = transition_frames(document, from, to, width, settings, FRAME_RATE)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "no such layout state"))?;
+    std::fs::create_dir_all(dir)?;
+    for (index, rects) in frames.iter().enumerate() {
+        std::fs::write(dir.join(format!("frame_{index:04}.svg")), export_svg_frame(document, rects))?;
+    }
+    Ok(frames.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text of the media query a breakpoint was written as, if it has one
+    fn media_query<'a>(css: &'a str, breakpoint: &str) -> Option<&'a str> {
+        let start = css.find(&format!("/* {breakpoint} */"))?;
+        let block = &css[start..];
+        Some(&block[..block.find("\n}\n").unwrap_or(block.len())])
+    }
+
+    /// Declarations of `.class` in `css`, up to its closing brace
+    fn rule<'a>(css: &'a str, class: &str) -> Option<&'a str> {
+        let start = css.find(&format!(".{class} {{"))?;
+        let block = &css[start..];
+        Some(&block[..block.find('}').unwrap_or(block.len())])
+    }
+
+    #[test]
+    fn breakpoints_only_restate_what_changes() {
+        let mut document = FlexDocument::default();
+        let header = document.named("header");
+        let body = document.named("body");
+        let footer = document.named("footer");
+        document.style_mut(header, Some("tablet")).unwrap().height = Some(80.0);
+        document.style_mut(footer, Some("tablet")).unwrap().height = None;
+        document.style_mut(body, Some("phone")).unwrap().direction = Direction::Column;
+        let css = export_css(&document);
+        let (header, body, footer) =
+            (class_name(&document, header), class_name(&document, body), class_name(&document, footer));
+
+        let base = &css[..css.find("@media").unwrap()];
+        assert!(rule(base, &header).unwrap().contains("height: 56px;"));
+
+        let tablet = media_query(&css, "tablet").unwrap();
+        assert!(tablet.contains("@media (max-width: 1024px)"));
+        assert!(rule(tablet, &header).unwrap().contains("height: 80px;"));
+        // A property the override drops goes back to its initial value
+        assert!(rule(tablet, &footer).unwrap().contains("height: initial;"));
+        assert!(rule(tablet, &body).is_none());
+
+        // The phone inherits the tablet's header through the cascade
+        let phone = media_query(&css, "phone").unwrap();
+        assert!(rule(phone, &header).is_none());
+        assert!(rule(phone, &body).unwrap().contains("flex-direction: column;"));
+    }
+
+    #[test]
+    fn breakpoints_without_changes_write_no_media_query() {
+        let css = export_css(&FlexDocument::default());
+        assert!(!css.contains("@media"));
+    }
+}
@@ -0,0 +1,455 @@
+//! CSS Grid containers: track lists, named areas and item placement, read and written as CSS text
+
+use serde::{Deserialize, Serialize};
+
+use crate::tools::flex_grid::document::FlexStyle;
+
+/// One track breadth as written in CSS
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TrackSize {
+    Px(f32),
+    Percent(f32),
+    Fr(f32),
+    Auto,
+}
+
+impl TrackSize {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let number = |suffix: &str| text.strip_suffix(suffix).and_then(|number| number.trim().parse::<f32>().ok());
+        let size = if text == "auto" {
+            TrackSize::Auto
+        } else if let Some(px) = number("px") {
+            TrackSize::Px(px)
+        } else if let Some(percent) = number("%") {
+            TrackSize::Percent(percent)
+        } else if let Some(fr) = number("fr") {
+            TrackSize::Fr(fr)
+        } else if text.parse::<f32>().ok() == Some(0.0) {
+            TrackSize::Px(0.0)
+        } else {
+            return Err(format!("'{text}' is not a px, %, fr or auto size"));
+        };
+        match size {
+            TrackSize::Px(value) | TrackSize::Percent(value) | TrackSize::Fr(value) if value < 0.0 => {
+                Err(format!("'{text}' is negative"))
+            }
+            size => Ok(size),
+        }
+    }
+}
+
+impl std::fmt::Display for TrackSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackSize::Px(px) => write!(f, "{px}px"),
+            TrackSize::Percent(percent) => write!(f, "{percent}%"),
+            TrackSize::Fr(fr) => write!(f, "{fr}fr"),
+            TrackSize::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Track {
+    Size(TrackSize),
+    /// `minmax(min, max)`; the minimum can't be `fr`
+    MinMax(TrackSize, TrackSize),
+}
+
+impl std::fmt::Display for Track {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Track::Size(size) => write!(f, "{size}"),
+            Track::MinMax(min, max) => write!(f, "minmax({min}, {max})"),
+        }
+    }
+}
+
+/// Splits on whitespace outside parentheses
+fn split_tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let (mut depth, mut start) = (0usize, None);
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(begin) = start.take() {
+                    tokens.push(&text[begin..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(begin) = start {
+        tokens.push(&text[begin..]);
+    }
+    tokens
+}
+
+/// Track list like `200px 1fr minmax(100px, 2fr) auto`; `none` or an empty list means no explicit tracks
+pub fn parse_tracks(text: &str) -> Result<Vec<Track>, String> {
+    let text = text.trim();
+    if text.is_empty() || text == "none" {
+        return Ok(Vec::new());
+    }
+    split_tokens(text)
+        .into_iter()
+        .map(|token| {
+            let Some(arguments) = token.strip_prefix("minmax(").and_then(|rest| rest.strip_suffix(')')) else {
+                return TrackSize::parse(token).map(Track::Size);
+            };
+            let (min, max) = arguments
+                .split_once(',')
+                .ok_or_else(|| format!("'{token}' needs a minimum and a maximum"))?;
+            let min = TrackSize::parse(min)?;
+            if matches!(min, TrackSize::Fr(_)) {
+                return Err(format!("'{token}' can't have an fr minimum"));
+            }
+            Ok(Track::MinMax(min, TrackSize::parse(max)?))
+        })
+        .collect()
+}
+
+pub fn format_tracks(tracks: &[Track]) -> String {
+    if tracks.is_empty() {
+        return "none".to_string();
+    }
+    tracks.iter().map(Track::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// Start or end of an item's placement on one axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GridLine {
+    #[default]
+    Auto,
+    /// 1-based line number, negative counts from the end
+    Line(i16),
+    Span(u16),
+}
+
+impl GridLine {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text == "auto" {
+            return Ok(GridLine::Auto);
+        }
+        if let Some(count) = text.strip_prefix("span") {
+            return match count.trim().parse::<u16>() {
+                Ok(count) if count > 0 => Ok(GridLine::Span(count)),
+                _ => Err(format!("'{text}' needs a positive span")),
+            };
+        }
+        match text.parse::<i16>() {
+            Ok(line) if line != 0 => Ok(GridLine::Line(line)),
+            _ => Err(format!("'{text}' is not a line number, span or auto")),
+        }
+    }
+}
+
+impl std::fmt::Display for GridLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridLine::Auto => write!(f, "auto"),
+            GridLine::Line(line) => write!(f, "{line}"),
+            GridLine::Span(span) => write!(f, "span {span}"),
+        }
+    }
+}
+
+/// `grid-column` or `grid-row` of an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LinePlacement {
+    pub start: GridLine,
+    pub end: GridLine,
+}
+
+impl LinePlacement {
+    /// `start`, `start / end`, e.g. `2 / span 3`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text.split_once('/') {
+            Some((start, end)) => Ok(Self {
+                start: GridLine::parse(start)?,
+                end: GridLine::parse(end)?,
+            }),
+            None => Ok(Self {
+                start: GridLine::parse(text)?,
+                end: GridLine::Auto,
+            }),
+        }
+    }
+
+    /// Number of tracks covered when both ends are known, otherwise the span or 1
+    pub fn span(&self) -> u16 {
+        match (self.start, self.end) {
+            (GridLine::Line(start), GridLine::Line(end)) if start > 0 && end > start => (end - start) as u16,
+            (_, GridLine::Span(span)) | (GridLine::Span(span), _) => span,
+            _ => 1,
+        }
+    }
+
+    /// Placement starting at `line` keeping the current span
+    pub fn moved_to(&self, line: i16) -> Self {
+        let span = self.span();
+        Self {
+            start: GridLine::Line(line),
+            end: if span > 1 { GridLine::Span(span) } else { GridLine::Auto },
+        }
+    }
+}
+
+impl std::fmt::Display for LinePlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.end {
+            GridLine::Auto => write!(f, "{}", self.start),
+            end => write!(f, "{} / {end}", self.start),
+        }
+    }
+}
+
+/// Where an item sits in its parent grid; a named area wins over line placement
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct GridItem {
+    pub area: Option<String>,
+    pub column: LinePlacement,
+    pub row: LinePlacement,
+}
+
+/// Explicit tracks and named areas of a grid container
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GridTemplate {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    /// Area name per cell, row by row; `.` leaves a cell unnamed
+    pub areas: Vec<Vec<String>>,
+}
+
+/// Cells an area covers, as 1-based lines with exclusive ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AreaLines {
+    pub column_start: i16,
+    pub column_end: i16,
+    pub row_start: i16,
+    pub row_end: i16,
+}
+
+impl GridTemplate {
+    /// Template with `columns` equal fractions and a single row
+    pub fn even(columns: usize) -> Self {
+        Self {
+            columns: vec![Track::Size(TrackSize::Fr(1.0)); columns.max(1)],
+            rows: vec![Track::Size(TrackSize::Fr(1.0))],
+            areas: Vec::new(),
+        }
+    }
+
+    /// Area names in first-appearance order
+    pub fn area_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in self.areas.iter().flatten() {
+            if name != "." && !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    pub fn area(&self, name: &str) -> Option<AreaLines> {
+        let mut found: Option<AreaLines> = None;
+        for (row, cells) in self.areas.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if cell != name {
+                    continue;
+                }
+                let (column, row) = (column as i16 + 1, row as i16 + 1);
+                let lines = found.get_or_insert(AreaLines {
+                    column_start: column,
+                    column_end: column + 1,
+                    row_start: row,
+                    row_end: row + 1,
+                });
+                lines.column_start = lines.column_start.min(column);
+                lines.column_end = lines.column_end.max(column + 1);
+                lines.row_start = lines.row_start.min(row);
+                lines.row_end = lines.row_end.max(row + 1);
+            }
+        }
+        found
+    }
+
+    /// Named area covering the cell at 0-based `column` and `row`
+    pub fn area_at(&self, column: usize, row: usize) -> Option<&str> {
+        self.areas
+            .get(row)
+            .and_then(|cells| cells.get(column))
+            .map(String::as_str)
+            .filter(|name| *name != ".")
+    }
+}
+
+/// `grid-template-areas` as quoted rows, `"head head" "side main"`, or rows separated by `/`
+pub fn parse_areas(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.trim();
+    if text.is_empty() || text == "none" {
+        return Ok(Vec::new());
+    }
+    let rows: Vec<&str> = if text.contains('"') {
+        text.split('"').skip(1).step_by(2).collect()
+    } else {
+        text.split('/').collect()
+    };
+    let areas: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .filter(|cells| !cells.is_empty())
+        .collect();
+    let width = areas.first().map_or(0, Vec::len);
+    if areas.iter().any(|cells| cells.len() != width) {
+        return Err("every area row needs the same number of cells".to_string());
+    }
+
+    // Each name has to fill exactly the rectangle it spans
+    let template = GridTemplate {
+        areas,
+        ..Default::default()
+    };
+    for name in template.area_names() {
+        let Some(lines) = template.area(name) else {
+            continue;
+        };
+        for row in lines.row_start..lines.row_end {
+            for column in lines.column_start..lines.column_end {
+                if template.area_at(column as usize - 1, row as usize - 1) != Some(name) {
+                    return Err(format!("area '{name}' is not a rectangle"));
+                }
+            }
+        }
+    }
+    Ok(template.areas)
+}
+
+pub fn format_areas(areas: &[Vec<String>]) -> String {
+    if areas.is_empty() {
+        return "none".to_string();
+    }
+    areas
+        .iter()
+        .map(|cells| format!("\"{}\"", cells.join(" ")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Grid property edited as CSS text in the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridField {
+    Columns,
+    Rows,
+    Areas,
+    /// Item properties, for children of a grid
+    Area,
+    Column,
+    Row,
+}
+
+impl GridField {
+    pub const CONTAINER: [GridField; 3] = [GridField::Columns, GridField::Rows, GridField::Areas];
+    pub const ITEM: [GridField; 3] = [GridField::Area, GridField::Column, GridField::Row];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GridField::Columns => "columns",
+            GridField::Rows => "rows",
+            GridField::Areas => "areas",
+            GridField::Area => "area",
+            GridField::Column => "column",
+            GridField::Row => "row",
+        }
+    }
+
+    pub fn get(self, style: &FlexStyle) -> String {
+        match self {
+            GridField::Columns => format_tracks(&style.grid.columns),
+            GridField::Rows => format_tracks(&style.grid.rows),
+            GridField::Areas => format_areas(&style.grid.areas),
+            GridField::Area => style.item.area.clone().unwrap_or_else(|| "none".to_string()),
+            GridField::Column => style.item.column.to_string(),
+            GridField::Row => style.item.row.to_string(),
+        }
+    }
+
+    /// Parses `text` into the property, leaving the style untouched on error
+    pub fn set(self, style: &mut FlexStyle, text: &str) -> Result<(), String> {
+        match self {
+            GridField::Columns => style.grid.columns = parse_tracks(text)?,
+            GridField::Rows => style.grid.rows = parse_tracks(text)?,
+            GridField::Areas => style.grid.areas = parse_areas(text)?,
+            GridField::Area => {
+                let name = text.trim();
+                style.item.area = (!name.is_empty() && name != "none" && name != "auto").then(|| name.to_string());
+            }
+            GridField::Column => style.item.column = LinePlacement::parse(text)?,
+            GridField::Row => style.item.row = LinePlacement::parse(text)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_track_lists() {
+        assert_eq!(
+            parse_tracks("200px 1fr minmax(100px, 2fr) auto 25%"),
+            Ok(vec![
+                Track::Size(TrackSize::Px(200.0)),
+                Track::Size(TrackSize::Fr(1.0)),
+                Track::MinMax(TrackSize::Px(100.0), TrackSize::Fr(2.0)),
+                Track::Size(TrackSize::Auto),
+                Track::Size(TrackSize::Percent(25.0)),
+            ])
+        );
+        assert_eq!(parse_tracks("none"), Ok(Vec::new()));
+        assert_eq!(parse_tracks("0"), Ok(vec![Track::Size(TrackSize::Px(0.0))]));
+    }
+
+    #[test]
+    fn rejects_bad_tracks() {
+        assert!(parse_tracks("minmax(1fr, 2fr)").is_err());
+        assert!(parse_tracks("minmax(100px)").is_err());
+        assert!(parse_tracks("-10px").is_err());
+        assert!(parse_tracks("12em").is_err());
+    }
+
+    #[test]
+    fn tracks_round_trip_through_text() {
+        let text = "200px 1fr minmax(100px, 2fr) auto";
+        assert_eq!(format_tracks(&parse_tracks(text).unwrap()), text);
+    }
+
+    #[test]
+    fn parses_areas_quoted_or_slashed() {
+        let quoted = parse_areas("\"head head\" \"side main\"").unwrap();
+        assert_eq!(quoted, parse_areas("head head / side main").unwrap());
+        let template = GridTemplate { areas: quoted, ..Default::default() };
+        assert_eq!(template.area_names(), vec!["head", "side", "main"]);
+        assert_eq!(
+            template.area("head"),
+            Some(AreaLines { column_start: 1, column_end: 3, row_start: 1, row_end: 2 })
+        );
+        assert_eq!(template.area_at(1, 1), Some("main"));
+    }
+
+    #[test]
+    fn rejects_bad_areas() {
+        assert!(parse_areas("\"a b\" \"c\"").is_err());
+        assert!(parse_areas("\"a b\" \"b a\"").is_err());
+        assert!(parse_areas("\"a .\" \". a\"").is_err());
+        // Unnamed cells may sit anywhere
+        assert!(parse_areas("\". a\" \". a\"").is_ok());
+    }
+}
@@ -0,0 +1,644 @@
+//! Builds a box tree from nested divs styled with a flexbox and grid subset of CSS
+//!
+//! Styles come from `.class` rules in `<style>` blocks and from inline `style` attributes, applied in
+//! that order. Anything outside the subset is skipped and reported as a warning.
+
+use std::collections::HashMap;
+
+use crate::tools::flex_grid::document::{Align, BoxId, Direction, Edges, FlexDocument, FlexStyle, Justify, LayoutMode};
+use crate::tools::flex_grid::grid::{parse_areas, parse_tracks, LinePlacement};
+use crate::tools::flex_grid::layout::root_size;
+
+/// Pixels per `em`/`rem`, the browser default font size
+const EM: f32 = 16.0;
+
+#[derive(Debug)]
+pub enum FlexImportError {
+    Io(std::io::Error),
+    /// No `<div>` to build a tree from
+    NoBoxes,
+}
+
+impl std::fmt::Display for FlexImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlexImportError::Io(err) => write!(f, "could not read layout: {err}"),
+            FlexImportError::NoBoxes => write!(f, "no <div> elements found"),
+        }
+    }
+}
+
+impl std::error::Error for FlexImportError {}
+
+#[derive(Debug)]
+pub struct FlexImport {
+    pub document: FlexDocument,
+    /// Everything that was skipped or approximated, in source order
+    pub warnings: Vec<String>,
+}
+
+/// Size as written in the source, resolved once the parent is known
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Length {
+    Px(f32),
+    Percent(f32),
+    Fr(f32),
+    Auto,
+}
+
+fn parse_length(value: &str) -> Option<Length> {
+    let value = value.trim();
+    let number = |suffix: &str| value.strip_suffix(suffix).and_then(|number| number.trim().parse::<f32>().ok());
+    if value == "auto" {
+        Some(Length::Auto)
+    } else if let Some(px) = number("px") {
+        Some(Length::Px(px))
+    } else if let Some(rem) = number("rem").or_else(|| number("em")) {
+        Some(Length::Px(rem * EM))
+    } else if let Some(percent) = number("%") {
+        Some(Length::Percent(percent))
+    } else if let Some(fr) = number("fr") {
+        Some(Length::Fr(fr))
+    } else {
+        // Unitless lengths are only valid as zero
+        value.parse::<f32>().ok().filter(|number| *number == 0.0).map(Length::Px)
+    }
+}
+
+/// Container a box is placed in, what percentages and `fr` resolve against
+#[derive(Debug, Clone, Copy)]
+struct ParentInfo {
+    /// Content box size, `None` on axes without a fixed size
+    width: Option<f32>,
+    height: Option<f32>,
+    row: bool,
+}
+
+impl ParentInfo {
+    fn of(style: &FlexStyle, size: (Option<f32>, Option<f32>)) -> Self {
+        let padding = style.padding;
+        Self {
+            width: size.0.map(|width| (width - padding.left - padding.right).max(0.0)),
+            height: size.1.map(|height| (height - padding.top - padding.bottom).max(0.0)),
+            row: style.direction.is_row(),
+        }
+    }
+}
+
+/// Applies declarations to one box
+struct StyleBuilder<'a> {
+    style: FlexStyle,
+    parent: Option<ParentInfo>,
+    warnings: &'a mut Vec<String>,
+    name: &'a str,
+}
+
+impl StyleBuilder<'_> {
+    fn warn(&mut self, message: String) {
+        self.warnings.push(format!("{}: {message}", self.name));
+    }
+
+    /// Pixel value of a length along one axis; `None` is auto
+    fn resolve(&mut self, property: &str, value: &str, horizontal: bool) -> Option<f32> {
+        match parse_length(value) {
+            Some(Length::Px(px)) => Some(px),
+            Some(Length::Auto) => None,
+            Some(Length::Percent(percent)) => {
+                let base = self.parent.and_then(|parent| if horizontal { parent.width } else { parent.height });
+                if base.is_none() {
+                    self.warn(format!("{property}: {value} ignored, the parent has no fixed size"));
+                }
+                base.map(|base| base * percent / 100.0)
+            }
+            Some(Length::Fr(_)) => {
+                self.warn(format!("{property}: {value} ignored, fr only works as a width or height along the parent's main axis"));
+                None
+            }
+            None => {
+                self.warn(format!("{property}: unsupported value '{value}'"));
+                None
+            }
+        }
+    }
+
+    /// Width or height; an `fr` size along the parent's main axis becomes a share of the free space
+    fn size(&mut self, property: &str, value: &str, horizontal: bool) -> Option<f32> {
+        if let (Some(Length::Fr(fr)), Some(parent)) = (parse_length(value), self.parent) {
+            if parent.row == horizontal {
+                self.style.grow = fr.max(0.0);
+                self.style.basis = Some(0.0);
+                return None;
+            }
+        }
+        self.resolve(property, value, horizontal)
+    }
+
+    /// Padding and margin both resolve percentages against the parent's width, like CSS
+    fn edges(&mut self, property: &str, value: &str) -> Edges {
+        let values: Vec<f32> = value
+            .split_whitespace()
+            .map(|part| {
+                if part == "auto" {
+                    self.warn(format!("{property}: auto treated as 0"));
+                    return 0.0;
+                }
+                self.resolve(property, part, true).unwrap_or(0.0)
+            })
+            .collect();
+        let (top, right, bottom, left) = match values.as_slice() {
+            [all] => (*all, *all, *all, *all),
+            [vertical, horizontal] => (*vertical, *horizontal, *vertical, *horizontal),
+            [top, horizontal, bottom] => (*top, *horizontal, *bottom, *horizontal),
+            [top, right, bottom, left] => (*top, *right, *bottom, *left),
+            _ => {
+                self.warn(format!("{property}: unsupported value '{value}'"));
+                (0.0, 0.0, 0.0, 0.0)
+            }
+        };
+        Edges { top, right, bottom, left }
+    }
+
+    fn number(&mut self, property: &str, value: &str) -> Option<f32> {
+        let number = value.trim().parse::<f32>().ok().filter(|number| *number >= 0.0);
+        if number.is_none() {
+            self.warn(format!("{property}: unsupported value '{value}'"));
+        }
+        number
+    }
+
+    /// `flex` shorthand: `none`, `auto`, `initial`, or grow, shrink and basis in any valid combination
+    fn flex(&mut self, value: &str) {
+        let (grow, shrink, basis) = match value.trim() {
+            "none" => (0.0, 0.0, None),
+            "auto" => (1.0, 1.0, None),
+            "initial" => (0.0, 1.0, None),
+            value => {
+                let parts: Vec<&str> = value.split_whitespace().collect();
+                let numbers: Vec<f32> = parts.iter().map_while(|part| part.parse::<f32>().ok()).collect();
+                let basis = match parts.get(numbers.len()) {
+                    Some(basis) => self.size("flex-basis", basis, self.parent.is_none_or(|parent| parent.row)),
+                    // A bare number means a zero basis
+                    None => Some(0.0),
+                };
+                match numbers.as_slice() {
+                    [grow] => (*grow, 1.0, basis),
+                    [grow, shrink] => (*grow, *shrink, basis),
+                    [] if parts.len() == 1 => (1.0, 1.0, basis),
+                    _ => {
+                        self.warn(format!("flex: unsupported value '{value}'"));
+                        return;
+                    }
+                }
+            }
+        };
+        self.style.grow = grow.max(0.0);
+        self.style.shrink = shrink.max(0.0);
+        self.style.basis = basis;
+    }
+
+    /// An area name, or `row-start / column-start / row-end / column-end` lines
+    fn grid_area(&mut self, value: &str) {
+        let parts: Vec<&str> = value.split('/').map(str::trim).collect();
+        if let [name] = parts.as_slice() {
+            if name.parse::<i16>().is_err() && !name.starts_with("span") {
+                self.style.item.area = (*name != "auto").then(|| name.to_string());
+                return;
+            }
+        }
+        let line = |index: usize| parts.get(index).copied().unwrap_or("auto");
+        let row = LinePlacement::parse(&format!("{} / {}", line(0), line(2)));
+        let column = LinePlacement::parse(&format!("{} / {}", line(1), line(3)));
+        match (row, column) {
+            (Ok(row), Ok(column)) => {
+                self.style.item.row = row;
+                self.style.item.column = column;
+            }
+            (Err(err), _) | (_, Err(err)) => self.warn(format!("grid-area: {err}")),
+        }
+    }
+
+    fn apply(&mut self, property: &str, value: &str) {
+        let horizontal_main = self.parent.is_none_or(|parent| parent.row);
+        match property {
+            "display" => match value {
+                "flex" => self.style.mode = LayoutMode::Flex,
+                "grid" => self.style.mode = LayoutMode::Grid,
+                _ => self.warn(format!("display: {value} treated as flex")),
+            },
+            "grid-template-columns" | "grid-template-rows" => match parse_tracks(value) {
+                Ok(tracks) if property.ends_with("columns") => self.style.grid.columns = tracks,
+                Ok(tracks) => self.style.grid.rows = tracks,
+                Err(err) => self.warn(format!("{property}: {err}")),
+            },
+            "grid-template-areas" => match parse_areas(value) {
+                Ok(areas) => self.style.grid.areas = areas,
+                Err(err) => self.warn(format!("{property}: {err}")),
+            },
+            "grid-column" | "grid-row" => match LinePlacement::parse(value) {
+                Ok(placement) if property == "grid-column" => self.style.item.column = placement,
+                Ok(placement) => self.style.item.row = placement,
+                Err(err) => self.warn(format!("{property}: {err}")),
+            },
+            "grid-area" => self.grid_area(value),
+            "box-sizing" => {
+                if value != "border-box" {
+                    self.warn(format!("box-sizing: {value} treated as border-box"));
+                }
+            }
+            "flex-direction" => match value {
+                "row" => self.style.direction = Direction::Row,
+                "column" => self.style.direction = Direction::Column,
+                "row-reverse" => self.style.direction = Direction::RowReverse,
+                "column-reverse" => self.style.direction = Direction::ColumnReverse,
+                _ => self.warn(format!("flex-direction: unsupported value '{value}'")),
+            },
+            "justify-content" => match value {
+                "flex-start" | "start" | "left" | "normal" => self.style.justify = Justify::Start,
+                "flex-end" | "end" | "right" => self.style.justify = Justify::End,
+                "center" => self.style.justify = Justify::Center,
+                "space-between" => self.style.justify = Justify::SpaceBetween,
+                "space-around" => self.style.justify = Justify::SpaceAround,
+                "space-evenly" => self.style.justify = Justify::SpaceEvenly,
+                _ => self.warn(format!("justify-content: unsupported value '{value}'")),
+            },
+            "align-items" => match value {
+                "stretch" | "normal" => self.style.align = Align::Stretch,
+                "flex-start" | "start" => self.style.align = Align::Start,
+                "flex-end" | "end" => self.style.align = Align::End,
+                "center" => self.style.align = Align::Center,
+                _ => self.warn(format!("align-items: unsupported value '{value}'")),
+            },
+            "gap" | "row-gap" | "column-gap" | "grid-gap" => {
+                let mut parts = value.split_whitespace();
+                let first = parts.next().unwrap_or_default();
+                if parts.next().is_some_and(|second| second != first) {
+                    self.warn(format!("{property}: different row and column gaps, using {first}"));
+                }
+                self.style.gap = self.resolve(property, first, self.style.direction.is_row()).unwrap_or(0.0);
+            }
+            "flex" => self.flex(value),
+            "flex-grow" => self.style.grow = self.number(property, value).unwrap_or(self.style.grow),
+            "flex-shrink" => self.style.shrink = self.number(property, value).unwrap_or(self.style.shrink),
+            "flex-basis" => {
+                let basis = match value {
+                    "content" | "auto" => None,
+                    value => self.size(property, value, horizontal_main),
+                };
+                // An fr basis already wrote grow and a zero basis
+                if !matches!(parse_length(value), Some(Length::Fr(_))) {
+                    self.style.basis = basis;
+                }
+            }
+            "width" => self.style.width = self.size(property, value, true),
+            "height" => self.style.height = self.size(property, value, false),
+            "padding" => self.style.padding = self.edges(property, value),
+            "margin" => self.style.margin = self.edges(property, value),
+            "padding-top" | "padding-right" | "padding-bottom" | "padding-left" | "margin-top" | "margin-right"
+            | "margin-bottom" | "margin-left" => {
+                let px = self.edges(property, value).top;
+                let (group, side) = property.split_once('-').unwrap_or_default();
+                let edges = if group == "padding" { &mut self.style.padding } else { &mut self.style.margin };
+                match side {
+                    "top" => edges.top = px,
+                    "right" => edges.right = px,
+                    "bottom" => edges.bottom = px,
+                    _ => edges.left = px,
+                }
+            }
+            _ => self.warn(format!("unsupported property '{property}'")),
+        }
+    }
+}
+
+/// `property: value` pairs of a declaration block, lowercased, with `!important` dropped
+fn declarations(block: &str) -> Vec<(String, String)> {
+    block
+        .split(';')
+        .filter_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let value = value.trim().trim_end_matches("!important").trim();
+            Some((property.trim().to_ascii_lowercase(), value.to_ascii_lowercase()))
+        })
+        .filter(|(property, value)| !property.is_empty() && !value.is_empty())
+        .collect()
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut out = String::new();
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = rest[start + 2..].split_once("*/").map_or("", |(_, after)| after);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Byte index of the `}` closing the block that `css` starts inside of, or the end when it never closes
+fn block_end(css: &str) -> usize {
+    let mut depth = 0usize;
+    for (index, c) in css.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return index,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    css.len()
+}
+
+/// Declarations per class from `.class { ... }` rules, in source order; other selectors are warned about
+///
+/// At-rule blocks like `@media` are skipped whole, the rules inside only apply under conditions the import can't
+/// check.
+fn parse_stylesheet(css: &str, warnings: &mut Vec<String>) -> HashMap<String, Vec<(String, String)>> {
+    let mut rules: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    let css = strip_comments(css);
+    let mut rest = css.as_str();
+    while let Some((prelude, after)) = rest.split_once('{') {
+        let end = block_end(after);
+        let block = &after[..end];
+        rest = after.get(end + 1..).unwrap_or("");
+        // Statement at-rules like `@import url(...);` end before the next block starts
+        let mut statements: Vec<&str> = prelude.split(';').map(str::trim).collect();
+        let selectors = statements.pop().unwrap_or_default();
+        for statement in statements.into_iter().filter(|statement| !statement.is_empty()) {
+            warnings.push(format!("'{statement}' skipped"));
+        }
+        if selectors.starts_with('@') {
+            warnings.push(format!("'{selectors}' block skipped, only base styles are imported"));
+            continue;
+        }
+        for selector in selectors.split(',').map(str::trim) {
+            let simple = selector.strip_prefix('.').filter(|class| {
+                !class.is_empty() && class.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            });
+            match simple {
+                Some(class) => rules.entry(class.to_string()).or_default().extend(declarations(block)),
+                None => warnings.push(format!("selector '{selector}' skipped, only single class selectors are supported")),
+            }
+        }
+    }
+    rules
+}
+
+/// Element tag with its attributes, or a closing tag
+#[derive(Debug)]
+struct Tag<'a> {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: Vec<(String, &'a str)>,
+}
+
+fn parse_attributes(mut source: &str) -> Vec<(String, &str)> {
+    let mut attributes = Vec::new();
+    loop {
+        source = source.trim_start();
+        let end = source.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(source.len());
+        if end == 0 {
+            break;
+        }
+        let name = source[..end].to_ascii_lowercase();
+        source = source[end..].trim_start();
+        let Some(value_source) = source.strip_prefix('=') else {
+            attributes.push((name, ""));
+            continue;
+        };
+        let value_source = value_source.trim_start();
+        let (value, after) = match value_source.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let inner = &value_source[1..];
+                inner.split_once(quote).unwrap_or((inner, ""))
+            }
+            _ => {
+                let end = value_source.find(char::is_whitespace).unwrap_or(value_source.len());
+                (&value_source[..end], &value_source[end..])
+            }
+        };
+        attributes.push((name, value));
+        source = after;
+    }
+    attributes
+}
+
+fn parse_tag(source: &str) -> Tag<'_> {
+    let closing = source.starts_with('/');
+    let source = source.trim_start_matches('/');
+    let self_closing = source.ends_with('/');
+    let source = source.trim_end_matches('/');
+    let end = source.find(char::is_whitespace).unwrap_or(source.len());
+    Tag {
+        name: source[..end].to_ascii_lowercase(),
+        closing,
+        self_closing,
+        attributes: parse_attributes(&source[end..]),
+    }
+}
+
+/// Position in the tree while walking the tags
+struct OpenBox {
+    id: BoxId,
+    info: ParentInfo,
+}
+
+/// Builds a document from HTML; several top-level divs are wrapped in a new root
+pub fn import_html(source: &str) -> Result<FlexImport, FlexImportError> {
+    let mut warnings = Vec::new();
+
+    // Gather every stylesheet first so rules apply no matter where the <style> sits
+    let mut css = String::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("<style") {
+        let Some((_, after)) = rest[start..].split_once('>') else {
+            break;
+        };
+        let (sheet, after) = after.split_once("</style>").unwrap_or((after, ""));
+        css.push_str(sheet);
+        css.push('\n');
+        rest = after;
+    }
+    let rules = parse_stylesheet(&css, &mut warnings);
+
+    // Placeholder root, replaced by the single top-level div when there is one
+    let mut document = FlexDocument::new("page");
+    let page = document.root;
+    if let Some(root) = document.get_mut(page) {
+        root.style = FlexStyle::initial();
+    }
+    let mut stack: Vec<OpenBox> = Vec::new();
+    let mut top_level = Vec::new();
+    let mut skipped_tags: Vec<String> = Vec::new();
+
+    let mut rest = source;
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        // Comments and raw-text elements hold nothing to lay out
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = comment.split_once("-->").map_or("", |(_, after)| after);
+            continue;
+        }
+        let Some((tag_source, after)) = after.split_once('>') else {
+            break;
+        };
+        rest = after;
+        let tag = parse_tag(tag_source.trim());
+        if tag.name == "style" || tag.name == "script" {
+            if !tag.closing {
+                rest = rest.split_once(&format!("</{}>", tag.name)).map_or("", |(_, after)| after);
+            }
+            continue;
+        }
+        if tag.name != "div" {
+            let name = tag.name.trim_start_matches('!').to_string();
+            if !matches!(name.as_str(), "doctype" | "html" | "head" | "body" | "meta" | "title" | "link")
+                && !skipped_tags.contains(&name)
+            {
+                warnings.push(format!("<{name}> elements skipped, their children are kept"));
+                skipped_tags.push(name);
+            }
+            continue;
+        }
+        if tag.closing {
+            if stack.pop().is_none() {
+                warnings.push("unmatched </div> ignored".to_string());
+            }
+            continue;
+        }
+
+        let attribute = |name: &str| tag.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| *value);
+        let classes: Vec<&str> = attribute("class").map(|class| class.split_whitespace().collect()).unwrap_or_default();
+        let name = attribute("title")
+            .or_else(|| attribute("id"))
+            .or_else(|| classes.first().copied())
+            .map(str::to_string)
+            .unwrap_or_else(|| document.unique_name());
+
+        let parent = stack.last().map_or(page, |open| open.id);
+        let id = document.add_child(parent, name.clone());
+        if stack.is_empty() {
+            top_level.push(id);
+        }
+        let mut builder = StyleBuilder {
+            style: FlexStyle::initial(),
+            parent: stack.last().map(|open| open.info),
+            warnings: &mut warnings,
+            name: &name,
+        };
+        for class in &classes {
+            for (property, value) in rules.get(*class).into_iter().flatten() {
+                builder.apply(property, value);
+            }
+        }
+        for (property, value) in declarations(attribute("style").unwrap_or_default()) {
+            builder.apply(&property, &value);
+        }
+        let style = builder.style;
+        let info = ParentInfo::of(&style, (style.width, style.height));
+        if let Some(item) = document.get_mut(id) {
+            item.style = style;
+        }
+        if !tag.self_closing {
+            stack.push(OpenBox { id, info });
+        }
+    }
+    if !stack.is_empty() {
+        warnings.push(format!("{} unclosed <div> closed at the end", stack.len()));
+    }
+
+    match top_level.as_slice() {
+        [] => Err(FlexImportError::NoBoxes),
+        [single] => Ok(FlexImport {
+            document: reroot(&document, *single),
+            warnings,
+        }),
+        _ => {
+            // The wrapper lays its divs out like a page body would
+            let size = root_size(&document);
+            if let Some(root) = document.get_mut(page) {
+                root.style.direction = Direction::Column;
+                root.style.width = Some(size.x);
+            }
+            Ok(FlexImport { document, warnings })
+        }
+    }
+}
+
+/// Copy of the subtree under `id` with `id` as root
+fn reroot(source: &FlexDocument, id: BoxId) -> FlexDocument {
+    let Some(root) = source.get(id) else {
+        return source.clone();
+    };
+    let mut document = FlexDocument::new(root.name.clone());
+    let new_root = document.root;
+    if let Some(item) = document.get_mut(new_root) {
+        item.style = root.style.clone();
+    }
+    let mut stack = vec![(id, new_root)];
+    while let Some((from, to)) = stack.pop() {
+        let Some(item) = source.get(from) else {
+            continue;
+        };
+        for child in &item.children {
+            let Some(child_item) = source.get(*child) else {
+                continue;
+            };
+            let copy = document.add_child(to, child_item.name.clone());
+            if let Some(copied) = document.get_mut(copy) {
+                copied.style = child_item.style.clone();
+            }
+            stack.push((*child, copy));
+        }
+    }
+    document
+}
+
+/// Reads and imports an HTML file
+pub fn load_flex_import(path: &std::path::Path) -> Result<FlexImport, FlexImportError> {
+    let source = std::fs::read_to_string(path).map_err(FlexImportError::Io)?;
+    import_html(&source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::flex_grid::export::export_html;
+
+    fn styles_by_name(document: &FlexDocument) -> Vec<(String, FlexStyle)> {
+        document
+            .walk()
+            .into_iter()
+            .filter_map(|(id, _)| document.get(id))
+            .map(|item| (item.name.clone(), item.style.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn breakpoint_overrides_stay_out_of_base_styles() {
+        let plain = FlexDocument::default();
+        let mut document = plain.clone();
+        let body = document.named("body");
+        let header = document.named("header");
+        document.style_mut(body, Some("phone")).unwrap().direction = Direction::Column;
+        document.style_mut(header, Some("phone")).unwrap().height = Some(80.0);
+
+        let html = export_html(&document);
+        let expected = import_html(&export_html(&plain)).unwrap();
+        let imported = import_html(&html).unwrap();
+
+        assert_eq!(styles_by_name(&imported.document), styles_by_name(&expected.document));
+        // One warning per media query, none for the rules inside them
+        let media = imported.warnings.iter().filter(|warning| warning.contains("@media")).count();
+        assert!(media > 0);
+        assert_eq!(media, html.matches("@media").count(), "{:?}", imported.warnings);
+    }
+
+    #[test]
+    fn statement_at_rules_do_not_swallow_the_next_rule() {
+        let mut warnings = Vec::new();
+        let rules = parse_stylesheet("@import url(a.css);\n.a { flex-grow: 2; }", &mut warnings);
+        assert_eq!(rules["a"], vec![("flex-grow".to_string(), "2".to_string())]);
+        assert_eq!(warnings.len(), 1);
+    }
+}
@@ -0,0 +1,416 @@
+//! Lays the box tree out with taffy, the same flex engine bevy_ui uses
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use taffy::style_helpers::{line, span};
+use taffy::{
+    AvailableSpace, Dimension, LengthPercentage, LengthPercentageAuto, MaxTrackSizingFunction, MinTrackSizingFunction,
+    NodeId, TaffyTree, TrackSizingFunction,
+};
+
+use crate::tools::flex_grid::constraint::{solve_constraints, ConstrainedChild};
+use crate::tools::flex_grid::document::{Align, BoxId, Direction, Edges, FlexDocument, FlexStyle, Justify, LayoutMode};
+use crate::tools::flex_grid::grid::{GridLine, Track, TrackSize};
+use crate::tools::flex_grid::resources::{FlexLayout, GridTracks};
+
+/// Size used for a root without a fixed width or height
+const FALLBACK_ROOT_SIZE: Vec2 = Vec2::new(640.0, 420.0);
+/// Size a child of a constraint box takes when neither its style nor a constraint sets one
+const FALLBACK_CONSTRAINED_SIZE: Vec2 = Vec2::new(120.0, 80.0);
+
+fn dimension(value: Option<f32>) -> Dimension {
+    value.map_or(Dimension::Auto, Dimension::Length)
+}
+
+fn padding(edges: &Edges) -> taffy::Rect<LengthPercentage> {
+    taffy::Rect {
+        left: LengthPercentage::Length(edges.left),
+        right: LengthPercentage::Length(edges.right),
+        top: LengthPercentage::Length(edges.top),
+        bottom: LengthPercentage::Length(edges.bottom),
+    }
+}
+
+fn margin(edges: &Edges) -> taffy::Rect<LengthPercentageAuto> {
+    taffy::Rect {
+        left: LengthPercentageAuto::Length(edges.left),
+        right: LengthPercentageAuto::Length(edges.right),
+        top: LengthPercentageAuto::Length(edges.top),
+        bottom: LengthPercentageAuto::Length(edges.bottom),
+    }
+}
+
+fn min_track(size: TrackSize) -> MinTrackSizingFunction {
+    match size {
+        TrackSize::Px(px) => MinTrackSizingFunction::Fixed(LengthPercentage::Length(px)),
+        TrackSize::Percent(percent) => MinTrackSizingFunction::Fixed(LengthPercentage::Percent(percent / 100.0)),
+        TrackSize::Fr(_) | TrackSize::Auto => MinTrackSizingFunction::Auto,
+    }
+}
+
+fn max_track(size: TrackSize) -> MaxTrackSizingFunction {
+    match size {
+        TrackSize::Px(px) => MaxTrackSizingFunction::Fixed(LengthPercentage::Length(px)),
+        TrackSize::Percent(percent) => MaxTrackSizingFunction::Fixed(LengthPercentage::Percent(percent / 100.0)),
+        TrackSize::Fr(fr) => MaxTrackSizingFunction::Fraction(fr),
+        TrackSize::Auto => MaxTrackSizingFunction::Auto,
+    }
+}
+
+fn track(track: &Track) -> TrackSizingFunction {
+    // A plain size is minmax(size, size), except fr whose minimum is auto like in CSS
+    let (min, max) = match *track {
+        Track::Size(size) => (size, size),
+        Track::MinMax(min, max) => (min, max),
+    };
+    TrackSizingFunction::Single(taffy::MinMax {
+        min: min_track(min),
+        max: max_track(max),
+    })
+}
+
+fn placement(value: GridLine) -> taffy::GridPlacement {
+    match value {
+        GridLine::Auto => taffy::GridPlacement::Auto,
+        GridLine::Line(index) => line(index),
+        GridLine::Span(count) => span(count),
+    }
+}
+
+/// Item placement in `parent`'s grid, named areas resolved to lines
+fn grid_item(style: &FlexStyle, parent: &FlexStyle) -> (taffy::Line<taffy::GridPlacement>, taffy::Line<taffy::GridPlacement>) {
+    if let Some(area) = style.item.area.as_deref().and_then(|name| parent.grid.area(name)) {
+        return (
+            taffy::Line {
+                start: line(area.column_start),
+                end: line(area.column_end),
+            },
+            taffy::Line {
+                start: line(area.row_start),
+                end: line(area.row_end),
+            },
+        );
+    }
+    let (column, row) = (style.item.column, style.item.row);
+    (
+        taffy::Line {
+            start: placement(column.start),
+            end: placement(column.end),
+        },
+        taffy::Line {
+            start: placement(row.start),
+            end: placement(row.end),
+        },
+    )
+}
+
+pub fn taffy_style(style: &FlexStyle, parent: Option<&FlexStyle>) -> taffy::Style {
+    let grid = style.mode == LayoutMode::Grid;
+    let (grid_column, grid_row) = match parent {
+        Some(parent) if parent.mode == LayoutMode::Grid => grid_item(style, parent),
+        _ => Default::default(),
+    };
+    // Placed by the solver once the parent's size is known, see `place_constrained`
+    let constrained = parent.is_some_and(|parent| parent.mode == LayoutMode::Constraint);
+    taffy::Style {
+        display: if grid { taffy::Display::Grid } else { taffy::Display::Flex },
+        flex_direction: match style.direction {
+            Direction::Row => taffy::FlexDirection::Row,
+            Direction::Column => taffy::FlexDirection::Column,
+            Direction::RowReverse => taffy::FlexDirection::RowReverse,
+            Direction::ColumnReverse => taffy::FlexDirection::ColumnReverse,
+        },
+        // Grid tracks keep CSS's default content distribution, justify only applies to flex
+        justify_content: (!grid).then_some(match style.justify {
+            Justify::Start => taffy::JustifyContent::FlexStart,
+            Justify::End => taffy::JustifyContent::FlexEnd,
+            Justify::Center => taffy::JustifyContent::Center,
+            Justify::SpaceBetween => taffy::JustifyContent::SpaceBetween,
+            Justify::SpaceAround => taffy::JustifyContent::SpaceAround,
+            Justify::SpaceEvenly => taffy::JustifyContent::SpaceEvenly,
+        }),
+        align_items: Some(match style.align {
+            Align::Stretch => taffy::AlignItems::Stretch,
+            Align::Start => taffy::AlignItems::FlexStart,
+            Align::End => taffy::AlignItems::FlexEnd,
+            Align::Center => taffy::AlignItems::Center,
+        }),
+        gap: taffy::Size {
+            width: LengthPercentage::Length(style.gap),
+            height: LengthPercentage::Length(style.gap),
+        },
+        flex_grow: style.grow,
+        flex_shrink: style.shrink,
+        flex_basis: dimension(style.basis),
+        size: taffy::Size {
+            width: dimension(style.width),
+            height: dimension(style.height),
+        },
+        padding: padding(&style.padding),
+        margin: if constrained { taffy::Rect::zero() } else { margin(&style.margin) },
+        position: if constrained { taffy::Position::Absolute } else { taffy::Position::Relative },
+        grid_template_columns: if grid { style.grid.columns.iter().map(track).collect() } else { Vec::new() },
+        grid_template_rows: if grid { style.grid.rows.iter().map(track).collect() } else { Vec::new() },
+        grid_column,
+        grid_row,
+        ..default()
+    }
+}
+
+fn build(
+    tree: &mut TaffyTree,
+    document: &FlexDocument,
+    id: BoxId,
+    parent: Option<&FlexStyle>,
+    width: f32,
+    nodes: &mut Vec<(BoxId, NodeId)>,
+) -> Option<NodeId> {
+    let item = document.get(id)?;
+    let style = document.style_at(id, width)?;
+    let children: Vec<NodeId> = item
+        .children
+        .iter()
+        .filter_map(|child| build(tree, document, *child, Some(style), width, nodes))
+        .collect();
+    let mut taffy_style = taffy_style(style, parent);
+    if item.hidden {
+        taffy_style.display = taffy::Display::None;
+    }
+    let node = tree.new_with_children(taffy_style, &children).ok()?;
+    nodes.push((id, node));
+    Some(node)
+}
+
+/// Size the root is laid out at by default, its own width being the widest, base layout
+pub fn root_size(document: &FlexDocument) -> Vec2 {
+    let style = document.get(document.root).map(|root| &root.style);
+    Vec2::new(
+        style.and_then(|style| style.width).unwrap_or(FALLBACK_ROOT_SIZE.x),
+        style.and_then(|style| style.height).unwrap_or(FALLBACK_ROOT_SIZE.y),
+    )
+}
+
+/// Track spans along one axis, starting at `origin` and walking the gutters between tracks
+fn track_spans(sizes: &[f32], gutters: &[f32], origin: f32) -> Vec<(f32, f32)> {
+    let mut position = origin + gutters.first().copied().unwrap_or(0.0);
+    sizes
+        .iter()
+        .enumerate()
+        .map(|(index, size)| {
+            let span = (position, position + size);
+            position = span.1 + gutters.get(index + 1).copied().unwrap_or(0.0);
+            span
+        })
+        .collect()
+}
+
+/// Solves the constraints of box `id` in its laid out size and pins its children where they land
+fn place_constrained(
+    tree: &mut TaffyTree,
+    document: &FlexDocument,
+    id: BoxId,
+    width: f32,
+    node_of: &HashMap<BoxId, NodeId>,
+    computed: &mut FlexLayout,
+) -> Option<()> {
+    let (item, style) = (document.get(id)?, document.style_at(id, width)?);
+    let size = tree.layout(*node_of.get(&id)?).ok()?.size;
+    let padding = &style.padding;
+    let content = Vec2::new(
+        size.width - padding.left - padding.right,
+        size.height - padding.top - padding.bottom,
+    )
+    .max(Vec2::ZERO);
+    let children: Vec<ConstrainedChild> = item
+        .children
+        .iter()
+        .filter_map(|child| {
+            let (child_item, child_style) = (document.get(*child)?, document.style_at(*child, width)?);
+            if child_item.hidden {
+                return None;
+            }
+            Some(ConstrainedChild {
+                id: *child,
+                name: &child_item.name,
+                preferred: Vec2::new(
+                    child_style.width.unwrap_or(FALLBACK_CONSTRAINED_SIZE.x),
+                    child_style.height.unwrap_or(FALLBACK_CONSTRAINED_SIZE.y),
+                ),
+            })
+        })
+        .collect();
+    let (rects, report) = solve_constraints(&children, &style.constraints, content);
+    for (child, rect) in rects {
+        let Some(node) = node_of.get(&child) else {
+            continue;
+        };
+        let Ok(mut child_style) = tree.style(*node).cloned() else {
+            continue;
+        };
+        // Absolute insets start at the padding edge, the solution at the content edge
+        child_style.inset = taffy::Rect {
+            left: LengthPercentageAuto::Length(padding.left + rect.min.x),
+            top: LengthPercentageAuto::Length(padding.top + rect.min.y),
+            right: LengthPercentageAuto::Auto,
+            bottom: LengthPercentageAuto::Auto,
+        };
+        child_style.size = taffy::Size {
+            width: Dimension::Length(rect.width()),
+            height: Dimension::Length(rect.height()),
+        };
+        let _ = tree.set_style(*node, child_style);
+    }
+    computed.constraints.insert(id, report);
+    Some(())
+}
+
+/// Box rectangles relative to the root's top-left corner, y pointing down, plus the tracks of grid boxes
+///
+/// The root is made `width` wide and every box uses its style for that width.
+pub fn compute_layout(document: &FlexDocument, width: f32) -> FlexLayout {
+    let mut tree = TaffyTree::new();
+    let mut nodes = Vec::new();
+    let height = document.style_at(document.root, width).and_then(|style| style.height);
+    let size = Vec2::new(width, height.unwrap_or(root_size(document).y));
+    let mut computed = FlexLayout {
+        root: size,
+        ..default()
+    };
+    let Some(root) = build(&mut tree, document, document.root, None, width, &mut nodes) else {
+        return computed;
+    };
+    // The container width replaces whatever width the root itself asks for
+    if let Ok(mut style) = tree.style(root).cloned() {
+        style.size.width = Dimension::Length(width);
+        let _ = tree.set_style(root, style);
+    }
+    let available = taffy::Size {
+        width: AvailableSpace::Definite(size.x),
+        height: AvailableSpace::Definite(size.y),
+    };
+    if let Err(err) = tree.compute_layout(root, available) {
+        log::warn!("Flex layout failed: {err}");
+        return computed;
+    }
+    let node_of: HashMap<BoxId, NodeId> = nodes.into_iter().collect();
+
+    // Constraint boxes are solved parents first, each needing its own final size
+    for (id, _) in document.walk() {
+        if document.style_at(id, width).is_none_or(|style| style.mode != LayoutMode::Constraint) {
+            continue;
+        }
+        if place_constrained(&mut tree, document, id, width, &node_of, &mut computed).is_none() {
+            continue;
+        }
+        if let Err(err) = tree.compute_layout(root, available) {
+            log::warn!("Flex layout failed: {err}");
+            return computed;
+        }
+    }
+
+    // Taffy locations are relative to the parent, walk down accumulating offsets
+    let mut stack = vec![(document.root, Vec2::ZERO)];
+    while let Some((id, offset)) = stack.pop() {
+        let (Some(item), Some(layout)) = (document.get(id), node_of.get(&id).and_then(|node| tree.layout(*node).ok()))
+        else {
+            continue;
+        };
+        // Hidden boxes have no rectangle, nor does anything inside them
+        if item.hidden {
+            continue;
+        }
+        let min = offset + Vec2::new(layout.location.x, layout.location.y);
+        let rect = Rect::from_corners(min, min + Vec2::new(layout.size.width, layout.size.height));
+        computed.rects.insert(id, rect);
+        if let Some(taffy::DetailedLayoutInfo::Grid(info)) = node_of.get(&id).map(|node| tree.detailed_layout_info(*node)) {
+            let padding = &document.style_at(id, width).unwrap_or(&item.style).padding;
+            let content = Rect::new(
+                rect.min.x + padding.left,
+                rect.min.y + padding.top,
+                rect.max.x - padding.right,
+                rect.max.y - padding.bottom,
+            );
+            computed.grids.insert(
+                id,
+                GridTracks {
+                    columns: track_spans(&info.columns.sizes, &info.columns.gutters, content.min.x),
+                    rows: track_spans(&info.rows.sizes, &info.rows.gutters, content.min.y),
+                    leading_columns: info.columns.negative_implicit_tracks as usize,
+                    leading_rows: info.rows.negative_implicit_tracks as usize,
+                    content,
+                },
+            );
+        }
+        stack.extend(item.children.iter().map(|child| (*child, min)));
+    }
+    computed
+}
+
+/// World position the root is centered on, right of the property panel
+pub const VIEW_CENTER: Vec2 = Vec2::new(140.0, -20.0);
+
+/// Layout point (root top-left origin, y down) in world space
+pub fn to_world(point: Vec2, root_size: Vec2) -> Vec2 {
+    VIEW_CENTER + Vec2::new(point.x - root_size.x * 0.5, root_size.y * 0.5 - point.y)
+}
+
+/// Inverse of [`to_world`]
+pub fn from_world(point: Vec2, root_size: Vec2) -> Vec2 {
+    let local = point - VIEW_CENTER;
+    Vec2::new(local.x + root_size.x * 0.5, root_size.y * 0.5 - local.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles_map_onto_taffy() {
+        let style = FlexStyle {
+            direction: Direction::Column,
+            justify: Justify::SpaceBetween,
+            align: Align::Center,
+            gap: 12.0,
+            basis: Some(50.0),
+            ..default()
+        };
+        let mapped = taffy_style(&style, None);
+        assert_eq!(mapped.display, taffy::Display::Flex);
+        assert_eq!(mapped.flex_direction, taffy::FlexDirection::Column);
+        assert_eq!(mapped.justify_content, Some(taffy::JustifyContent::SpaceBetween));
+        assert_eq!(mapped.align_items, Some(taffy::AlignItems::Center));
+        assert_eq!(mapped.gap.width, LengthPercentage::Length(12.0));
+        assert_eq!(mapped.flex_basis, Dimension::Length(50.0));
+        assert_eq!(mapped.size.width, Dimension::Auto);
+    }
+
+    #[test]
+    fn grid_and_constraint_parents_change_the_mapping() {
+        let grid = FlexStyle { mode: LayoutMode::Grid, ..default() };
+        assert_eq!(taffy_style(&grid, None).display, taffy::Display::Grid);
+        assert_eq!(taffy_style(&grid, None).justify_content, None);
+
+        let constraint = FlexStyle { mode: LayoutMode::Constraint, ..default() };
+        let child = FlexStyle { margin: Edges::all(10.0), ..default() };
+        let placed = taffy_style(&child, Some(&constraint));
+        assert_eq!(placed.position, taffy::Position::Absolute);
+        assert_eq!(placed.margin, taffy::Rect::zero());
+    }
+
+    #[test]
+    fn grow_shares_the_free_space() {
+        let mut document = FlexDocument::new("root");
+        let root = document.root;
+        document.get_mut(root).unwrap().style = FlexStyle { height: Some(100.0), ..FlexStyle::initial() };
+        let a = document.add_child(root, "a");
+        let b = document.add_child(root, "b");
+        document.get_mut(a).unwrap().style = FlexStyle { grow: 1.0, ..FlexStyle::initial() };
+        document.get_mut(b).unwrap().style = FlexStyle { grow: 3.0, ..FlexStyle::initial() };
+
+        let layout = compute_layout(&document, 400.0);
+        assert_eq!(layout.root, Vec2::new(400.0, 100.0));
+        assert_eq!(layout.rects[&a], Rect::new(0.0, 0.0, 100.0, 100.0));
+        assert_eq!(layout.rects[&b], Rect::new(100.0, 0.0, 400.0, 100.0));
+    }
+}
@@ -0,0 +1,125 @@
+//! Motors from `tools::motors` swinging layout properties, to watch a layout reflow under continuous change
+
+use std::f64::consts::TAU;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::flex_grid::document::{FlexDocument, FlexProp};
+use crate::tools::motors::{Motor, Waveform};
+
+/// Properties motors can be added to from the panel
+pub const MOTOR_PROPS: [FlexProp; 5] = [FlexProp::Grow, FlexProp::Gap, FlexProp::Padding, FlexProp::Width, FlexProp::Height];
+
+const WAVEFORMS: [Waveform; 4] = [Waveform::Sine, Waveform::Triangle, Waveform::Square, Waveform::Saw];
+
+/// Motor swinging one property of a box around the value it is edited at; `amp` is in the property's units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutMotor {
+    pub prop: FlexProp,
+    pub motor: Motor,
+}
+
+impl LayoutMotor {
+    /// Swings a few panel steps either way
+    pub fn new(prop: FlexProp) -> Self {
+        Self {
+            prop,
+            motor: Motor {
+                amp: prop.step() * 4.0,
+                ..default()
+            },
+        }
+    }
+
+    pub fn next_waveform(&mut self) {
+        let current = WAVEFORMS.iter().position(|waveform| *waveform == self.motor.waveform);
+        self.motor.waveform = WAVEFORMS[current.map_or(0, |index| (index + 1) % WAVEFORMS.len())];
+    }
+}
+
+/// Motor setting changed with the panel's -/+ buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotorParam {
+    /// Shown and edited in Hz like the grid inspector, while [`Motor::freq`] is stored in radians per second
+    Freq,
+    Amp,
+    /// Offset in cycles
+    Phase,
+}
+
+impl MotorParam {
+    pub const ALL: [MotorParam; 3] = [MotorParam::Freq, MotorParam::Amp, MotorParam::Phase];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MotorParam::Freq => "freq Hz",
+            MotorParam::Amp => "amp",
+            MotorParam::Phase => "phase",
+        }
+    }
+
+    /// Amount the -/+ buttons change the setting by, amplitude steps follow the property
+    pub fn step(self, motor: &LayoutMotor) -> f32 {
+        match self {
+            MotorParam::Freq => 0.05,
+            MotorParam::Amp => motor.prop.step(),
+            MotorParam::Phase => 0.125,
+        }
+    }
+
+    pub fn get(self, motor: &LayoutMotor) -> f32 {
+        match self {
+            MotorParam::Freq => (motor.motor.freq / TAU) as f32,
+            MotorParam::Amp => motor.motor.amp,
+            MotorParam::Phase => motor.motor.phase,
+        }
+    }
+
+    /// Frequency and amplitude stay non-negative, the phase wraps into one cycle
+    pub fn set(self, motor: &mut LayoutMotor, value: f32) {
+        match self {
+            MotorParam::Freq => motor.motor.freq = f64::from(value.max(0.0)) * TAU,
+            MotorParam::Amp => motor.motor.amp = value.max(0.0),
+            MotorParam::Phase => motor.motor.phase = value.rem_euclid(1.0),
+        }
+    }
+}
+
+/// Copy of the document with every motor applied at transport time `t`, in a container `width` wide
+///
+/// Motors on one property add up, and an `auto` size has nothing to swing around so it stays `auto`.
+/// `None` when no box has motors.
+pub fn driven_document(document: &FlexDocument, width: f32, t: f32) -> Option<FlexDocument> {
+    if !document.has_motors() {
+        return None;
+    }
+    let mut driven = document.clone();
+    let breakpoint = document.breakpoint_at(width).map(|breakpoint| breakpoint.name.clone());
+    for item in document.boxes.values() {
+        for motor in &item.motors {
+            let Some(value) = driven.style_at(item.id, width).and_then(|style| motor.prop.get(style)) else {
+                continue;
+            };
+            if let Some(style) = driven.style_mut(item.id, breakpoint.as_deref()) {
+                motor.prop.set(style, Some(value + motor.motor.value(t)));
+            }
+        }
+    }
+    Some(driven)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequency_is_edited_in_hz() {
+        let mut motor = LayoutMotor::new(FlexProp::Gap);
+        MotorParam::Freq.set(&mut motor, 0.5);
+        assert_eq!(motor.motor.freq, 0.5 * TAU);
+        assert_eq!(MotorParam::Freq.get(&motor), 0.5);
+        MotorParam::Freq.set(&mut motor, -1.0);
+        assert_eq!(motor.motor.freq, 0.0);
+    }
+}
@@ -1,10 +1,365 @@
 use bevy::prelude::*;
-use crate::{GameState, tools::flex_grid::events::BackButtonPressed};
+use bevy::sprite::Anchor;
+use bevy::ui::RelativeCursorPosition;
 
-pub fn back_button_pressed_observer(
-    trigger: Trigger<BackButtonPressed>,
-    mut next_state: ResMut<NextState<GameState>>,
+use crate::systems::loading::FontAssets;
+use crate::tools::flex_grid::components::{
+    FlexBoxView, FlexGridEntity, FlexSplitter, OutlineButton, OutlineRow, SplitterReadout, ViewportEdge,
+};
+use crate::tools::flex_grid::document::{BoxId, FlexDocument, LayoutMode};
+use crate::tools::flex_grid::grid::{GridLine, LinePlacement};
+use crate::tools::flex_grid::layout::from_world;
+use crate::tools::flex_grid::resources::{
+    DropPlace, FlexLayout, FlexSelection, FlexTextEntry, FlexViewport, GridDrag, OutlineDrag, OutlineState, SplitterDrag,
+    TextField, ViewportDrag,
+};
+use crate::tools::flex_grid::split::ActiveSplit;
+
+/// Readout position relative to the handle
+const READOUT_OFFSET: Vec3 = Vec3::new(10.0, 10.0, 1.0);
+/// Narrowest container the edge can be dragged to
+const MIN_VIEWPORT_WIDTH: f32 = 240.0;
+const MAX_VIEWPORT_WIDTH: f32 = 2560.0;
+
+/// World units per screen pixel, the editor camera zooms out to fit wide containers
+fn camera_scale(cameras: &Query<&Projection, With<Camera2d>>) -> f32 {
+    match cameras.single() {
+        Ok(Projection::Orthographic(projection)) => projection.scale,
+        _ => 1.0,
+    }
+}
+
+pub fn select_box_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    views: Query<&FlexBoxView>,
+    mut selection: ResMut<FlexSelection>,
+) {
+    let Ok(view) = views.get(trigger.target()) else {
+        return;
+    };
+    selection.selected = Some(view.0);
+}
+
+pub fn start_splitter_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    splitters: Query<(&FlexSplitter, &Transform)>,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    mut drag: ResMut<SplitterDrag>,
+) {
+    let Ok((splitter, transform)) = splitters.get(trigger.target()) else {
+        return;
+    };
+    let (Some(a), Some(b)) = (layout.rects.get(&splitter.before), layout.rects.get(&splitter.after)) else {
+        return;
+    };
+    let main = |rect: &Rect| if splitter.row { rect.width() } else { rect.height() };
+    drag.active = ActiveSplit::new(&document, layout.root.x, splitter.before, splitter.after, main(a), main(a) + main(b));
+    commands.spawn((
+        Text2d::new(""),
+        TextFont {
+            font: fonts.geist_regular.clone(),
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Anchor::BottomLeft,
+        Transform::from_translation(transform.translation + READOUT_OFFSET),
+        SplitterReadout,
+        FlexGridEntity,
+    ));
+}
+
+/// Redistributes the pair while the handle moves, pointer distance is in screen pixels with y down like the layout
+pub fn drag_splitter(
+    trigger: Trigger<Pointer<Drag>>,
+    splitters: Query<(&FlexSplitter, &Transform)>,
+    cameras: Query<&Projection, With<Camera2d>>,
+    mut document: ResMut<FlexDocument>,
+    drag: Res<SplitterDrag>,
+    mut readouts: Query<(&mut Text2d, &mut Transform), (With<SplitterReadout>, Without<FlexSplitter>)>,
+) {
+    let (Ok((splitter, handle)), Some(active)) = (splitters.get(trigger.target()), drag.active.as_ref()) else {
+        return;
+    };
+    let distance = trigger.event().distance * camera_scale(&cameras);
+    let text = active.apply(&mut document, if splitter.row { distance.x } else { distance.y });
+    for (mut readout, mut transform) in readouts.iter_mut() {
+        readout.0 = text.clone();
+        transform.translation = handle.translation + READOUT_OFFSET;
+    }
+}
+
+pub fn end_splitter_drag(
+    trigger: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    splitters: Query<(), With<FlexSplitter>>,
+    mut drag: ResMut<SplitterDrag>,
+    readouts: Query<(Entity, &Text2d), With<SplitterReadout>>,
+) {
+    if !splitters.contains(trigger.target()) {
+        return;
+    }
+    drag.active = None;
+    for (entity, readout) in readouts.iter() {
+        if !readout.0.is_empty() {
+            log::info!("Split set to {}", readout.0);
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Parent of `id` when it lays its children out as a grid at the container `width`
+fn grid_parent(document: &FlexDocument, id: BoxId, width: f32) -> Option<BoxId> {
+    document.parent(id).filter(|parent| {
+        document
+            .style_at(*parent, width)
+            .is_some_and(|parent| parent.mode == LayoutMode::Grid)
+    })
+}
+
+pub fn start_grid_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    views: Query<&FlexBoxView>,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    mut drag: ResMut<GridDrag>,
+) {
+    let Ok(view) = views.get(trigger.target()) else {
+        return;
+    };
+    if grid_parent(&document, view.0, layout.root.x).is_some() {
+        drag.item = Some(view.0);
+        drag.target = None;
+    }
+}
+
+/// Tracks the parent cell under the pointer while a grid item is dragged
+pub fn drag_grid_item(
+    trigger: Trigger<Pointer<Drag>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    mut drag: ResMut<GridDrag>,
+) {
+    let Some(item) = drag.item else {
+        return;
+    };
+    let (Some(parent), Ok((camera, camera_transform))) = (grid_parent(&document, item, layout.root.x), cameras.single()) else {
+        return;
+    };
+    let Ok(world) = camera.viewport_to_world_2d(camera_transform, trigger.event().pointer_location.position) else {
+        return;
+    };
+    let point = from_world(world, layout.root);
+    let target = layout.grids.get(&parent).and_then(|tracks| tracks.cell_at(point));
+    if drag.target != target {
+        drag.target = target;
+    }
+}
+
+/// Drops the item into the named area under the pointer, or onto the cell keeping its span
+pub fn end_grid_drag(
+    _trigger: Trigger<Pointer<DragEnd>>,
+    mut document: ResMut<FlexDocument>,
+    layout: Res<FlexLayout>,
+    mut drag: ResMut<GridDrag>,
+) {
+    let (Some(item), target) = (drag.item.take(), drag.target.take()) else {
+        return;
+    };
+    let width = layout.root.x;
+    let (Some((column, row)), Some(parent)) = (target, grid_parent(&document, item, width)) else {
+        return;
+    };
+    let Some(template) = document.style_at(parent, width).map(|parent| parent.grid.clone()) else {
+        return;
+    };
+    let name = document.get(item).map_or(String::new(), |item| item.name.clone());
+    let breakpoint = document.breakpoint_at(width).map(|breakpoint| breakpoint.name.clone());
+    let Some(style) = document.style_mut(item, breakpoint.as_deref()) else {
+        return;
+    };
+    let area = usize::try_from(column - 1)
+        .ok()
+        .zip(usize::try_from(row - 1).ok())
+        .and_then(|(column, row)| template.area_at(column, row));
+    if let Some(area) = area {
+        style.item.area = Some(area.to_string());
+        log::info!("Moved {name} into area {area}");
+        return;
+    }
+    // Leaving an area keeps the size it had there
+    let (columns, rows) = match style.item.area.take().and_then(|name| template.area(&name)) {
+        Some(lines) => (
+            LinePlacement {
+                start: GridLine::Line(lines.column_start),
+                end: GridLine::Line(lines.column_end),
+            },
+            LinePlacement {
+                start: GridLine::Line(lines.row_start),
+                end: GridLine::Line(lines.row_end),
+            },
+        ),
+        None => (style.item.column, style.item.row),
+    };
+    style.item.column = columns.moved_to(column);
+    style.item.row = rows.moved_to(row);
+    log::info!("Moved {name} to column {column}, row {row}");
+}
+
+pub fn start_viewport_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    edges: Query<(), With<ViewportEdge>>,
+    cameras: Query<&Projection, With<Camera2d>>,
+    layout: Res<FlexLayout>,
+    mut drag: ResMut<ViewportDrag>,
+) {
+    if edges.contains(trigger.target()) {
+        drag.active = Some((layout.root.x, camera_scale(&cameras)));
+    }
+}
+
+/// Resizes the container with its right edge; the root stays centred, so the width changes twice as fast as the edge moves
+pub fn drag_viewport_edge(
+    trigger: Trigger<Pointer<Drag>>,
+    edges: Query<(), With<ViewportEdge>>,
+    drag: Res<ViewportDrag>,
+    mut viewport: ResMut<FlexViewport>,
+) {
+    let (true, Some((start, scale))) = (edges.contains(trigger.target()), drag.active) else {
+        return;
+    };
+    let width = (start + trigger.event().distance.x * scale * 2.0).clamp(MIN_VIEWPORT_WIDTH, MAX_VIEWPORT_WIDTH);
+    viewport.width = Some(width.round());
+}
+
+pub fn end_viewport_drag(
+    trigger: Trigger<Pointer<DragEnd>>,
+    edges: Query<(), With<ViewportEdge>>,
+    mut drag: ResMut<ViewportDrag>,
+    viewport: Res<FlexViewport>,
+) {
+    if !edges.contains(trigger.target()) {
+        return;
+    }
+    drag.active = None;
+    if let Some(width) = viewport.width {
+        log::info!("Container width set to {width}px");
+    }
+}
+
+/// Outline buttons act on their box; clicking a row selects its box, clicking the selected row again renames it
+pub fn click_outline(
+    mut trigger: Trigger<Pointer<Click>>,
+    rows: Query<&OutlineRow>,
+    buttons: Query<&OutlineButton>,
+    mut document: ResMut<FlexDocument>,
+    mut outline: ResMut<OutlineState>,
+    mut selection: ResMut<FlexSelection>,
+    mut entry: ResMut<FlexTextEntry>,
+) {
+    if let Ok(button) = buttons.get(trigger.target()) {
+        trigger.propagate(false);
+        match *button {
+            OutlineButton::Toggle(id) => {
+                if !outline.collapsed.remove(&id) {
+                    outline.collapsed.insert(id);
+                }
+            }
+            OutlineButton::Visibility(id) => {
+                if let Some(item) = document.get_mut(id) {
+                    item.hidden = !item.hidden;
+                }
+            }
+        }
+        return;
+    }
+    let Ok(row) = rows.get(trigger.target()) else {
+        return;
+    };
+    trigger.propagate(false);
+    if selection.selected != Some(row.0) {
+        selection.selected = Some(row.0);
+    } else if entry.field != Some(TextField::Name) {
+        entry.field = Some(TextField::Name);
+        entry.buffer = TextField::Name.get(&document, row.0, 0.0);
+    }
+}
+
+pub fn start_outline_drag(
+    mut trigger: Trigger<Pointer<DragStart>>,
+    rows: Query<&OutlineRow>,
+    document: Res<FlexDocument>,
+    mut drag: ResMut<OutlineDrag>,
+) {
+    let Ok(row) = rows.get(trigger.target()) else {
+        return;
+    };
+    trigger.propagate(false);
+    if row.0 != document.root {
+        drag.item = Some(row.0);
+        drag.target = None;
+    }
+}
+
+/// Tracks the row under the pointer and which part of it, a box can't be dropped inside itself
+pub fn drag_over_outline(
+    mut trigger: Trigger<Pointer<DragOver>>,
+    rows: Query<(&OutlineRow, &RelativeCursorPosition)>,
+    document: Res<FlexDocument>,
+    mut drag: ResMut<OutlineDrag>,
+) {
+    let Some(item) = drag.item else {
+        return;
+    };
+    let Ok((row, cursor)) = rows.get(trigger.target()) else {
+        return;
+    };
+    trigger.propagate(false);
+    // Nothing goes beside the root
+    let place = match cursor.normalized {
+        Some(position) if row.0 != document.root => DropPlace::at(position.y),
+        _ => DropPlace::Into,
+    };
+    let target = (!document.is_within(row.0, item)).then_some((row.0, place));
+    if drag.target != target {
+        drag.target = target;
+    }
+}
+
+pub fn leave_outline_row(
+    mut trigger: Trigger<Pointer<DragLeave>>,
+    rows: Query<&OutlineRow>,
+    mut drag: ResMut<OutlineDrag>,
+) {
+    let Ok(row) = rows.get(trigger.target()) else {
+        return;
+    };
+    trigger.propagate(false);
+    if drag.target.is_some_and(|(target, _)| target == row.0) {
+        drag.target = None;
+    }
+}
+
+/// Moves the dragged box before, after or into the row it was released over
+pub fn end_outline_drag(
+    _trigger: Trigger<Pointer<DragEnd>>,
+    mut document: ResMut<FlexDocument>,
+    mut drag: ResMut<OutlineDrag>,
 ) {
-    log::info!("Back button pressed event received");
-    next_state.set(GameState::Startup);
+    let (Some(item), Some((target, place))) = (drag.item.take(), drag.target.take()) else {
+        return;
+    };
+    let Some((parent, index)) = place.destination(&document, target) else {
+        return;
+    };
+    let name = |id| document.get(id).map_or(String::new(), |item| item.name.clone());
+    let (item_name, parent_name) = (name(item), name(parent));
+    match document.move_box(item, parent, index) {
+        Ok(()) => log::info!("Moved {item_name} into {parent_name}"),
+        Err(err) => log::warn!("Can't move {item_name}: {err}"),
+    }
 }
@@ -0,0 +1,103 @@
+use crate::tools::flex_grid::document::FlexDocument;
+use crate::tools::flex_grid::observers::{
+    click_outline, drag_grid_item, drag_over_outline, drag_splitter, drag_viewport_edge, end_grid_drag, end_outline_drag,
+    end_splitter_drag, end_viewport_drag, leave_outline_row, select_box_on_click, start_grid_drag, start_outline_drag,
+    start_splitter_drag, start_viewport_drag,
+};
+use crate::tools::flex_grid::resources::{
+    BreakpointPreviews, FlexLayout, FlexSelection, FlexTextEntry, FlexViewport, GridDrag, LayoutTransition,
+    LayoutTransitionSettings, OutlineDrag, OutlineState, SplitterDrag, ViewportDrag,
+};
+use crate::tools::flex_grid::systems::{
+    animate_state_transition, cleanup_flex_grid, draw_box_outlines, draw_breakpoint_previews, draw_constraint_issues,
+    draw_grid_guides, fit_flex_camera, flex_panel_buttons, flex_text_entry, highlight_outline, import_dropped_layouts,
+    rebuild_flex_panel, rebuild_outline, rebuild_state_buttons, refresh_constraint_status, refresh_flex_panel,
+    refresh_motor_values, refresh_outline_names, refresh_panel_headings, refresh_state_panel, relayout,
+    spawn_flex_editor, state_buttons, sync_box_views, sync_grid_area_labels, sync_preview_labels, sync_splitters,
+    sync_viewport_edge, update_breakpoint_previews,
+};
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct FlexGridPlugin;
+
+impl Plugin for FlexGridPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FlexDocument>()
+            .init_resource::<FlexSelection>()
+            .init_resource::<FlexLayout>()
+            .init_resource::<SplitterDrag>()
+            .init_resource::<FlexTextEntry>()
+            .init_resource::<GridDrag>()
+            .init_resource::<FlexViewport>()
+            .init_resource::<ViewportDrag>()
+            .init_resource::<BreakpointPreviews>()
+            .init_resource::<OutlineState>()
+            .init_resource::<OutlineDrag>()
+            .init_resource::<LayoutTransitionSettings>()
+            .init_resource::<LayoutTransition>()
+            .add_systems(
+                OnEnter(GameState::Flexer),
+                spawn_flex_editor,
+            )
+            .add_systems(
+                Update,
+                (
+                    import_dropped_layouts.before(rebuild_flex_panel),
+                    (
+                        rebuild_flex_panel,
+                        flex_panel_buttons,
+                        flex_text_entry,
+                        refresh_flex_panel,
+                        refresh_panel_headings,
+                        refresh_constraint_status,
+                        refresh_motor_values,
+                    )
+                        .chain(),
+                    (rebuild_outline, refresh_outline_names, highlight_outline)
+                        .chain()
+                        .after(flex_text_entry),
+                    (rebuild_state_buttons, state_buttons, refresh_state_panel).chain(),
+                    (
+                        relayout,
+                        animate_state_transition,
+                        update_breakpoint_previews,
+                        sync_box_views,
+                        sync_splitters,
+                        sync_viewport_edge,
+                        sync_grid_area_labels,
+                        sync_preview_labels,
+                        draw_box_outlines,
+                        draw_grid_guides,
+                        draw_constraint_issues,
+                        draw_breakpoint_previews,
+                        fit_flex_camera,
+                    )
+                        .chain()
+                        .after(flex_text_entry)
+                        .after(state_buttons),
+                )
+                    .run_if(in_state(GameState::Flexer)),
+            )
+            .add_systems(
+                OnExit(GameState::Flexer),
+                cleanup_flex_grid
+            )
+            .add_observer(select_box_on_click)
+            .add_observer(start_splitter_drag)
+            .add_observer(drag_splitter)
+            .add_observer(end_splitter_drag)
+            .add_observer(start_grid_drag)
+            .add_observer(drag_grid_item)
+            .add_observer(end_grid_drag)
+            .add_observer(start_viewport_drag)
+            .add_observer(drag_viewport_edge)
+            .add_observer(end_viewport_drag)
+            .add_observer(click_outline)
+            .add_observer(start_outline_drag)
+            .add_observer(drag_over_outline)
+            .add_observer(leave_outline_row)
+            .add_observer(end_outline_drag);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::tools::flex_grid::constraint::{ConstraintField, ConstraintReport};
+use crate::tools::flex_grid::document::{BoxId, FlexDocument, FlexStyle};
+use crate::tools::flex_grid::grid::GridField;
+use crate::tools::flex_grid::layout::root_size;
+use crate::tools::flex_grid::split::ActiveSplit;
+use crate::tools::flex_grid::transition::RunningTransition;
+
+#[derive(Resource, Debug, Default)]
+pub struct FlexSelection {
+    pub selected: Option<BoxId>,
+}
+
+/// Computed box rectangles, see [`compute_layout`](crate::tools::flex_grid::layout::compute_layout)
+#[derive(Resource, Debug, Default)]
+pub struct FlexLayout {
+    /// Container size the layout was computed for
+    pub root: Vec2,
+    pub rects: HashMap<BoxId, Rect>,
+    pub grids: HashMap<BoxId, GridTracks>,
+    /// Diagnosis of every constraint-mode box
+    pub constraints: HashMap<BoxId, ConstraintReport>,
+}
+
+/// Computed tracks of a grid box, in layout space
+#[derive(Debug, Clone, Default)]
+pub struct GridTracks {
+    /// Start and end of every column, implicit ones included
+    pub columns: Vec<(f32, f32)>,
+    pub rows: Vec<(f32, f32)>,
+    /// Implicit tracks created before line 1
+    pub leading_columns: usize,
+    pub leading_rows: usize,
+    /// Content box the tracks are laid into
+    pub content: Rect,
+}
+
+impl GridTracks {
+    /// 1-based column and row lines starting the cell under `point`; gaps belong to the track before them
+    pub fn cell_at(&self, point: Vec2) -> Option<(i16, i16)> {
+        if !self.content.contains(point) {
+            return None;
+        }
+        let index = |tracks: &[(f32, f32)], value: f32| tracks.iter().rposition(|(start, _)| *start <= value).unwrap_or(0);
+        let column = index(&self.columns, point.x) as i16 - self.leading_columns as i16 + 1;
+        let row = index(&self.rows, point.y) as i16 - self.leading_rows as i16 + 1;
+        Some((column, row))
+    }
+
+    /// Rectangle from line `column`/`row` spanning the given number of tracks
+    pub fn cells_rect(&self, column: i16, row: i16, columns: u16, rows: u16) -> Option<Rect> {
+        let track = |tracks: &[(f32, f32)], leading: usize, line: i16, count: u16| {
+            let first = usize::try_from(line - 1 + leading as i16).ok()?;
+            let last = (first + count.max(1) as usize - 1).min(tracks.len().checked_sub(1)?);
+            Some((tracks.get(first)?.0, tracks[last].1))
+        };
+        let (left, right) = track(&self.columns, self.leading_columns, column, columns)?;
+        let (top, bottom) = track(&self.rows, self.leading_rows, row, rows)?;
+        Some(Rect::new(left, top, right, bottom))
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct SplitterDrag {
+    pub active: Option<ActiveSplit>,
+}
+
+/// Property edited as text in the panel, or a box's name in the outline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Name,
+    Grid(GridField),
+    Constraint(ConstraintField),
+}
+
+impl TextField {
+    pub fn label(self) -> &'static str {
+        match self {
+            TextField::Name => "name",
+            TextField::Grid(field) => field.label(),
+            TextField::Constraint(field) => field.label(),
+        }
+    }
+
+    /// Current text of the field on box `id` in a container `width` wide
+    pub fn get(self, document: &FlexDocument, id: BoxId, width: f32) -> String {
+        let style = document.style_at(id, width);
+        match self {
+            TextField::Name => document.get(id).map(|item| item.name.clone()).unwrap_or_default(),
+            TextField::Grid(field) => style.map(|style| field.get(style)).unwrap_or_default(),
+            TextField::Constraint(field) => style.map(|style| field.get(style)).unwrap_or_default(),
+        }
+    }
+
+    /// Parses `text` into box `id`, style fields going to `breakpoint`'s override
+    pub fn set(self, document: &mut FlexDocument, id: BoxId, breakpoint: Option<&str>, text: &str) -> Result<(), String> {
+        match self {
+            TextField::Name => document.rename(id, text),
+            TextField::Grid(field) => field.set(editable_style(document, id, breakpoint)?, text),
+            TextField::Constraint(field) => field.set(editable_style(document, id, breakpoint)?, text),
+        }
+    }
+}
+
+fn editable_style<'a>(document: &'a mut FlexDocument, id: BoxId, breakpoint: Option<&str>) -> Result<&'a mut FlexStyle, String> {
+    document
+        .style_mut(id, breakpoint)
+        .ok_or_else(|| "box no longer exists".to_string())
+}
+
+/// Property being typed into; Enter commits, Escape cancels
+#[derive(Resource, Debug, Default)]
+pub struct FlexTextEntry {
+    pub field: Option<TextField>,
+    pub buffer: String,
+}
+
+/// Grid item being dragged onto another cell or area of its parent
+#[derive(Resource, Debug, Default)]
+pub struct GridDrag {
+    pub item: Option<BoxId>,
+    /// Column and row lines of the cell under the pointer
+    pub target: Option<(i16, i16)>,
+}
+
+/// Container width the editor lays out and edits at; `None` uses the root's own width
+#[derive(Resource, Debug, Default)]
+pub struct FlexViewport {
+    pub width: Option<f32>,
+}
+
+impl FlexViewport {
+    pub fn width(&self, document: &FlexDocument) -> f32 {
+        self.width.unwrap_or_else(|| root_size(document).x)
+    }
+}
+
+/// Container edge being dragged, with the width and camera zoom when it started
+#[derive(Resource, Debug, Default)]
+pub struct ViewportDrag {
+    pub active: Option<(f32, f32)>,
+}
+
+/// Layouts shown side by side above the canvas, one per preview width
+#[derive(Resource, Debug, Default)]
+pub struct BreakpointPreviews {
+    pub frames: Vec<(&'static str, FlexLayout)>,
+}
+
+/// Boxes whose children the outline hides
+#[derive(Resource, Debug, Default)]
+pub struct OutlineState {
+    pub collapsed: HashSet<BoxId>,
+}
+
+/// Where a box dropped on an outline row goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPlace {
+    Before,
+    After,
+    /// Last child of the row's box
+    Into,
+}
+
+impl DropPlace {
+    /// Top and bottom quarter of a row place beside it, the middle inside it
+    pub fn at(y: f32) -> Self {
+        if y < 0.25 {
+            DropPlace::Before
+        } else if y > 0.75 {
+            DropPlace::After
+        } else {
+            DropPlace::Into
+        }
+    }
+
+    /// Parent and index a box dropped at this place next to `target` moves to
+    pub fn destination(self, document: &FlexDocument, target: BoxId) -> Option<(BoxId, usize)> {
+        let parent = document.parent(target);
+        match (self, parent) {
+            (DropPlace::Into, _) | (_, None) => Some((target, document.get(target)?.children.len())),
+            (place, Some(parent)) => {
+                let index = document.get(parent)?.children.iter().position(|child| *child == target)?;
+                Some((parent, if place == DropPlace::After { index + 1 } else { index }))
+            }
+        }
+    }
+}
+
+/// Box being dragged in the outline and the row it would be dropped on
+#[derive(Resource, Debug, Default)]
+pub struct OutlineDrag {
+    pub item: Option<BoxId>,
+    pub target: Option<(BoxId, DropPlace)>,
+}
+
+/// How switching layout states animates the boxes
+#[derive(Resource, Debug, Clone)]
+pub struct LayoutTransitionSettings {
+    /// Seconds, 0 switches instantly
+    pub duration: f32,
+    pub easing: EaseFunction,
+}
+
+impl Default for LayoutTransitionSettings {
+    fn default() -> Self {
+        Self {
+            duration: 0.4,
+            easing: EaseFunction::CubicInOut,
+        }
+    }
+}
+
+/// Switch between layout states whose animation is playing
+#[derive(Resource, Debug, Default)]
+pub struct LayoutTransition {
+    pub running: Option<RunningTransition>,
+}
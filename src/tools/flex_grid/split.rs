@@ -0,0 +1,149 @@
+//! Redistributing size between two neighbouring boxes from a splitter drag
+
+use std::fmt::Write;
+
+use crate::tools::flex_grid::document::{BoxId, FlexDocument};
+
+/// Snap targets as the share of the pair taken by the first box
+const SNAP_RATIOS: [(&str, f32); 5] = [
+    ("1:2", 1.0 / 3.0),
+    ("1:φ", 0.381_966),
+    ("1:1", 0.5),
+    ("φ:1", 0.618_034),
+    ("2:1", 2.0 / 3.0),
+];
+/// Pixels within which a drag snaps onto a ratio
+const SNAP_DISTANCE: f32 = 8.0;
+
+/// What the drag rewrites on the two boxes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitMode {
+    /// Both boxes grow, their grow values are shared out keeping the sum
+    Grow { sum: f32 },
+    /// Fixed bases summing to the pair's size
+    Basis,
+}
+
+/// Splitter being dragged
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveSplit {
+    pub before: BoxId,
+    pub after: BoxId,
+    /// Main-axis size of `before` when the drag started
+    pub start: f32,
+    /// Main-axis size of both boxes together
+    pub total: f32,
+    pub mode: SplitMode,
+    /// Breakpoint whose overrides are rewritten, `None` for the base styles
+    pub breakpoint: Option<String>,
+}
+
+impl ActiveSplit {
+    /// Split between two boxes laid out in a container `width` wide
+    pub fn new(document: &FlexDocument, width: f32, before: BoxId, after: BoxId, start: f32, total: f32) -> Option<Self> {
+        let (a, b) = (document.style_at(before, width)?, document.style_at(after, width)?);
+        let mode = if a.grow > 0.0 && b.grow > 0.0 {
+            SplitMode::Grow { sum: a.grow + b.grow }
+        } else {
+            SplitMode::Basis
+        };
+        let breakpoint = document.breakpoint_at(width).map(|breakpoint| breakpoint.name.clone());
+        Some(Self {
+            before,
+            after,
+            start,
+            total,
+            mode,
+            breakpoint,
+        })
+    }
+
+    /// Share of the pair for `before` after dragging `offset` pixels along the main axis, with the snapped ratio
+    pub fn fraction(&self, offset: f32) -> (f32, Option<&'static str>) {
+        if self.total <= 0.0 {
+            return (0.5, None);
+        }
+        let size = (self.start + offset).clamp(0.0, self.total);
+        let snapped = SNAP_RATIOS
+            .iter()
+            .find(|(_, ratio)| (ratio * self.total - size).abs() <= SNAP_DISTANCE);
+        match snapped {
+            Some((label, ratio)) => (*ratio, Some(*label)),
+            None => (size / self.total, None),
+        }
+    }
+
+    /// Writes the new grow or basis values and returns the readout shown next to the splitter
+    pub fn apply(&self, document: &mut FlexDocument, offset: f32) -> String {
+        let (fraction, snapped) = self.fraction(offset);
+        let (first, second) = match self.mode {
+            SplitMode::Grow { sum } => (fraction * sum, (1.0 - fraction) * sum),
+            SplitMode::Basis => ((fraction * self.total).round(), self.total.round() - (fraction * self.total).round()),
+        };
+        for (id, value) in [(self.before, first), (self.after, second)] {
+            let Some(style) = document.style_mut(id, self.breakpoint.as_deref()) else {
+                continue;
+            };
+            match self.mode {
+                SplitMode::Grow { .. } => style.grow = value,
+                SplitMode::Basis => style.basis = Some(value),
+            }
+        }
+
+        let mut readout = match self.mode {
+            SplitMode::Grow { .. } => format!("grow {first:.2} : {second:.2}"),
+            SplitMode::Basis => format!("basis {first:.0}px : {second:.0}px"),
+        };
+        if let Some(label) = snapped {
+            let _ = write!(readout, "  ({label})");
+        }
+        readout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(start: f32, total: f32) -> ActiveSplit {
+        ActiveSplit {
+            before: BoxId(1),
+            after: BoxId(2),
+            start,
+            total,
+            mode: SplitMode::Basis,
+            breakpoint: None,
+        }
+    }
+
+    #[test]
+    fn fraction_follows_the_drag_and_snaps_near_ratios() {
+        let split = split(100.0, 300.0);
+        assert_eq!(split.fraction(0.0), (1.0 / 3.0, Some("1:2")));
+        // 145px is within snapping distance of half of 300px
+        assert_eq!(split.fraction(45.0), (0.5, Some("1:1")));
+        // 125px sits between 1:2 and 1:φ, too far from both
+        assert_eq!(split.fraction(25.0), (125.0 / 300.0, None));
+    }
+
+    #[test]
+    fn fraction_stays_within_the_pair() {
+        assert_eq!(split(100.0, 300.0).fraction(-500.0), (0.0, None));
+        assert_eq!(split(100.0, 300.0).fraction(500.0), (1.0, None));
+        assert_eq!(split(0.0, 0.0).fraction(10.0), (0.5, None));
+    }
+
+    #[test]
+    fn basis_split_keeps_the_total() {
+        let mut document = FlexDocument::default();
+        let body = document.get(document.named("body")).unwrap();
+        let (sidebar, content) = (body.children[0], body.children[1]);
+        // The sidebar has a basis and no grow, so the drag rewrites bases
+        let split = ActiveSplit::new(&document, 1280.0, sidebar, content, 160.0, 1000.0).unwrap();
+        assert_eq!(split.mode, SplitMode::Basis);
+        split.apply(&mut document, 40.0);
+        let basis = |id| document.get(id).unwrap().style.basis.unwrap();
+        assert_eq!(basis(sidebar), 200.0);
+        assert_eq!(basis(sidebar) + basis(content), 1000.0);
+    }
+}
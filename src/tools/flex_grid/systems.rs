@@ -0,0 +1,1652 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::{FileDragAndDrop, PrimaryWindow};
+use std::path::PathBuf;
+
+use crate::systems::loading::FontAssets;
+use crate::tools::flex_grid::components::{
+    ConstraintStatusText, FlexBoxLabel, MotorAction, MotorValueText, FlexBoxView, FlexBreakpointText, FlexButton, FlexFieldText, FlexGridEntity, FlexPanelContent,
+    FlexPanelTitle, FlexSplitter, FlexValueText, GridAreaLabel, OutlineButton, OutlineContent, OutlineDropLine, OutlineName,
+    OutlineRow, PreviewLabel, StateButton, StateContent, TransitionText, ViewportEdge,
+};
+use crate::tools::flex_grid::document::{Align, BoxId, Direction, FlexDocument, FlexProp, Justify, LayoutMode};
+use crate::tools::flex_grid::export::{save_flex_export, save_transition_frames, FlexExportFormat};
+use crate::tools::flex_grid::constraint::{ConstraintField, ConstraintIssue, LayoutConstraint};
+use crate::tools::flex_grid::grid::{AreaLines, GridField, GridTemplate};
+use crate::tools::flex_grid::import::load_flex_import;
+use crate::tools::flex_grid::layout::{compute_layout, root_size, to_world};
+use crate::tools::flex_grid::motor::{driven_document, LayoutMotor, MotorParam, MOTOR_PROPS};
+use crate::tools::flex_grid::resources::{
+    BreakpointPreviews, DropPlace, FlexLayout, FlexSelection, FlexTextEntry, FlexViewport, GridDrag, GridTracks, OutlineDrag,
+    OutlineState, LayoutTransition, LayoutTransitionSettings, TextField, ViewportDrag,
+};
+use crate::tools::flex_grid::transition::{blend_rects, RunningTransition};
+use crate::tools::transport::{spawn_transport_bar, Transport};
+use crate::ui::components::ButtonColors;
+use crate::ui::font_utils::text_geist_regular_with_font;
+use crate::ui::KeyboardFocus;
+
+const PANEL_WIDTH: f32 = 280.0;
+/// Width of the outline docked on the right
+const OUTLINE_WIDTH: f32 = 240.0;
+/// Indent per nesting level in the outline
+const OUTLINE_INDENT: f32 = 12.0;
+const DROP_COLOR: Color = Color::srgba(1.0, 0.6, 0.15, 0.35);
+const PANEL_TOP: f32 = 80.0;
+const ACTIVE_COLOR: Color = Color::linear_rgb(0.2, 0.35, 0.6);
+const OUTLINE_COLOR: Color = Color::linear_rgb(0.55, 0.55, 0.6);
+const SELECTED_COLOR: Color = Color::linear_rgb(1.0, 0.6, 0.15);
+const LABEL_SIZE: f32 = 12.0;
+const EXPORT_DIR: &str = "exports";
+const SPLITTER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.18);
+/// Splitters are at least this wide even when the gap is smaller
+const SPLITTER_THICKNESS: f32 = 6.0;
+/// Above every box so handles stay grabbable over nested content
+const SPLITTER_Z: f32 = 5.0;
+const GUIDE_COLOR: Color = Color::srgba(0.3, 0.85, 0.9, 0.6);
+const AREA_COLOR: Color = Color::srgba(0.95, 0.45, 0.85, 0.8);
+const AREA_LABEL_Z: f32 = 4.0;
+const KEYBOARD_OWNER: &str = "flex_grid";
+const CONFLICT_COLOR: Color = Color::linear_rgb(1.0, 0.2, 0.2);
+const UNSATISFIED_COLOR: Color = Color::linear_rgb(1.0, 0.55, 0.1);
+const LOOSE_COLOR: Color = Color::linear_rgb(0.95, 0.85, 0.2);
+const HINT_COLOR: Color = Color::linear_rgb(0.6, 0.6, 0.65);
+/// Container widths previewed side by side above the canvas, also offered as presets in the panel
+const PREVIEW_WIDTHS: [(&str, f32); 3] = [("phone", 375.0), ("tablet", 768.0), ("desktop", 1280.0)];
+const PREVIEW_SCALE: f32 = 0.25;
+/// Space between preview frames, and between the strip and the canvas
+const PREVIEW_GAP: f32 = 40.0;
+const PREVIEW_COLOR: Color = Color::srgba(0.55, 0.55, 0.6, 0.6);
+const EDGE_COLOR: Color = Color::srgba(1.0, 0.6, 0.15, 0.5);
+const EDGE_THICKNESS: f32 = 10.0;
+/// Empty space kept around the canvas when the camera fits it
+const FIT_MARGIN: f32 = 40.0;
+/// Easings state switches cycle through
+const EASINGS: [(&str, EaseFunction); 6] = [
+    ("linear", EaseFunction::Linear),
+    ("ease in", EaseFunction::CubicIn),
+    ("ease out", EaseFunction::CubicOut),
+    ("ease in-out", EaseFunction::CubicInOut),
+    ("back out", EaseFunction::BackOut),
+    ("elastic out", EaseFunction::ElasticOut),
+];
+const MAX_TRANSITION_DURATION: f32 = 5.0;
+
+/// Fill for a box, nested boxes get lighter so they stand out from their parent
+fn box_color(depth: usize) -> Color {
+    let lightness = 0.12 + 0.07 * depth.min(8) as f32;
+    Color::hsl(215.0, 0.25, lightness)
+}
+
+pub fn spawn_flex_editor(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    mut selection: ResMut<FlexSelection>,
+    mut layout: ResMut<FlexLayout>,
+    viewport: Res<FlexViewport>,
+) {
+    info!("Spawning Flex Grid");
+    selection.selected = Some(document.root);
+    *layout = compute_layout(&document, viewport.width(&document));
+
+    // Layout motors run on the shared transport
+    let transport_bar = spawn_transport_bar(&mut commands, &fonts);
+    commands.entity(transport_bar).insert(FlexGridEntity);
+
+    commands.spawn((
+        Sprite {
+            color: EDGE_COLOR,
+            custom_size: Some(Vec2::new(EDGE_THICKNESS, layout.root.y)),
+            ..default()
+        },
+        Transform::from_translation(to_world(Vec2::new(layout.root.x, layout.root.y * 0.5), layout.root).extend(SPLITTER_Z)),
+        ViewportEdge,
+        FlexGridEntity,
+    ));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(PANEL_TOP),
+                width: Val::Px(PANEL_WIDTH),
+                max_height: Val::Percent(85.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(8.0),
+                overflow: Overflow::clip_y(),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            FlexGridEntity,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                text_geist_regular_with_font("Box", 18.0, Color::WHITE, &fonts),
+                FlexPanelTitle,
+            ));
+            panel.spawn((
+                text_geist_regular_with_font("", 12.0, Color::WHITE, &fonts),
+                FlexBreakpointText,
+            ));
+            panel.spawn(row()).with_children(|presets| {
+                for (name, width) in PREVIEW_WIDTHS {
+                    spawn_flex_button(presets, name, FlexButton::Viewport(Some(width)), &fonts);
+                }
+                spawn_flex_button(presets, "own width", FlexButton::Viewport(None), &fonts);
+                spawn_flex_button(presets, "clear override", FlexButton::ClearOverride, &fonts);
+            });
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                FlexPanelContent,
+            ));
+        });
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                top: Val::Px(PANEL_TOP),
+                width: Val::Px(OUTLINE_WIDTH),
+                max_height: Val::Percent(85.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(8.0),
+                overflow: Overflow::clip_y(),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            FlexGridEntity,
+        ))
+        .with_children(|panel| {
+            panel.spawn(text_geist_regular_with_font("States", 18.0, Color::WHITE, &fonts));
+            panel.spawn((row(), StateContent));
+            panel.spawn(row()).with_children(|settings| {
+                spawn_flex_button(settings, "-", StateButton::Duration(-0.1), &fonts);
+                spawn_flex_button(settings, "+", StateButton::Duration(0.1), &fonts);
+                spawn_flex_button(settings, "easing", StateButton::Easing, &fonts);
+                settings.spawn((text_geist_regular_with_font("", 12.0, Color::WHITE, &fonts), TransitionText));
+            });
+            panel.spawn(text_geist_regular_with_font("Outline", 18.0, Color::WHITE, &fonts));
+            panel.spawn(text_geist_regular_with_font(
+                "click a selected box to rename it, drag to move it",
+                11.0,
+                HINT_COLOR,
+                &fonts,
+            ));
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                OutlineContent,
+            ));
+        });
+}
+
+/// Lays the document out when it or the container width changes, and every frame while motors drive it
+///
+/// Transport time only moves in fixed steps, so motors are sampled between them to keep the motion smooth.
+pub fn relayout(
+    document: Res<FlexDocument>,
+    viewport: Res<FlexViewport>,
+    transport: Res<Transport>,
+    fixed: Res<Time<Fixed>>,
+    mut layout: ResMut<FlexLayout>,
+) {
+    let driven = document.has_motors() && (transport.is_playing() || transport.is_changed());
+    if !document.is_changed() && !viewport.is_changed() && !driven {
+        return;
+    }
+    let width = viewport.width(&document);
+    let t = transport.interpolated(fixed.overstep().as_secs_f64());
+    *layout = match driven_document(&document, width, t) {
+        Some(driven) => compute_layout(&driven, width),
+        None => compute_layout(&document, width),
+    };
+}
+
+/// Moves the boxes from where they were when the state switched towards the new state's layout
+///
+/// Edits and motors during the switch retarget it to the layout computed since.
+pub fn animate_state_transition(
+    time: Res<Time>,
+    settings: Res<LayoutTransitionSettings>,
+    mut transition: ResMut<LayoutTransition>,
+    mut layout: ResMut<FlexLayout>,
+) {
+    let Some(running) = transition.running.as_mut() else {
+        return;
+    };
+    // Only relayout changes it, this system's own writes don't count
+    if running.to.is_none() || layout.is_changed() {
+        running.to = Some(layout.rects.clone());
+    }
+    let now = time.elapsed_secs_f64();
+    let Some(to) = running.to.as_ref() else {
+        return;
+    };
+    if running.finished(now, &settings) {
+        layout.rects = to.clone();
+        transition.running = None;
+        return;
+    }
+    layout.rects = blend_rects(&running.from, to, running.progress(now, &settings));
+}
+
+/// Keeps the container edge handle on the root's right edge
+pub fn sync_viewport_edge(layout: Res<FlexLayout>, mut edges: Query<(&mut Sprite, &mut Transform), With<ViewportEdge>>) {
+    if !layout.is_changed() {
+        return;
+    }
+    let root = layout.root;
+    for (mut sprite, mut transform) in edges.iter_mut() {
+        sprite.custom_size = Some(Vec2::new(EDGE_THICKNESS, root.y));
+        transform.translation = to_world(Vec2::new(root.x, root.y * 0.5), root).extend(SPLITTER_Z);
+    }
+}
+
+/// Keeps one sprite and label per box, matching the computed layout
+pub fn sync_box_views(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    mut views: Query<(Entity, &FlexBoxView, &mut Sprite, &mut Transform)>,
+    mut labels: Query<(Entity, &FlexBoxLabel, &mut Text2d, &mut Transform), Without<FlexBoxView>>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+    let root = layout.root;
+    let depths: std::collections::HashMap<_, _> = document.walk().into_iter().collect();
+
+    let mut shown = Vec::new();
+    for (entity, view, mut sprite, mut transform) in views.iter_mut() {
+        let (Some(rect), Some(depth)) = (layout.rects.get(&view.0), depths.get(&view.0)) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        sprite.custom_size = Some(rect.size());
+        sprite.color = box_color(*depth);
+        transform.translation = to_world(rect.center(), root).extend(*depth as f32 * 0.1);
+        shown.push(view.0);
+    }
+    for (entity, label, mut text, mut transform) in labels.iter_mut() {
+        let (Some(rect), Some(item), Some(depth)) =
+            (layout.rects.get(&label.0), document.get(label.0), depths.get(&label.0))
+        else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        text.0 = format!("{} {:.0}x{:.0}", item.name, rect.width(), rect.height());
+        transform.translation = to_world(rect.min + Vec2::splat(4.0), root).extend(*depth as f32 * 0.1 + 0.05);
+    }
+
+    for (id, depth) in document.walk() {
+        let (Some(rect), Some(item)) = (layout.rects.get(&id), document.get(id)) else {
+            continue;
+        };
+        if shown.contains(&id) {
+            continue;
+        }
+        commands.spawn((
+            Sprite {
+                color: box_color(depth),
+                custom_size: Some(rect.size()),
+                ..default()
+            },
+            Transform::from_translation(to_world(rect.center(), root).extend(depth as f32 * 0.1)),
+            FlexBoxView(id),
+            FlexGridEntity,
+        ));
+        commands.spawn((
+            Text2d::new(format!("{} {:.0}x{:.0}", item.name, rect.width(), rect.height())),
+            TextFont {
+                font: fonts.geist_regular.clone(),
+                font_size: LABEL_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Anchor::TopLeft,
+            Transform::from_translation(to_world(rect.min + Vec2::splat(4.0), root).extend(depth as f32 * 0.1 + 0.05)),
+            FlexBoxLabel(id),
+            FlexGridEntity,
+        ));
+    }
+}
+
+/// Handle rectangle covering the gap between two neighbouring boxes, in layout space
+fn splitter_rect(a: Rect, b: Rect, row: bool) -> Rect {
+    if row {
+        let x = (a.max.x + b.min.x) * 0.5;
+        let half = (b.min.x - a.max.x).max(SPLITTER_THICKNESS) * 0.5;
+        Rect::new(x - half, a.min.y.min(b.min.y), x + half, a.max.y.max(b.max.y))
+    } else {
+        let y = (a.max.y + b.min.y) * 0.5;
+        let half = (b.min.y - a.max.y).max(SPLITTER_THICKNESS) * 0.5;
+        Rect::new(a.min.x.min(b.min.x), y - half, a.max.x.max(b.max.x), y + half)
+    }
+}
+
+/// Keeps a drag handle between every pair of neighbouring boxes; handles are updated in place so a drag survives relayout
+pub fn sync_splitters(
+    mut commands: Commands,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    mut splitters: Query<(Entity, &FlexSplitter, &mut Sprite, &mut Transform)>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+    let root = layout.root;
+    let mut wanted: Vec<(FlexSplitter, Rect)> = Vec::new();
+    for item in document.boxes.values() {
+        let Some(style) = document.style_at(item.id, root.x) else {
+            continue;
+        };
+        // Grid items are moved between cells instead
+        if style.mode != LayoutMode::Flex {
+            continue;
+        }
+        let row = style.direction.is_row();
+        let mut children: Vec<(BoxId, Rect)> = item
+            .children
+            .iter()
+            .filter_map(|child| Some((*child, *layout.rects.get(child)?)))
+            .collect();
+        // Reversed directions still get handles in on-screen order
+        children.sort_by(|(_, a), (_, b)| if row { a.min.x.total_cmp(&b.min.x) } else { a.min.y.total_cmp(&b.min.y) });
+        for pair in children.windows(2) {
+            let ((before, a), (after, b)) = (pair[0], pair[1]);
+            wanted.push((FlexSplitter { before, after, row }, splitter_rect(a, b, row)));
+        }
+    }
+
+    for (entity, splitter, mut sprite, mut transform) in splitters.iter_mut() {
+        let Some(index) = wanted.iter().position(|(candidate, _)| candidate == splitter) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let (_, rect) = wanted.swap_remove(index);
+        sprite.custom_size = Some(rect.size());
+        transform.translation = to_world(rect.center(), root).extend(SPLITTER_Z);
+    }
+    for (splitter, rect) in wanted {
+        commands.spawn((
+            Sprite {
+                color: SPLITTER_COLOR,
+                custom_size: Some(rect.size()),
+                ..default()
+            },
+            Transform::from_translation(to_world(rect.center(), root).extend(SPLITTER_Z)),
+            splitter,
+            FlexGridEntity,
+        ));
+    }
+}
+
+/// Outlines every box so nested boxes stay readable, the selected one on top
+pub fn draw_box_outlines(mut gizmos: Gizmos, layout: Res<FlexLayout>, selection: Res<FlexSelection>) {
+    let root = layout.root;
+    for (id, rect) in layout.rects.iter() {
+        if Some(*id) != selection.selected {
+            gizmos.rect_2d(to_world(rect.center(), root), rect.size(), OUTLINE_COLOR);
+        }
+    }
+    if let Some(rect) = selection.selected.and_then(|id| layout.rects.get(&id)) {
+        gizmos.rect_2d(to_world(rect.center(), root), rect.size(), SELECTED_COLOR);
+    }
+}
+
+/// Track lines of every grid box, its named areas, and the cell a dragged item would land in
+pub fn draw_grid_guides(
+    mut gizmos: Gizmos,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    drag: Res<GridDrag>,
+) {
+    let root = layout.root;
+    let line = |gizmos: &mut Gizmos, from: Vec2, to: Vec2, color: Color| {
+        gizmos.line_2d(to_world(from, root), to_world(to, root), color);
+    };
+    for (id, tracks) in layout.grids.iter() {
+        let content = tracks.content;
+        for (start, end) in tracks.columns.iter() {
+            for x in [*start, *end] {
+                line(&mut gizmos, Vec2::new(x, content.min.y), Vec2::new(x, content.max.y), GUIDE_COLOR);
+            }
+        }
+        for (start, end) in tracks.rows.iter() {
+            for y in [*start, *end] {
+                line(&mut gizmos, Vec2::new(content.min.x, y), Vec2::new(content.max.x, y), GUIDE_COLOR);
+            }
+        }
+        let Some(style) = document.style_at(*id, root.x) else {
+            continue;
+        };
+        for name in style.grid.area_names() {
+            let Some(rect) = style.grid.area(name).and_then(|area| area_rect(tracks, area)) else {
+                continue;
+            };
+            gizmos.rect_2d(to_world(rect.center(), root), rect.size() - Vec2::splat(4.0), AREA_COLOR);
+        }
+    }
+
+    // Landing spot: the whole area under the pointer, or a cell sized like the item
+    let (Some(dragged), Some((column, row))) = (drag.item, drag.target) else {
+        return;
+    };
+    let Some(parent) = document.parent(dragged) else {
+        return;
+    };
+    let (Some(tracks), Some(template)) = (layout.grids.get(&parent), document.style_at(parent, root.x).map(|style| &style.grid))
+    else {
+        return;
+    };
+    let area = usize::try_from(column - 1)
+        .ok()
+        .zip(usize::try_from(row - 1).ok())
+        .and_then(|(column, row)| template.area_at(column, row))
+        .and_then(|name| template.area(name));
+    let rect = match area {
+        Some(area) => area_rect(tracks, area),
+        None => {
+            let (columns, rows) = document
+                .style_at(dragged, root.x)
+                .map_or((1, 1), |style| (style.item.column.span(), style.item.row.span()));
+            tracks.cells_rect(column, row, columns, rows)
+        }
+    };
+    if let Some(rect) = rect {
+        gizmos.rect_2d(to_world(rect.center(), root), rect.size(), SELECTED_COLOR);
+    }
+}
+
+fn area_rect(tracks: &GridTracks, area: AreaLines) -> Option<Rect> {
+    tracks.cells_rect(
+        area.column_start,
+        area.row_start,
+        (area.column_end - area.column_start) as u16,
+        (area.row_end - area.row_start) as u16,
+    )
+}
+
+/// Respawns the area names of every grid box after each relayout
+pub fn sync_grid_area_labels(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    labels: Query<Entity, With<GridAreaLabel>>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+    for entity in labels.iter() {
+        commands.entity(entity).despawn();
+    }
+    let root = layout.root;
+    for (id, tracks) in layout.grids.iter() {
+        let Some(style) = document.style_at(*id, root.x) else {
+            continue;
+        };
+        for name in style.grid.area_names() {
+            let Some(rect) = style.grid.area(name).and_then(|area| area_rect(tracks, area)) else {
+                continue;
+            };
+            commands.spawn((
+                Text2d::new(name),
+                TextFont {
+                    font: fonts.geist_regular.clone(),
+                    font_size: LABEL_SIZE,
+                    ..default()
+                },
+                TextColor(AREA_COLOR),
+                Transform::from_translation(to_world(rect.center(), root).extend(AREA_LABEL_Z)),
+                GridAreaLabel,
+                FlexGridEntity,
+            ));
+        }
+    }
+}
+
+/// Frames of the preview strip in layout space, left to right above the canvas and top aligned
+fn preview_frames(previews: &BreakpointPreviews) -> Vec<Rect> {
+    let height = previews.frames.iter().map(|(_, frame)| frame.root.y).fold(0.0, f32::max) * PREVIEW_SCALE;
+    let mut x = 0.0;
+    previews
+        .frames
+        .iter()
+        .map(|(_, frame)| {
+            let min = Vec2::new(x, -PREVIEW_GAP - height);
+            let size = frame.root * PREVIEW_SCALE;
+            x += size.x + PREVIEW_GAP;
+            Rect::from_corners(min, min + size)
+        })
+        .collect()
+}
+
+pub fn update_breakpoint_previews(document: Res<FlexDocument>, mut previews: ResMut<BreakpointPreviews>) {
+    if !document.is_changed() {
+        return;
+    }
+    previews.frames = PREVIEW_WIDTHS
+        .iter()
+        .map(|(name, width)| (*name, compute_layout(&document, *width)))
+        .collect();
+}
+
+/// Scaled down boxes of every preview, the frames in the breakpoint being edited highlighted
+pub fn draw_breakpoint_previews(
+    mut gizmos: Gizmos,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    previews: Res<BreakpointPreviews>,
+) {
+    let root = layout.root;
+    let editing = document.breakpoint_at(root.x).map(|breakpoint| &breakpoint.name);
+    for ((_, frame), bounds) in previews.frames.iter().zip(preview_frames(&previews)) {
+        let color = if document.breakpoint_at(frame.root.x).map(|breakpoint| &breakpoint.name) == editing {
+            SELECTED_COLOR
+        } else {
+            OUTLINE_COLOR
+        };
+        gizmos.rect_2d(to_world(bounds.center(), root), bounds.size(), color);
+        for rect in frame.rects.values() {
+            let scaled = Rect::from_corners(bounds.min + rect.min * PREVIEW_SCALE, bounds.min + rect.max * PREVIEW_SCALE);
+            gizmos.rect_2d(to_world(scaled.center(), root), scaled.size(), PREVIEW_COLOR);
+        }
+    }
+}
+
+/// Respawns the preview names, widths and the breakpoint each one falls in
+pub fn sync_preview_labels(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    layout: Res<FlexLayout>,
+    previews: Res<BreakpointPreviews>,
+    labels: Query<Entity, With<PreviewLabel>>,
+) {
+    if !previews.is_changed() && !layout.is_changed() {
+        return;
+    }
+    for entity in labels.iter() {
+        commands.entity(entity).despawn();
+    }
+    for ((name, frame), bounds) in previews.frames.iter().zip(preview_frames(&previews)) {
+        let breakpoint = document.breakpoint_at(frame.root.x).map_or("base", |breakpoint| breakpoint.name.as_str());
+        commands.spawn((
+            Text2d::new(format!("{name} {:.0}px - {breakpoint}", frame.root.x)),
+            TextFont {
+                font: fonts.geist_regular.clone(),
+                font_size: LABEL_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Anchor::BottomLeft,
+            Transform::from_translation(to_world(bounds.min - Vec2::new(0.0, 4.0), layout.root).extend(AREA_LABEL_Z)),
+            PreviewLabel,
+            FlexGridEntity,
+        ));
+    }
+}
+
+/// Zooms and pans the camera so the canvas and the preview strip fit between the panels; left alone while the edge is dragged
+pub fn fit_flex_camera(
+    layout: Res<FlexLayout>,
+    previews: Res<BreakpointPreviews>,
+    drag: Res<ViewportDrag>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Projection, &mut Transform), With<Camera2d>>,
+) {
+    if drag.active.is_some() {
+        return;
+    }
+    let (Ok(window), Ok((mut projection, mut transform))) = (windows.single(), cameras.single_mut()) else {
+        return;
+    };
+    let root = layout.root;
+    let bounds = preview_frames(&previews)
+        .into_iter()
+        .fold(Rect::from_corners(Vec2::ZERO, root), |bounds, frame| bounds.union(frame));
+    let bounds = Rect::from_corners(to_world(bounds.min, root), to_world(bounds.max, root)).inflate(FIT_MARGIN);
+    let available = Vec2::new(window.width() - PANEL_WIDTH - OUTLINE_WIDTH, window.height() - PANEL_TOP).max(Vec2::ONE);
+    // Never zoom in, labels stay at their font size
+    let scale = (bounds.size() / available).max_element().max(1.0);
+    // The free area's centre sits below the window's centre, and beside it when the panels differ in width
+    let center = bounds.center() + Vec2::new((OUTLINE_WIDTH - PANEL_WIDTH) * 0.5, PANEL_TOP * 0.5) * scale;
+    let current = match projection.as_ref() {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => return,
+    };
+    if current != scale {
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scale = scale;
+        }
+    }
+    if transform.translation.truncate() != center {
+        transform.translation = center.extend(transform.translation.z);
+    }
+}
+
+fn row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        flex_wrap: FlexWrap::Wrap,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(4.0),
+        row_gap: Val::Px(4.0),
+        ..default()
+    }
+}
+
+fn spawn_flex_button(parent: &mut ChildSpawnerCommands, label: &str, action: impl Component, fonts: &FontAssets) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(ButtonColors::default().normal),
+            BorderRadius::all(Val::Px(3.0)),
+            ButtonColors::default(),
+            action,
+        ))
+        .with_children(|button| {
+            button.spawn(text_geist_regular_with_font(label, 12.0, Color::WHITE, fonts));
+        });
+}
+
+/// Label and the property's text, clicking the text starts editing it
+fn spawn_text_field(parent: &mut ChildSpawnerCommands, field: TextField, fonts: &FontAssets) {
+    parent.spawn(row()).with_children(|field_row| {
+        field_row.spawn((
+            text_geist_regular_with_font(field.label(), 12.0, Color::WHITE, fonts),
+            Node { width: Val::Px(56.0), ..default() },
+        ));
+        spawn_field_button(field_row, field, fonts);
+    });
+}
+
+fn spawn_field_button(parent: &mut ChildSpawnerCommands, field: TextField, fonts: &FontAssets) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                flex_grow: 1.0,
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(ButtonColors::default().normal),
+            BorderRadius::all(Val::Px(3.0)),
+            ButtonColors::default(),
+            FlexButton::Edit(field),
+        ))
+        .with_children(|button| {
+            button.spawn((text_geist_regular_with_font("", 12.0, Color::WHITE, fonts), FlexFieldText(field)));
+        });
+}
+
+/// One row per constraint with its priority and diagnosis, then a field to add another
+fn spawn_constraint_rows(parent: &mut ChildSpawnerCommands, constraints: &[LayoutConstraint], fonts: &FontAssets) {
+    parent.spawn(text_geist_regular_with_font("constraints", 12.0, Color::WHITE, fonts));
+    for (index, constraint) in constraints.iter().enumerate() {
+        parent.spawn(row()).with_children(|constraint_row| {
+            spawn_flex_button(constraint_row, constraint.priority.label(), FlexButton::Priority(index), fonts);
+            spawn_field_button(constraint_row, TextField::Constraint(ConstraintField::Existing(index)), fonts);
+        });
+        parent.spawn((
+            text_geist_regular_with_font("", 11.0, HINT_COLOR, fonts),
+            ConstraintStatusText(Some(index)),
+        ));
+    }
+    spawn_text_field(parent, TextField::Constraint(ConstraintField::New), fonts);
+    parent.spawn(text_geist_regular_with_font(
+        "e.g. [box 1].right + 16 = [box 2].left @weak",
+        11.0,
+        HINT_COLOR,
+        fonts,
+    ));
+    parent.spawn((text_geist_regular_with_font("", 11.0, LOOSE_COLOR, fonts), ConstraintStatusText(None)));
+}
+
+/// One row per motor on the selected box with its settings, then a button per property a motor can be added to
+fn spawn_motor_rows(parent: &mut ChildSpawnerCommands, motors: &[LayoutMotor], fonts: &FontAssets) {
+    parent.spawn(text_geist_regular_with_font("motors", 12.0, Color::WHITE, fonts));
+    for (index, motor) in motors.iter().enumerate() {
+        parent.spawn(row()).with_children(|motor_row| {
+            motor_row.spawn((
+                text_geist_regular_with_font(motor.prop.label(), 12.0, Color::WHITE, fonts),
+                Node { width: Val::Px(56.0), ..default() },
+            ));
+            let waveform = format!("{:?}", motor.motor.waveform);
+            spawn_flex_button(motor_row, &waveform, FlexButton::Motor { index, action: MotorAction::Waveform }, fonts);
+            spawn_flex_button(motor_row, "x", FlexButton::Motor { index, action: MotorAction::Remove }, fonts);
+        });
+        for param in MotorParam::ALL {
+            let step = param.step(motor);
+            parent.spawn(row()).with_children(|param_row| {
+                param_row.spawn((
+                    text_geist_regular_with_font(param.label(), 11.0, HINT_COLOR, fonts),
+                    Node { width: Val::Px(56.0), ..default() },
+                ));
+                let action = |delta| FlexButton::Motor { index, action: MotorAction::Step { param, delta } };
+                spawn_flex_button(param_row, "-", action(-step), fonts);
+                param_row.spawn((
+                    text_geist_regular_with_font("", 12.0, Color::WHITE, fonts),
+                    Node { width: Val::Px(48.0), ..default() },
+                    MotorValueText { index, param },
+                ));
+                spawn_flex_button(param_row, "+", action(step), fonts);
+            });
+        }
+    }
+    parent.spawn(row()).with_children(|add_row| {
+        for prop in MOTOR_PROPS {
+            spawn_flex_button(add_row, &format!("+ {}", prop.label()), FlexButton::AddMotor(prop), fonts);
+        }
+    });
+}
+
+/// Rebuilds the property rows when a different box is selected, its layout mode changes or another breakpoint applies
+pub fn rebuild_flex_panel(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    layout: Res<FlexLayout>,
+    mut shown_breakpoint: Local<Option<String>>,
+    contents: Query<Entity, With<FlexPanelContent>>,
+) {
+    let width = layout.root.x;
+    let breakpoint = document.breakpoint_at(width).map(|breakpoint| breakpoint.name.clone());
+    if !selection.is_changed() && *shown_breakpoint == breakpoint {
+        return;
+    }
+    *shown_breakpoint = breakpoint;
+    let Some(selected) = selection.selected.and_then(|id| document.get(id)) else {
+        return;
+    };
+    let is_root = selected.id == document.root;
+    let Some(style) = document.style_at(selected.id, width) else {
+        return;
+    };
+    let is_grid = |id: BoxId| document.style_at(id, width).is_some_and(|style| style.mode == LayoutMode::Grid);
+    let in_grid = document.parent(selected.id).is_some_and(is_grid);
+
+    for content in contents.iter() {
+        commands.entity(content).despawn_related::<Children>().with_children(|content| {
+            content.spawn(row()).with_children(|actions| {
+                spawn_flex_button(actions, "+ child", FlexButton::AddChild, &fonts);
+                if !is_root {
+                    spawn_flex_button(actions, "delete", FlexButton::Remove, &fonts);
+                }
+                spawn_flex_button(actions, "export html", FlexButton::Export(FlexExportFormat::Html), &fonts);
+                spawn_flex_button(actions, "export css", FlexButton::Export(FlexExportFormat::Css), &fonts);
+            });
+            content.spawn(row()).with_children(|options| {
+                for mode in LayoutMode::ALL {
+                    spawn_flex_button(options, &format!("{mode:?}"), FlexButton::Mode(mode), &fonts);
+                }
+            });
+            match style.mode {
+                LayoutMode::Grid => {
+                    for field in GridField::CONTAINER {
+                        spawn_text_field(content, TextField::Grid(field), &fonts);
+                    }
+                }
+                LayoutMode::Flex => {
+                    content.spawn(row()).with_children(|options| {
+                        for direction in Direction::ALL {
+                            spawn_flex_button(options, &format!("{direction:?}"), FlexButton::Direction(direction), &fonts);
+                        }
+                    });
+                    content.spawn(row()).with_children(|options| {
+                        for justify in Justify::ALL {
+                            spawn_flex_button(options, &format!("{justify:?}"), FlexButton::Justify(justify), &fonts);
+                        }
+                    });
+                }
+                LayoutMode::Constraint => spawn_constraint_rows(content, &style.constraints, &fonts),
+            }
+            if in_grid {
+                for field in GridField::ITEM {
+                    spawn_text_field(content, TextField::Grid(field), &fonts);
+                }
+            }
+            // Constrained children are placed by the solver, alignment has nothing to act on
+            if style.mode != LayoutMode::Constraint {
+                content.spawn(row()).with_children(|options| {
+                    for align in Align::ALL {
+                        spawn_flex_button(options, &format!("{align:?}"), FlexButton::Align(align), &fonts);
+                    }
+                });
+            }
+            for prop in FlexProp::ALL {
+                content.spawn(row()).with_children(|prop_row| {
+                    prop_row.spawn((
+                        text_geist_regular_with_font(prop.label(), 12.0, Color::WHITE, &fonts),
+                        Node { width: Val::Px(56.0), ..default() },
+                    ));
+                    spawn_flex_button(prop_row, "-", FlexButton::Step { prop, delta: -prop.step() }, &fonts);
+                    prop_row.spawn((
+                        text_geist_regular_with_font("", 12.0, Color::WHITE, &fonts),
+                        Node { width: Val::Px(48.0), ..default() },
+                        FlexValueText(prop),
+                    ));
+                    spawn_flex_button(prop_row, "+", FlexButton::Step { prop, delta: prop.step() }, &fonts);
+                    if prop.optional() {
+                        spawn_flex_button(prop_row, "auto", FlexButton::Auto(prop), &fonts);
+                    }
+                });
+            }
+            spawn_motor_rows(content, &selected.motors, &fonts);
+        });
+    }
+}
+
+pub fn flex_panel_buttons(
+    interaction_query: Query<(&Interaction, &FlexButton), Changed<Interaction>>,
+    mut document: ResMut<FlexDocument>,
+    mut selection: ResMut<FlexSelection>,
+    mut entry: ResMut<FlexTextEntry>,
+    mut viewport: ResMut<FlexViewport>,
+    layout: Res<FlexLayout>,
+) {
+    let Some(selected) = selection.selected else {
+        return;
+    };
+    // Edits land in the breakpoint the container is laid out in
+    let width = layout.root.x;
+    let breakpoint = document.breakpoint_at(width).map(|breakpoint| breakpoint.name.clone());
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            FlexButton::AddChild => {
+                let name = document.unique_name();
+                let child = document.add_child(selected, name);
+                if let Some(item) = document.get_mut(child) {
+                    item.style.grow = 1.0;
+                }
+                selection.selected = Some(child);
+            }
+            FlexButton::Remove => {
+                let parent = document.parent(selected);
+                document.remove(selected);
+                selection.selected = parent;
+            }
+            FlexButton::Mode(mode) => {
+                if document.style_at(selected, width).is_none_or(|style| style.mode == *mode) {
+                    continue;
+                }
+                let children = document.get(selected).map_or(0, |item| item.children.len());
+                let Some(style) = document.style_mut(selected, breakpoint.as_deref()) else {
+                    continue;
+                };
+                style.mode = *mode;
+                // A fresh grid gets one column per child so nothing jumps into implicit rows
+                if *mode == LayoutMode::Grid && style.grid == GridTemplate::default() {
+                    style.grid = GridTemplate::even(children.max(2));
+                }
+                // Mode decides which rows the panel shows
+                selection.set_changed();
+            }
+            FlexButton::Edit(field) => {
+                entry.field = Some(*field);
+                entry.buffer = field.get(&document, selected, width);
+            }
+            FlexButton::Viewport(preset) => viewport.width = *preset,
+            FlexButton::Priority(index) => {
+                let Some(constraint) = document
+                    .style_mut(selected, breakpoint.as_deref())
+                    .and_then(|style| style.constraints.get_mut(*index))
+                else {
+                    continue;
+                };
+                constraint.priority = constraint.priority.next();
+                // The button shows the priority as its label
+                selection.set_changed();
+            }
+            FlexButton::ClearOverride => {
+                let (Some(name), Some(item)) = (breakpoint.as_ref(), document.get_mut(selected)) else {
+                    continue;
+                };
+                if item.overrides.remove(name).is_some() {
+                    selection.set_changed();
+                }
+            }
+            FlexButton::AddMotor(prop) => {
+                // Motors swing around a fixed value, an auto size starts from the size the box has now
+                let current = layout.rects.get(&selected).map(|rect| match prop {
+                    FlexProp::Height => rect.height(),
+                    _ => rect.width(),
+                });
+                if let Some(style) = document.style_mut(selected, breakpoint.as_deref()) {
+                    if prop.get(style).is_none() {
+                        prop.set(style, current.map(f32::round));
+                    }
+                }
+                if let Some(item) = document.get_mut(selected) {
+                    item.motors.push(LayoutMotor::new(*prop));
+                }
+                // The motor gets its own rows
+                selection.set_changed();
+            }
+            FlexButton::Motor { index, action } => {
+                let Some(item) = document.get_mut(selected) else {
+                    continue;
+                };
+                match action {
+                    MotorAction::Remove if *index < item.motors.len() => {
+                        item.motors.remove(*index);
+                        selection.set_changed();
+                    }
+                    MotorAction::Waveform => {
+                        if let Some(motor) = item.motors.get_mut(*index) {
+                            motor.next_waveform();
+                            // The button shows the waveform as its label
+                            selection.set_changed();
+                        }
+                    }
+                    MotorAction::Step { param, delta } => {
+                        if let Some(motor) = item.motors.get_mut(*index) {
+                            param.set(motor, param.get(motor) + delta);
+                        }
+                    }
+                    MotorAction::Remove => {}
+                }
+            }
+            FlexButton::Export(format) => {
+                let stamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |duration| duration.as_secs());
+                let path = PathBuf::from(EXPORT_DIR).join(format!("flex_{stamp}.{}", format.extension()));
+                match save_flex_export(&document, *format, &path) {
+                    Ok(()) => log::info!("Exported layout to {}", path.display()),
+                    Err(err) => log::error!("Layout export to {} failed: {err}", path.display()),
+                }
+            }
+            FlexButton::Auto(prop) => {
+                // Going from auto to fixed starts from the size the box has now
+                let current = layout.rects.get(&selected).map(|rect| match prop {
+                    FlexProp::Height => rect.height(),
+                    _ => rect.width(),
+                });
+                let Some(style) = document.style_mut(selected, breakpoint.as_deref()) else {
+                    continue;
+                };
+                let value = match prop.get(style) {
+                    Some(_) => None,
+                    None => current.map(f32::round),
+                };
+                prop.set(style, value);
+            }
+            _ => {
+                let Some(style) = document.style_mut(selected, breakpoint.as_deref()) else {
+                    continue;
+                };
+                match action {
+                    FlexButton::Direction(direction) => style.direction = *direction,
+                    FlexButton::Justify(justify) => style.justify = *justify,
+                    FlexButton::Align(align) => style.align = *align,
+                    FlexButton::Step { prop, delta } => {
+                        let value = prop.get(style).unwrap_or(0.0) + delta;
+                        prop.set(style, Some(value));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the title and the breakpoint being edited in sync with the selection and container width
+pub fn refresh_panel_headings(
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    layout: Res<FlexLayout>,
+    mut headings: Query<(&mut Text, Has<FlexBreakpointText>), Or<(With<FlexPanelTitle>, With<FlexBreakpointText>)>>,
+) {
+    let selected = selection.selected.and_then(|id| document.get(id));
+    let width = layout.root.x;
+    let breakpoint = document.breakpoint_at(width);
+    for (mut text, is_breakpoint) in headings.iter_mut() {
+        let next = if !is_breakpoint {
+            selected.map_or("Box".to_string(), |item| format!("Box - {}", item.name))
+        } else {
+            let editing = match breakpoint {
+                Some(breakpoint) => {
+                    let overridden = selected.is_some_and(|item| item.overrides.contains_key(&breakpoint.name));
+                    format!(
+                        "editing {} (max {}px){}",
+                        breakpoint.name,
+                        breakpoint.max_width,
+                        if overridden { ", overridden" } else { "" }
+                    )
+                }
+                None => "editing base styles".to_string(),
+            };
+            format!("container {width:.0}px, {editing}")
+        };
+        if text.0 != next {
+            text.0 = next;
+        }
+    }
+}
+
+/// Diagnosis of each constraint of the selected box, and the values none of them decide
+pub fn refresh_constraint_status(
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    layout: Res<FlexLayout>,
+    mut texts: Query<(&mut Text, &mut TextColor, &ConstraintStatusText)>,
+) {
+    let report = selection.selected.and_then(|id| layout.constraints.get(&id));
+    for (mut text, mut color, status) in texts.iter_mut() {
+        let (next, next_color) = match (status.0, report) {
+            (Some(index), Some(report)) => match report.issues.get(index).cloned().flatten() {
+                Some(issue) => {
+                    let color = match issue {
+                        ConstraintIssue::Conflict | ConstraintIssue::Invalid(_) => CONFLICT_COLOR,
+                        ConstraintIssue::Unsatisfied => UNSATISFIED_COLOR,
+                    };
+                    (issue.to_string(), color)
+                }
+                None => ("ok".to_string(), HINT_COLOR),
+            },
+            (None, Some(report)) if !report.loose.is_empty() => {
+                let loose: Vec<String> = report
+                    .loose
+                    .iter()
+                    .map(|(id, attribute)| {
+                        let name = document.get(*id).map_or("?", |item| item.name.as_str());
+                        format!("{name}.{}", attribute.label())
+                    })
+                    .collect();
+                (format!("under-constrained: {}", loose.join(", ")), LOOSE_COLOR)
+            }
+            _ => (String::new(), HINT_COLOR),
+        };
+        if text.0 != next {
+            text.0 = next;
+        }
+        if color.0 != next_color {
+            color.0 = next_color;
+        }
+    }
+}
+
+/// Constrained boxes behind broken constraints and loose values, and those of the constraint under the pointer
+pub fn draw_constraint_issues(
+    mut gizmos: Gizmos,
+    layout: Res<FlexLayout>,
+    buttons: Query<(&Interaction, &FlexButton)>,
+    selection: Res<FlexSelection>,
+) {
+    let root = layout.root;
+    let mut outline = |id: &BoxId, inset: f32, color: Color| {
+        if let Some(rect) = layout.rects.get(id) {
+            gizmos.rect_2d(to_world(rect.center(), root), (rect.size() - Vec2::splat(inset)).max(Vec2::ZERO), color);
+        }
+    };
+    for report in layout.constraints.values() {
+        for (issue, boxes) in report.issues.iter().zip(&report.boxes) {
+            let color = match issue {
+                Some(ConstraintIssue::Conflict) => CONFLICT_COLOR,
+                Some(ConstraintIssue::Unsatisfied) => UNSATISFIED_COLOR,
+                _ => continue,
+            };
+            for id in boxes {
+                outline(id, 4.0, color);
+            }
+        }
+        for (id, _) in &report.loose {
+            outline(id, 8.0, LOOSE_COLOR);
+        }
+    }
+
+    let Some(report) = selection.selected.and_then(|id| layout.constraints.get(&id)) else {
+        return;
+    };
+    for (interaction, action) in buttons.iter() {
+        let FlexButton::Edit(TextField::Constraint(ConstraintField::Existing(index))) = action else {
+            continue;
+        };
+        if *interaction == Interaction::None {
+            continue;
+        }
+        for id in report.boxes.get(*index).into_iter().flatten() {
+            outline(id, 12.0, SELECTED_COLOR);
+        }
+    }
+}
+
+/// Keeps the value labels and option highlights in sync with the selected box
+pub fn refresh_flex_panel(
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    entry: Res<FlexTextEntry>,
+    layout: Res<FlexLayout>,
+    mut value_texts: Query<(&mut Text, &FlexValueText), Without<FlexFieldText>>,
+    mut field_texts: Query<(&mut Text, &FlexFieldText)>,
+    mut buttons: Query<(&Interaction, &mut BackgroundColor, &ButtonColors, &FlexButton)>,
+) {
+    let width = layout.root.x;
+    let Some((selected, style)) = selection
+        .selected
+        .and_then(|id| Some((id, document.style_at(id, width)?)))
+    else {
+        return;
+    };
+    for (mut text, field) in value_texts.iter_mut() {
+        text.0 = match field.0.get(style) {
+            Some(value) => format!("{value:.1}"),
+            None => "auto".to_string(),
+        };
+    }
+    for (mut text, field) in field_texts.iter_mut() {
+        let next = if entry.field == Some(field.0) {
+            format!("{}|", entry.buffer)
+        } else {
+            field.0.get(&document, selected, width)
+        };
+        if text.0 != next {
+            text.0 = next;
+        }
+    }
+    for (interaction, mut color, button_colors, action) in buttons.iter_mut() {
+        let active = match action {
+            FlexButton::Mode(mode) => style.mode == *mode,
+            FlexButton::Edit(field) => entry.field == Some(*field),
+            FlexButton::Direction(direction) => style.direction == *direction,
+            FlexButton::Justify(justify) => style.justify == *justify,
+            FlexButton::Align(align) => style.align == *align,
+            FlexButton::Auto(prop) => prop.get(style).is_none(),
+            FlexButton::Viewport(preset) => preset.unwrap_or_else(|| root_size(&document).x) == width,
+            _ => false,
+        };
+        let next = if active {
+            ACTIVE_COLOR
+        } else if *interaction == Interaction::Hovered {
+            button_colors.hovered
+        } else {
+            button_colors.normal
+        };
+        if color.0 != next {
+            color.0 = next;
+        }
+    }
+}
+
+/// Keeps the motor settings of the selected box in sync
+pub fn refresh_motor_values(
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    mut texts: Query<(&mut Text, &MotorValueText)>,
+) {
+    let Some(item) = selection.selected.and_then(|id| document.get(id)) else {
+        return;
+    };
+    for (mut text, value) in texts.iter_mut() {
+        let Some(motor) = item.motors.get(value.index) else {
+            continue;
+        };
+        let next = match value.param {
+            MotorParam::Freq => format!("{:.2}", value.param.get(motor)),
+            MotorParam::Phase => format!("{:.3}", value.param.get(motor)),
+            _ => format!("{:.1}", value.param.get(motor)),
+        };
+        if text.0 != next {
+            text.0 = next;
+        }
+    }
+}
+
+/// Typing into the grid property being edited; Enter parses and applies it, Escape cancels
+pub fn flex_text_entry(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut entry: ResMut<FlexTextEntry>,
+    mut document: ResMut<FlexDocument>,
+    mut selection: ResMut<FlexSelection>,
+    layout: Res<FlexLayout>,
+    mut focus: ResMut<KeyboardFocus>,
+) {
+    if selection.is_changed() {
+        entry.field = None;
+    }
+    let Some(field) = entry.field else {
+        focus.release(KEYBOARD_OWNER);
+        keyboard_events.clear();
+        return;
+    };
+    focus.claim(KEYBOARD_OWNER);
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(chars) => entry.buffer.extend(chars.chars().filter(|c| !c.is_control())),
+            Key::Space => entry.buffer.push(' '),
+            Key::Backspace => {
+                entry.buffer.pop();
+            }
+            Key::Escape => {
+                entry.field = None;
+                return;
+            }
+            Key::Enter => {
+                let breakpoint = document.breakpoint_at(layout.root.x).map(|breakpoint| breakpoint.name.clone());
+                let Some(selected) = selection.selected else {
+                    entry.field = None;
+                    return;
+                };
+                // A typo keeps the field open so it can be fixed
+                match field.set(&mut document, selected, breakpoint.as_deref(), &entry.buffer) {
+                    Ok(()) => {
+                        entry.field = None;
+                        // Adding or removing a constraint changes the panel's rows
+                        if matches!(field, TextField::Constraint(_)) {
+                            selection.set_changed();
+                        }
+                    }
+                    Err(err) => log::warn!("Invalid {}: {err}", field.label()),
+                }
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rebuilds the state buttons when states are added, removed or switched
+pub fn rebuild_state_buttons(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    mut shown: Local<Option<(Vec<String>, usize)>>,
+    contents: Query<(Entity, Ref<StateContent>)>,
+) {
+    let states = (
+        document.states.iter().map(|state| state.name.clone()).collect::<Vec<_>>(),
+        document.active_state,
+    );
+    let spawned = contents.iter().any(|(_, content)| content.is_added());
+    if shown.as_ref() == Some(&states) && !spawned {
+        return;
+    }
+    for (content, _) in contents.iter() {
+        commands.entity(content).despawn_related::<Children>().with_children(|content| {
+            for (index, name) in states.0.iter().enumerate() {
+                spawn_flex_button(content, name, StateButton::Switch(index), &fonts);
+            }
+            spawn_flex_button(content, "+ state", StateButton::Add, &fonts);
+            if states.0.len() > 1 {
+                spawn_flex_button(content, "delete state", StateButton::Remove, &fonts);
+                spawn_flex_button(content, "export frames", StateButton::ExportFrames, &fonts);
+            }
+        });
+    }
+    *shown = Some(states);
+}
+
+/// Switching or removing a state animates the boxes from where they are now
+pub fn state_buttons(
+    interaction_query: Query<(&Interaction, &StateButton), Changed<Interaction>>,
+    time: Res<Time>,
+    mut document: ResMut<FlexDocument>,
+    mut selection: ResMut<FlexSelection>,
+    mut settings: ResMut<LayoutTransitionSettings>,
+    mut transition: ResMut<LayoutTransition>,
+    layout: Res<FlexLayout>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let switched = match action {
+            StateButton::Switch(index) => document.switch_state(*index),
+            StateButton::Remove => document.remove_state(),
+            StateButton::Add => {
+                document.add_state();
+                false
+            }
+            StateButton::Easing => {
+                let current = EASINGS.iter().position(|(_, easing)| *easing == settings.easing);
+                let next = current.map_or(0, |index| (index + 1) % EASINGS.len());
+                settings.easing = EASINGS[next].1;
+                false
+            }
+            StateButton::Duration(delta) => {
+                settings.duration = ((settings.duration + delta) * 10.0).round().clamp(0.0, MAX_TRANSITION_DURATION * 10.0) / 10.0;
+                false
+            }
+            StateButton::ExportFrames => {
+                let from = document.active_state;
+                let to = (from + 1) % document.states.len().max(1);
+                let stamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |duration| duration.as_secs());
+                let dir = PathBuf::from(EXPORT_DIR).join(format!("flex_{stamp}_frames"));
+                match save_transition_frames(&document, from, to, layout.root.x, &settings, &dir) {
+                    Ok(frames) => log::info!("Exported {frames} transition frames to {}", dir.display()),
+                    Err(err) => log::error!("Transition export to {} failed: {err}", dir.display()),
+                }
+                false
+            }
+        };
+        if switched {
+            transition.running = Some(RunningTransition::new(time.elapsed_secs_f64(), layout.rects.clone()));
+            // The new state may change the selected box's mode, which decides the panel's rows
+            selection.set_changed();
+        }
+    }
+}
+
+/// Highlights the active state and shows the transition settings
+pub fn refresh_state_panel(
+    document: Res<FlexDocument>,
+    settings: Res<LayoutTransitionSettings>,
+    mut buttons: Query<(&Interaction, &mut BackgroundColor, &ButtonColors, &StateButton)>,
+    mut texts: Query<&mut Text, With<TransitionText>>,
+) {
+    for (interaction, mut color, button_colors, action) in buttons.iter_mut() {
+        let next = if *action == StateButton::Switch(document.active_state) {
+            ACTIVE_COLOR
+        } else if *interaction == Interaction::Hovered {
+            button_colors.hovered
+        } else {
+            button_colors.normal
+        };
+        if color.0 != next {
+            color.0 = next;
+        }
+    }
+    let easing = EASINGS
+        .iter()
+        .find(|(_, easing)| *easing == settings.easing)
+        .map_or("custom", |(name, _)| name);
+    let next = format!("{:.1}s, {easing}", settings.duration);
+    for mut text in texts.iter_mut() {
+        if text.0 != next {
+            text.0 = next.clone();
+        }
+    }
+}
+
+/// One outline row: indented by depth, expand toggle, name and visibility button
+fn spawn_outline_row(parent: &mut ChildSpawnerCommands, row: &OutlineRowState, fonts: &FontAssets) {
+    let drop_line = |after| {
+        (
+            Node {
+                height: Val::Px(2.0),
+                ..default()
+            },
+            BackgroundColor(Color::NONE),
+            OutlineDropLine { id: row.id, after },
+        )
+    };
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::NONE),
+            RelativeCursorPosition::default(),
+            OutlineRow(row.id),
+        ))
+        .with_children(|outline_row| {
+            outline_row.spawn(drop_line(false));
+            outline_row
+                .spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(4.0),
+                    padding: UiRect::left(Val::Px(row.depth as f32 * OUTLINE_INDENT)),
+                    ..default()
+                })
+                .with_children(|content| {
+                    let toggle = match (row.has_children, row.collapsed) {
+                        (false, _) => None,
+                        (true, false) => Some("-"),
+                        (true, true) => Some("+"),
+                    };
+                    match toggle {
+                        Some(label) => spawn_outline_button(content, label, OutlineButton::Toggle(row.id), fonts),
+                        None => {
+                            content.spawn(Node { width: Val::Px(16.0), ..default() });
+                        }
+                    }
+                    let color = if row.hidden { HINT_COLOR } else { Color::WHITE };
+                    content.spawn((
+                        text_geist_regular_with_font(&row.name, 12.0, color, fonts),
+                        Node { flex_grow: 1.0, ..default() },
+                        OutlineName(row.id),
+                    ));
+                    // The root is the page itself and always shown
+                    if row.depth > 0 {
+                        let visibility = if row.hidden { "show" } else { "hide" };
+                        spawn_outline_button(content, visibility, OutlineButton::Visibility(row.id), fonts);
+                    }
+                });
+            outline_row.spawn(drop_line(true));
+        });
+}
+
+fn spawn_outline_button(parent: &mut ChildSpawnerCommands, label: &str, action: OutlineButton, fonts: &FontAssets) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                min_width: Val::Px(16.0),
+                justify_content: JustifyContent::Center,
+                padding: UiRect::axes(Val::Px(4.0), Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(ButtonColors::default().normal),
+            BorderRadius::all(Val::Px(3.0)),
+            ButtonColors::default(),
+            action,
+        ))
+        .with_children(|button| {
+            button.spawn(text_geist_regular_with_font(label, 11.0, Color::WHITE, fonts));
+        });
+}
+
+/// Everything an outline row shows, the outline is rebuilt when any of it changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineRowState {
+    id: BoxId,
+    depth: usize,
+    name: String,
+    hidden: bool,
+    has_children: bool,
+    collapsed: bool,
+}
+
+/// Rows of the boxes whose parents are all expanded
+fn outline_rows(document: &FlexDocument, outline: &OutlineState) -> Vec<OutlineRowState> {
+    let mut rows = Vec::new();
+    // Depth of a collapsed box whose subtree is being skipped
+    let mut skip_below = None;
+    for (id, depth) in document.walk() {
+        if skip_below.is_some_and(|collapsed| depth > collapsed) {
+            continue;
+        }
+        skip_below = None;
+        let Some(item) = document.get(id) else {
+            continue;
+        };
+        let collapsed = outline.collapsed.contains(&id);
+        if collapsed {
+            skip_below = Some(depth);
+        }
+        rows.push(OutlineRowState {
+            id,
+            depth,
+            name: item.name.clone(),
+            hidden: item.hidden,
+            has_children: !item.children.is_empty(),
+            collapsed,
+        });
+    }
+    rows
+}
+
+/// Expands the parents of a box selected on the canvas and rebuilds the rows when the tree they show changes
+pub fn rebuild_outline(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    mut outline: ResMut<OutlineState>,
+    mut shown: Local<Vec<OutlineRowState>>,
+    contents: Query<(Entity, Ref<OutlineContent>)>,
+) {
+    if selection.is_changed() {
+        let mut parent = selection.selected.and_then(|id| document.parent(id));
+        while let Some(id) = parent {
+            if outline.collapsed.contains(&id) {
+                outline.collapsed.remove(&id);
+            }
+            parent = document.parent(id);
+        }
+    }
+    let rows = outline_rows(&document, &outline);
+    let spawned = contents.iter().any(|(_, content)| content.is_added());
+    if rows == *shown && !spawned {
+        return;
+    }
+    for (content, _) in contents.iter() {
+        commands.entity(content).despawn_related::<Children>().with_children(|content| {
+            for row in &rows {
+                spawn_outline_row(content, row, &fonts);
+            }
+        });
+    }
+    *shown = rows;
+}
+
+/// Shows the name being typed in the selected row
+pub fn refresh_outline_names(
+    document: Res<FlexDocument>,
+    selection: Res<FlexSelection>,
+    entry: Res<FlexTextEntry>,
+    mut names: Query<(&mut Text, &OutlineName)>,
+) {
+    for (mut text, name) in names.iter_mut() {
+        let next = if entry.field == Some(TextField::Name) && selection.selected == Some(name.0) {
+            format!("{}|", entry.buffer)
+        } else {
+            TextField::Name.get(&document, name.0, 0.0)
+        };
+        if text.0 != next {
+            text.0 = next;
+        }
+    }
+}
+
+/// Highlights the selected row, where a dragged box would land and the hovered buttons
+pub fn highlight_outline(
+    selection: Res<FlexSelection>,
+    drag: Res<OutlineDrag>,
+    mut rows: Query<(&OutlineRow, &mut BackgroundColor)>,
+    mut lines: Query<(&OutlineDropLine, &mut BackgroundColor), Without<OutlineRow>>,
+    mut buttons: Query<
+        (&Interaction, &ButtonColors, &mut BackgroundColor),
+        (With<OutlineButton>, Without<OutlineRow>, Without<OutlineDropLine>),
+    >,
+) {
+    for (row, mut color) in rows.iter_mut() {
+        let next = if drag.target == Some((row.0, DropPlace::Into)) {
+            DROP_COLOR
+        } else if selection.selected == Some(row.0) {
+            ACTIVE_COLOR
+        } else {
+            Color::NONE
+        };
+        if color.0 != next {
+            color.0 = next;
+        }
+    }
+    for (line, mut color) in lines.iter_mut() {
+        let place = if line.after { DropPlace::After } else { DropPlace::Before };
+        let next = if drag.target == Some((line.id, place)) {
+            SELECTED_COLOR
+        } else {
+            Color::NONE
+        };
+        if color.0 != next {
+            color.0 = next;
+        }
+    }
+    for (interaction, button_colors, mut color) in buttons.iter_mut() {
+        let next = if *interaction == Interaction::Hovered {
+            button_colors.hovered
+        } else {
+            button_colors.normal
+        };
+        if color.0 != next {
+            color.0 = next;
+        }
+    }
+}
+
+/// Replaces the document with an HTML file dropped on the window, logging whatever the import skipped
+pub fn import_dropped_layouts(
+    mut events: EventReader<FileDragAndDrop>,
+    mut document: ResMut<FlexDocument>,
+    mut selection: ResMut<FlexSelection>,
+) {
+    for event in events.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = event else {
+            continue;
+        };
+        if !path_buf.extension().is_some_and(|extension| extension == "html" || extension == "htm") {
+            log::warn!("Only .html files can be imported, ignoring {}", path_buf.display());
+            continue;
+        }
+        match load_flex_import(path_buf) {
+            Ok(import) => {
+                for warning in &import.warnings {
+                    log::warn!("Import {}: {warning}", path_buf.display());
+                }
+                log::info!(
+                    "Imported {} boxes from {} with {} warnings",
+                    import.document.boxes.len(),
+                    path_buf.display(),
+                    import.warnings.len()
+                );
+                selection.selected = Some(import.document.root);
+                *document = import.document;
+            }
+            Err(err) => log::error!("Import of {} failed: {err}", path_buf.display()),
+        }
+    }
+}
+
+pub fn cleanup_flex_grid(
+    mut commands: Commands,
+    entities: Query<Entity, With<FlexGridEntity>>,
+    mut entry: ResMut<FlexTextEntry>,
+    mut focus: ResMut<KeyboardFocus>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    entry.field = None;
+    focus.release(KEYBOARD_OWNER);
+}
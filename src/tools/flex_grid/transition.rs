@@ -0,0 +1,143 @@
+//! Animates boxes from the rectangles one layout state gives them to those of another
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::tools::flex_grid::document::{BoxId, FlexDocument};
+use crate::tools::flex_grid::layout::compute_layout;
+use crate::tools::flex_grid::resources::LayoutTransitionSettings;
+
+/// Rectangles `t` of the way from `from` to `to`
+///
+/// Boxes only one side shows grow out of or shrink into their centre.
+pub fn blend_rects(from: &HashMap<BoxId, Rect>, to: &HashMap<BoxId, Rect>, t: f32) -> HashMap<BoxId, Rect> {
+    let scaled = |rect: &Rect, amount: f32| Rect::from_center_size(rect.center(), rect.size() * amount.max(0.0));
+    let mut rects: HashMap<BoxId, Rect> = to
+        .iter()
+        .map(|(id, end)| {
+            let rect = match from.get(id) {
+                Some(start) => Rect {
+                    min: start.min.lerp(end.min, t),
+                    max: start.max.lerp(end.max, t),
+                },
+                None => scaled(end, t),
+            };
+            (*id, rect)
+        })
+        .collect();
+    if t < 1.0 {
+        for (id, start) in from {
+            rects.entry(*id).or_insert_with(|| scaled(start, 1.0 - t));
+        }
+    }
+    rects
+}
+
+/// Switch between states being animated
+#[derive(Debug, Clone)]
+pub struct RunningTransition {
+    /// Virtual time the switch happened at, in seconds
+    pub started: f64,
+    /// Where the boxes were when the switch happened
+    pub from: HashMap<BoxId, Rect>,
+    /// Layout of the new state, taken once it has been computed
+    pub to: Option<HashMap<BoxId, Rect>>,
+}
+
+impl RunningTransition {
+    pub fn new(started: f64, from: HashMap<BoxId, Rect>) -> Self {
+        Self { started, from, to: None }
+    }
+
+    /// Eased progress, 1 once the transition is over; overshooting easings go past 1 on the way
+    pub fn progress(&self, now: f64, settings: &LayoutTransitionSettings) -> f32 {
+        if settings.duration <= 0.0 {
+            return 1.0;
+        }
+        let linear = ((now - self.started) as f32 / settings.duration).clamp(0.0, 1.0);
+        settings.easing.sample_clamped(linear)
+    }
+
+    pub fn finished(&self, now: f64, settings: &LayoutTransitionSettings) -> bool {
+        (now - self.started) as f32 >= settings.duration
+    }
+}
+
+/// Box rectangles for every frame of the switch from state `from` to state `to`, at `fps` frames per second
+///
+/// Both ends are included, so even an instant switch has two frames.
+pub fn transition_frames(
+    document: &FlexDocument,
+    from: usize,
+    to: usize,
+    width: f32,
+    settings: &LayoutTransitionSettings,
+    fps: f32,
+) -> Option<Vec<HashMap<BoxId, Rect>>> {
+    if from >= document.states.len() || to >= document.states.len() {
+        return None;
+    }
+    let mut document = document.clone();
+    let mut layout_of = |index: usize| {
+        document.switch_state(index);
+        compute_layout(&document, width).rects
+    };
+    let start = layout_of(from);
+    let end = layout_of(to);
+    let steps = (settings.duration * fps).ceil().max(1.0) as usize;
+    let frames = (0..=steps)
+        .map(|step| {
+            let linear = step as f32 / steps as f32;
+            blend_rects(&start, &end, settings.easing.sample_clamped(linear))
+        })
+        .collect();
+    Some(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rects(entries: &[(u32, Rect)]) -> HashMap<BoxId, Rect> {
+        entries.iter().map(|(id, rect)| (BoxId(*id), *rect)).collect()
+    }
+
+    #[test]
+    fn blend_moves_shared_boxes_and_scales_the_rest() {
+        let from = rects(&[(1, Rect::new(0.0, 0.0, 10.0, 10.0)), (3, Rect::new(0.0, 0.0, 10.0, 10.0))]);
+        let to = rects(&[(1, Rect::new(10.0, 10.0, 30.0, 30.0)), (2, Rect::new(0.0, 0.0, 20.0, 20.0))]);
+
+        let half = blend_rects(&from, &to, 0.5);
+        assert_eq!(half[&BoxId(1)], Rect::new(5.0, 5.0, 20.0, 20.0));
+        // Appearing boxes grow out of their centre, leaving ones shrink into it
+        assert_eq!(half[&BoxId(2)], Rect::new(5.0, 5.0, 15.0, 15.0));
+        assert_eq!(half[&BoxId(3)], Rect::new(2.5, 2.5, 7.5, 7.5));
+
+        let end = blend_rects(&from, &to, 1.0);
+        assert_eq!(end, to);
+    }
+
+    #[test]
+    fn frames_run_from_one_state_to_the_other() {
+        let mut document = FlexDocument::default();
+        let sidebar = document.named("sidebar");
+        document.add_state();
+        document.get_mut(sidebar).unwrap().style.basis = Some(400.0);
+        let settings = LayoutTransitionSettings {
+            duration: 0.5,
+            easing: EaseFunction::Linear,
+        };
+
+        let frames = transition_frames(&document, 0, 1, 1280.0, &settings, 10.0).unwrap();
+        assert_eq!(frames.len(), 6);
+        let mut end = document.clone();
+        assert_eq!(frames[5], compute_layout(&end, 1280.0).rects);
+        end.switch_state(0);
+        assert_eq!(frames[0], compute_layout(&end, 1280.0).rects);
+        let width = |frame: &HashMap<BoxId, Rect>| frame[&sidebar].width();
+        assert!(frames.windows(2).all(|pair| width(&pair[0]) < width(&pair[1])));
+
+        assert!(transition_frames(&document, 0, 2, 1280.0, &settings, 10.0).is_none());
+    }
+}
@@ -1,8 +1,22 @@
 pub mod tile_map_grid;
 pub mod line_grid;
 pub mod motors;
+pub mod motor_graph;
+pub mod motor_inspector;
+pub mod motor_export;
+pub mod motor_audio;
+pub mod motor_scope;
 pub mod flex_grid;
+pub mod transport;
+pub mod osc;
 
 pub use tile_map_grid::TileMapGridPlugin;
 pub use motors::MotorsPlugin;
+pub use motor_graph::MotorGraphPlugin;
+pub use motor_inspector::MotorInspectorPlugin;
+pub use motor_export::MotorExportPlugin;
+pub use motor_audio::MotorAudioPlugin;
+pub use motor_scope::MotorScopePlugin;
 pub use flex_grid::FlexGridPlugin;
+pub use transport::TransportPlugin;
+pub use osc::OscPlugin;
@@ -0,0 +1,266 @@
+use std::io::Cursor;
+
+use bevy::prelude::*;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+/// Analysis frames per second of audio
+pub const ANALYSIS_RATE: f32 = 60.0;
+/// Samples per FFT window
+const WINDOW_SIZE: usize = 1024;
+pub const BAND_COUNT: usize = 16;
+const LOWEST_BAND_HZ: f32 = 40.0;
+const HIGHEST_BAND_HZ: f32 = 16_000.0;
+
+#[derive(Debug)]
+pub enum AudioDecodeError {
+    Wav(hound::Error),
+    Ogg(lewton::VorbisError),
+    UnsupportedFormat(String),
+    Empty,
+}
+
+impl std::fmt::Display for AudioDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioDecodeError::Wav(err) => write!(f, "could not decode wav: {err}"),
+            AudioDecodeError::Ogg(err) => write!(f, "could not decode ogg: {err}"),
+            AudioDecodeError::UnsupportedFormat(ext) => write!(f, "unsupported audio format '{ext}'"),
+            AudioDecodeError::Empty => write!(f, "audio file has no samples"),
+        }
+    }
+}
+
+impl std::error::Error for AudioDecodeError {}
+
+/// Decoded audio mixed down to one channel, samples in [-1, 1]
+pub struct MonoSamples {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+/// Decodes a `wav` or `ogg` file held in memory
+pub fn decode_audio(bytes: &[u8], extension: &str) -> Result<MonoSamples, AudioDecodeError> {
+    let decoded = match extension.to_ascii_lowercase().as_str() {
+        "wav" => decode_wav(bytes)?,
+        "ogg" => decode_ogg(bytes)?,
+        other => return Err(AudioDecodeError::UnsupportedFormat(other.to_string())),
+    };
+    if decoded.samples.is_empty() || decoded.sample_rate == 0 {
+        return Err(AudioDecodeError::Empty);
+    }
+    Ok(decoded)
+}
+
+fn decode_wav(bytes: &[u8]) -> Result<MonoSamples, AudioDecodeError> {
+    let mut reader = hound::WavReader::new(Cursor::new(bytes)).map_err(AudioDecodeError::Wav)?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>().map_err(AudioDecodeError::Wav)?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 * scale))
+                .collect::<Result<_, _>>()
+                .map_err(AudioDecodeError::Wav)?
+        }
+    };
+    Ok(MonoSamples {
+        sample_rate: spec.sample_rate,
+        samples: mix_down(&interleaved, spec.channels as usize),
+    })
+}
+
+fn decode_ogg(bytes: &[u8]) -> Result<MonoSamples, AudioDecodeError> {
+    let mut reader = lewton::inside_ogg::OggStreamReader::new(Cursor::new(bytes)).map_err(AudioDecodeError::Ogg)?;
+    let channels = reader.ident_hdr.audio_channels as usize;
+    let mut interleaved = Vec::new();
+    while let Some(packet) = reader.read_dec_packet_itl().map_err(AudioDecodeError::Ogg)? {
+        interleaved.extend(packet.into_iter().map(|sample| sample as f32 / 32768.0));
+    }
+    Ok(MonoSamples {
+        sample_rate: reader.ident_hdr.audio_sample_rate,
+        samples: mix_down(&interleaved, channels),
+    })
+}
+
+fn mix_down(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    let channels = channels.max(1);
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Amplitude envelope and band energies of an audio file, sampled at [`ANALYSIS_RATE`]
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct AudioAnalysis {
+    pub duration: f32,
+    /// Loudness per frame in [0, 1], relative to the loudest frame
+    pub envelope: Vec<f32>,
+    /// Energy per frame and band in [0, 1], each band relative to its own peak, low to high frequencies
+    pub bands: Vec<[f32; BAND_COUNT]>,
+}
+
+impl AudioAnalysis {
+    pub fn new(audio: &MonoSamples) -> Self {
+        let sample_rate = audio.sample_rate as f32;
+        let hop = (sample_rate / ANALYSIS_RATE).max(1.0);
+        let frame_count = (audio.samples.len() as f32 / hop).ceil() as usize;
+        let band_edges = band_edges(sample_rate);
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(WINDOW_SIZE);
+        let hann: Vec<f32> = (0..WINDOW_SIZE)
+            .map(|i| 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / WINDOW_SIZE as f32).cos())
+            .collect();
+
+        let mut envelope = Vec::with_capacity(frame_count);
+        let mut bands = Vec::with_capacity(frame_count);
+        let mut buffer = vec![Complex::default(); WINDOW_SIZE];
+        for frame in 0..frame_count {
+            let center = (frame as f32 * hop) as isize;
+            let sample_at = |i: isize| {
+                usize::try_from(i).ok().and_then(|i| audio.samples.get(i)).copied().unwrap_or(0.0)
+            };
+
+            // RMS over one hop around the frame
+            let half_hop = (hop / 2.0) as isize;
+            let square_sum: f32 = (center - half_hop..center + half_hop).map(|i| sample_at(i).powi(2)).sum();
+            envelope.push((square_sum / (2 * half_hop).max(1) as f32).sqrt());
+
+            let start = center - WINDOW_SIZE as isize / 2;
+            for (i, value) in buffer.iter_mut().enumerate() {
+                *value = Complex::new(sample_at(start + i as isize) * hann[i], 0.0);
+            }
+            fft.process(&mut buffer);
+            let mut energies = [0.0; BAND_COUNT];
+            for (band, energy) in energies.iter_mut().enumerate() {
+                let (low, high) = (band_edges[band], band_edges[band + 1]);
+                let magnitude: f32 = buffer[low..high].iter().map(|bin| bin.norm()).sum::<f32>() / (high - low) as f32;
+                // Log compression keeps quiet bands visible next to the bass
+                *energy = magnitude.ln_1p();
+            }
+            bands.push(energies);
+        }
+
+        normalize(&mut envelope);
+        for band in 0..BAND_COUNT {
+            let peak = bands.iter().map(|energies| energies[band]).fold(0.0, f32::max);
+            if peak > 0.0 {
+                for energies in bands.iter_mut() {
+                    energies[band] /= peak;
+                }
+            }
+        }
+
+        Self {
+            duration: audio.samples.len() as f32 / sample_rate,
+            envelope,
+            bands,
+        }
+    }
+
+    /// Envelope at `t` seconds, 0 before the start and after the end
+    pub fn envelope_at(&self, t: f32) -> f32 {
+        interpolate(t, self.envelope.len(), |frame| self.envelope[frame])
+    }
+
+    /// Energy of `band` at `t` seconds, 0 before the start and after the end
+    pub fn band_at(&self, band: usize, t: f32) -> f32 {
+        let band = band.min(BAND_COUNT - 1);
+        interpolate(t, self.bands.len(), |frame| self.bands[frame][band])
+    }
+}
+
+/// FFT bin boundaries of log-spaced bands, every band gets at least one bin
+fn band_edges(sample_rate: f32) -> [usize; BAND_COUNT + 1] {
+    let bin_hz = sample_rate / WINDOW_SIZE as f32;
+    let high = HIGHEST_BAND_HZ.min(sample_rate / 2.0);
+    let mut edges = [0; BAND_COUNT + 1];
+    for (i, edge) in edges.iter_mut().enumerate() {
+        let hz = LOWEST_BAND_HZ * (high / LOWEST_BAND_HZ).powf(i as f32 / BAND_COUNT as f32);
+        *edge = ((hz / bin_hz).round() as usize).clamp(1, WINDOW_SIZE / 2);
+    }
+    for i in 1..edges.len() {
+        edges[i] = edges[i].max(edges[i - 1] + 1);
+    }
+    edges
+}
+
+fn normalize(values: &mut [f32]) {
+    let peak = values.iter().copied().fold(0.0, f32::max);
+    if peak > 0.0 {
+        values.iter_mut().for_each(|value| *value /= peak);
+    }
+}
+
+fn interpolate(t: f32, len: usize, value_at: impl Fn(usize) -> f32) -> f32 {
+    let position = t * ANALYSIS_RATE;
+    if len == 0 || position < 0.0 || position > (len - 1) as f32 {
+        return 0.0;
+    }
+    let frame = position.floor() as usize;
+    let next = (frame + 1).min(len - 1);
+    let blend = position - frame as f32;
+    value_at(frame) * (1.0 - blend) + value_at(next) * blend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44_100;
+
+    fn sine(hz: f32, seconds: f32, amplitude: impl Fn(f32) -> f32) -> Vec<f32> {
+        (0..(seconds * SAMPLE_RATE as f32) as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                amplitude(t / seconds) * (std::f32::consts::TAU * hz * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn envelope_follows_the_loudness() {
+        let audio = MonoSamples {
+            sample_rate: SAMPLE_RATE,
+            samples: sine(440.0, 1.0, |progress| progress),
+        };
+        let analysis = AudioAnalysis::new(&audio);
+        assert_eq!(analysis.duration, 1.0);
+        assert_eq!(analysis.envelope.len(), ANALYSIS_RATE as usize);
+        assert_eq!(analysis.envelope.iter().copied().fold(0.0, f32::max), 1.0);
+        assert!(analysis.envelope_at(0.1) < analysis.envelope_at(0.5));
+        assert!(analysis.envelope_at(0.5) < analysis.envelope_at(0.9));
+    }
+
+    #[test]
+    fn bands_pick_out_the_frequencies_playing() {
+        // Low tone for the first half second, high tone for the second
+        let mut samples = sine(100.0, 0.5, |_| 1.0);
+        samples.extend(sine(3_000.0, 0.5, |_| 1.0));
+        let analysis = AudioAnalysis::new(&MonoSamples {
+            sample_rate: SAMPLE_RATE,
+            samples,
+        });
+        let (low, high) = (2, 11);
+        assert!(analysis.band_at(low, 0.25) > 0.9 && analysis.band_at(low, 0.75) < 0.1);
+        assert!(analysis.band_at(high, 0.75) > 0.9 && analysis.band_at(high, 0.25) < 0.1);
+    }
+
+    #[test]
+    fn frames_are_blended_and_silent_outside_the_file() {
+        let analysis = AudioAnalysis {
+            duration: 3.0 / ANALYSIS_RATE,
+            envelope: vec![0.0, 1.0, 0.5],
+            bands: vec![[0.0; BAND_COUNT]; 3],
+        };
+        let at_frame = |frame: f32| analysis.envelope_at(frame / ANALYSIS_RATE);
+        assert!((at_frame(0.5) - 0.5).abs() < 1e-5);
+        assert!((at_frame(1.5) - 0.75).abs() < 1e-5);
+        assert!((at_frame(2.0) - 0.5).abs() < 1e-5);
+        assert_eq!(at_frame(-0.5), 0.0);
+        assert_eq!(at_frame(2.5), 0.0);
+        assert_eq!(AudioAnalysis::default().envelope_at(0.0), 0.0);
+    }
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+use crate::tools::motor_audio::analysis::{AudioAnalysis, BAND_COUNT};
+use crate::tools::motors::MotorTarget;
+
+/// Which part of the analysis a cell follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioMapping {
+    /// Columns spread across the bands, bass on the left
+    #[default]
+    ColumnBands,
+    Envelope,
+    Band(usize),
+}
+
+impl AudioMapping {
+    pub fn next(self) -> Self {
+        match self {
+            AudioMapping::ColumnBands => AudioMapping::Envelope,
+            AudioMapping::Envelope => AudioMapping::Band(0),
+            AudioMapping::Band(band) if band + 1 < BAND_COUNT => AudioMapping::Band(band + 1),
+            AudioMapping::Band(_) => AudioMapping::ColumnBands,
+        }
+    }
+
+    /// Analysis value in [0, 1] for a cell in column `col` of `columns`
+    pub fn value(self, analysis: &AudioAnalysis, t: f32, col: usize, columns: usize) -> f32 {
+        match self {
+            AudioMapping::ColumnBands => analysis.band_at(col * BAND_COUNT / columns.max(1), t),
+            AudioMapping::Envelope => analysis.envelope_at(t),
+            AudioMapping::Band(band) => analysis.band_at(band, t),
+        }
+    }
+}
+
+/// Drives one property of a grid cell from an analysed audio file, following the transport
+#[derive(Component, Debug, Clone)]
+pub struct AudioMotor {
+    pub analysis: Handle<AudioAnalysis>,
+    pub mapping: AudioMapping,
+    pub target: MotorTarget,
+    pub gain: f32,
+}
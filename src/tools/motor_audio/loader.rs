@@ -0,0 +1,76 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+
+use crate::tools::motor_audio::analysis::{decode_audio, AudioAnalysis, AudioDecodeError};
+
+/// Folder (relative to `assets/`) audio motors pick their files from
+pub const AUDIO_DIR: &str = "audio";
+const AUDIO_EXTENSIONS: [&str; 2] = ["wav", "ogg"];
+
+#[derive(Debug)]
+pub enum AudioAnalysisLoaderError {
+    Io(std::io::Error),
+    Decode(AudioDecodeError),
+}
+
+impl std::fmt::Display for AudioAnalysisLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioAnalysisLoaderError::Io(err) => write!(f, "could not read audio file: {err}"),
+            AudioAnalysisLoaderError::Decode(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for AudioAnalysisLoaderError {}
+
+/// Decodes `*.wav` / `*.ogg` files and analyses them into [`AudioAnalysis`] assets, no audio device involved
+#[derive(Default)]
+pub struct AudioAnalysisLoader;
+
+impl AssetLoader for AudioAnalysisLoader {
+    type Asset = AudioAnalysis;
+    type Settings = ();
+    type Error = AudioAnalysisLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<AudioAnalysis, AudioAnalysisLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(AudioAnalysisLoaderError::Io)?;
+        let extension = load_context
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let audio = decode_audio(&bytes, &extension).map_err(AudioAnalysisLoaderError::Decode)?;
+        Ok(AudioAnalysis::new(&audio))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &AUDIO_EXTENSIONS
+    }
+}
+
+/// Asset paths of the audio files in `assets/audio`, sorted alphabetically
+pub fn audio_files() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(std::path::Path::new("assets").join(AUDIO_DIR)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_owned))
+        .filter(|name| {
+            std::path::Path::new(name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        })
+        .map(|name| format!("{AUDIO_DIR}/{name}"))
+        .collect();
+    files.sort();
+    files
+}
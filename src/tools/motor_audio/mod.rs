@@ -0,0 +1,12 @@
+mod analysis;
+mod components;
+mod loader;
+mod plugin;
+mod resources;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use plugin::MotorAudioPlugin;
+
+pub use analysis::AudioAnalysis;
+pub use components::{AudioMapping, AudioMotor};
@@ -0,0 +1,31 @@
+use crate::tools::motor_audio::analysis::AudioAnalysis;
+use crate::tools::motor_audio::loader::AudioAnalysisLoader;
+use crate::tools::motor_audio::resources::AudioMotorSettings;
+use crate::tools::motor_audio::systems::{
+    audio_motor_shortcuts, audio_motors_update, load_audio_files, report_audio_analysis,
+};
+use crate::tools::motors::motors_update;
+use crate::ui::keyboard_free;
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct MotorAudioPlugin;
+
+impl Plugin for MotorAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<AudioAnalysis>()
+            .init_asset_loader::<AudioAnalysisLoader>()
+            .init_resource::<AudioMotorSettings>()
+            .add_systems(OnEnter(GameState::GridAndMotors), load_audio_files)
+            .add_systems(
+                Update,
+                (
+                    audio_motor_shortcuts.run_if(keyboard_free),
+                    report_audio_analysis,
+                    audio_motors_update.after(motors_update),
+                )
+                    .run_if(in_state(GameState::GridAndMotors)),
+            );
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+use crate::tools::motor_audio::analysis::AudioAnalysis;
+use crate::tools::motor_audio::components::AudioMapping;
+use crate::tools::motors::MotorTarget;
+
+/// Audio file and mapping used when attaching audio motors
+#[derive(Resource, Debug, Clone)]
+pub struct AudioMotorSettings {
+    /// Asset paths found in `assets/audio`
+    pub files: Vec<String>,
+    pub current: usize,
+    pub analysis: Option<Handle<AudioAnalysis>>,
+    pub mapping: AudioMapping,
+    pub target: MotorTarget,
+    pub gain: f32,
+}
+
+impl Default for AudioMotorSettings {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            current: 0,
+            analysis: None,
+            mapping: AudioMapping::default(),
+            target: MotorTarget::Scale,
+            gain: 1.0,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::tools::motor_audio::analysis::AudioAnalysis;
+use crate::tools::motor_audio::components::AudioMotor;
+use crate::tools::motor_audio::loader::audio_files;
+use crate::tools::motor_audio::resources::AudioMotorSettings;
+use crate::tools::motors::{MotorTarget, Motors, MOTOR_BASE_COLOR};
+use crate::tools::tile_map_grid::components::{GridCell, MainCell, SelectedCell};
+use crate::tools::transport::Transport;
+
+pub fn load_audio_files(mut settings: ResMut<AudioMotorSettings>, asset_server: Res<AssetServer>) {
+    settings.files = audio_files();
+    settings.current = settings.current.min(settings.files.len().saturating_sub(1));
+    settings.analysis = settings.files.get(settings.current).map(|path| asset_server.load(path.clone()));
+    if settings.files.is_empty() {
+        log::info!("No audio files in assets/audio, audio motors are unavailable");
+    }
+}
+
+/// A toggles an audio motor on the selection, Shift+A switches file,
+/// B cycles the band mapping and Shift+B the target of every audio motor
+pub fn audio_motor_shortcuts(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<AudioMotorSettings>,
+    asset_server: Res<AssetServer>,
+    selected: Query<(Entity, Option<&AudioMotor>), (With<SelectedCell>, With<GridCell>)>,
+    mut audio_motors: Query<&mut AudioMotor>,
+) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if keyboard.just_pressed(KeyCode::KeyA) && shift {
+        if settings.files.is_empty() {
+            return;
+        }
+        settings.current = (settings.current + 1) % settings.files.len();
+        let handle: Handle<AudioAnalysis> = asset_server.load(settings.files[settings.current].clone());
+        log::info!("Audio motors follow {}", settings.files[settings.current]);
+        for mut audio_motor in audio_motors.iter_mut() {
+            audio_motor.analysis = handle.clone();
+        }
+        settings.analysis = Some(handle);
+    } else if keyboard.just_pressed(KeyCode::KeyA) {
+        let Some(analysis) = settings.analysis.clone() else {
+            log::info!("Add a .wav or .ogg file to assets/audio to use audio motors");
+            return;
+        };
+        for (entity, existing) in selected.iter() {
+            if existing.is_some() {
+                commands.entity(entity).remove::<AudioMotor>();
+            } else {
+                commands.entity(entity).insert(AudioMotor {
+                    analysis: analysis.clone(),
+                    mapping: settings.mapping,
+                    target: settings.target,
+                    gain: settings.gain,
+                });
+            }
+        }
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyB) {
+        if shift {
+            let index = MotorTarget::ALL.iter().position(|target| *target == settings.target).unwrap_or(0);
+            settings.target = MotorTarget::ALL[(index + 1) % MotorTarget::ALL.len()];
+            log::info!("Audio motor target: {:?}", settings.target);
+        } else {
+            settings.mapping = settings.mapping.next();
+            log::info!("Audio motor mapping: {:?}", settings.mapping);
+        }
+        for mut audio_motor in audio_motors.iter_mut() {
+            audio_motor.mapping = settings.mapping;
+            audio_motor.target = settings.target;
+        }
+    }
+}
+
+/// Logs once when the current file has been analysed or failed to load
+pub fn report_audio_analysis(
+    settings: Res<AudioMotorSettings>,
+    analyses: Res<Assets<AudioAnalysis>>,
+    asset_server: Res<AssetServer>,
+    mut reported: Local<bool>,
+) {
+    if settings.is_changed() {
+        *reported = false;
+    }
+    let Some(handle) = settings.analysis.as_ref() else {
+        return;
+    };
+    if *reported {
+        return;
+    }
+    if let Some(analysis) = analyses.get(handle) {
+        log::info!(
+            "Analysed {}: {:.1}s, {} frames",
+            settings.files[settings.current],
+            analysis.duration,
+            analysis.envelope.len()
+        );
+        *reported = true;
+    } else if let Some(LoadState::Failed(err)) = asset_server.get_load_state(handle) {
+        log::warn!("Audio analysis failed: {err}");
+        *reported = true;
+    }
+}
+
+/// Writes the analysed value on top of whatever the cell's motor channels produced this frame
+pub fn audio_motors_update(
+    analyses: Res<Assets<AudioAnalysis>>,
+    transport: Res<Transport>,
+    mut cells: Query<(&AudioMotor, &GridCell, Option<&Motors>, &mut Sprite, &mut Transform)>,
+    grid: Query<&GridCell, With<MainCell>>,
+) {
+    // Spectrum bands spread over the whole grid, not just the columns that have audio motors
+    let columns = grid.iter().map(|cell| cell.col + 1).max().unwrap_or(1);
+    let t = transport.seconds();
+    for (audio_motor, cell, motors, mut sprite, mut transform) in cells.iter_mut() {
+        let Some(analysis) = analyses.get(&audio_motor.analysis) else {
+            continue;
+        };
+        let value = audio_motor.mapping.value(analysis, t, cell.col, columns) * audio_motor.gain;
+        let mut color = if motors.is_some() { Hsla::from(sprite.color) } else { MOTOR_BASE_COLOR };
+        audio_motor.target.apply(value, &mut color, &mut transform);
+        sprite.color = color.into();
+    }
+}
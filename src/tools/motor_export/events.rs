@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+use crate::tools::motor_export::export::ExportSettings;
+
+/// Renders the current grid and its motors to disk
+#[derive(Event, Debug, Clone)]
+pub struct ExportMotorAnimation(pub ExportSettings);
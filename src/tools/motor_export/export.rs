@@ -0,0 +1,158 @@
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame};
+use std::path::{Path, PathBuf};
+
+use crate::tools::motor_export::raster::GridSnapshot;
+use crate::tools::transport::Transport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// Single looping animated GIF
+    #[default]
+    Gif,
+    /// Numbered `frame_0000.png` files in a folder
+    PngSequence,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportSettings {
+    pub format: ExportFormat,
+    pub fps: u32,
+    /// Transport time range in seconds, end exclusive
+    pub start: f64,
+    pub end: f64,
+    pub width: u32,
+    pub height: u32,
+    /// GIF file, or the folder PNG frames are written into
+    pub output: PathBuf,
+}
+
+impl ExportSettings {
+    pub fn frame_count(&self) -> u32 {
+        if self.fps == 0 || self.end <= self.start {
+            return 0;
+        }
+        ((self.end - self.start) * self.fps as f64).ceil() as u32
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    EmptyRange,
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "could not write export: {err}"),
+            ExportError::Image(err) => write!(f, "could not encode export: {err}"),
+            ExportError::EmptyRange => write!(f, "export range contains no frames"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(err: std::io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<image::ImageError> for ExportError {
+    fn from(err: image::ImageError) -> Self {
+        ExportError::Image(err)
+    }
+}
+
+/// Steps a copy of `transport` through the range and writes every frame, returning the frame count
+///
+/// Runs entirely on the CPU, so it needs neither a window nor a GPU.
+pub fn export_animation(
+    snapshot: &GridSnapshot,
+    transport: &Transport,
+    settings: &ExportSettings,
+) -> Result<u32, ExportError> {
+    let frame_count = settings.frame_count();
+    if frame_count == 0 {
+        return Err(ExportError::EmptyRange);
+    }
+
+    let mut transport = transport.clone();
+    let frames = (0..frame_count).map(move |index| {
+        // Seeking (rather than accumulating) keeps every frame exact and honours the loop region
+        transport.seek(settings.start + index as f64 / settings.fps as f64);
+        snapshot.render(transport.seconds(), settings.width, settings.height)
+    });
+
+    match settings.format {
+        ExportFormat::Gif => {
+            if let Some(parent) = settings.output.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let file = std::fs::File::create(&settings.output)?;
+            let mut encoder = GifEncoder::new_with_speed(std::io::BufWriter::new(file), 10);
+            encoder.set_repeat(Repeat::Infinite)?;
+            let delay = Delay::from_numer_denom_ms(1000, settings.fps);
+            for image in frames {
+                encoder.encode_frame(Frame::from_parts(image, 0, 0, delay))?;
+            }
+        }
+        ExportFormat::PngSequence => {
+            std::fs::create_dir_all(&settings.output)?;
+            for (index, image) in frames.enumerate() {
+                image.save(png_frame_path(&settings.output, index as u32))?;
+            }
+        }
+    }
+    Ok(frame_count)
+}
+
+pub fn png_frame_path(folder: &Path, index: u32) -> PathBuf {
+    folder.join(format!("frame_{index:04}.png"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+
+    fn settings(start: f64, end: f64, fps: u32, output: PathBuf) -> ExportSettings {
+        ExportSettings {
+            format: ExportFormat::PngSequence,
+            fps,
+            start,
+            end,
+            width: 4,
+            height: 4,
+            output,
+        }
+    }
+
+    #[test]
+    fn frame_count_covers_the_range_end_exclusive() {
+        let count = |start, end, fps| settings(start, end, fps, PathBuf::new()).frame_count();
+        assert_eq!(count(1.0, 3.0, 15), 30);
+        assert_eq!(count(0.0, 0.1, 15), 2);
+        assert_eq!(count(2.0, 2.0, 15), 0);
+        assert_eq!(count(0.0, 1.0, 0), 0);
+    }
+
+    #[test]
+    fn png_export_writes_one_file_per_frame() {
+        let output = std::env::temp_dir().join(format!("motor_export_test_{}", std::process::id()));
+        let snapshot = GridSnapshot::new(Vec::new(), Color::BLACK, 0.0);
+        let settings = settings(0.5, 1.0, 10, output.clone());
+
+        assert_eq!(export_animation(&snapshot, &Transport::default(), &settings).unwrap(), 5);
+        assert!(png_frame_path(&output, 4).exists());
+        assert!(!png_frame_path(&output, 5).exists());
+        std::fs::remove_dir_all(&output).unwrap();
+
+        let empty = ExportSettings { end: 0.5, ..settings };
+        assert!(matches!(export_animation(&snapshot, &Transport::default(), &empty), Err(ExportError::EmptyRange)));
+    }
+}
@@ -0,0 +1,9 @@
+mod events;
+mod export;
+mod observers;
+mod plugin;
+mod raster;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use plugin::MotorExportPlugin;
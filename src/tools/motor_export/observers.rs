@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::tasks::IoTaskPool;
+
+use crate::tools::motor_export::events::ExportMotorAnimation;
+use crate::tools::motor_export::export::export_animation;
+use crate::tools::motor_audio::{AudioAnalysis, AudioMotor};
+use crate::tools::motor_export::raster::{AudioSnapshot, CellSnapshot, GridSnapshot};
+use crate::tools::motor_graph::{AttachedMotorGraph, MotorGraph};
+use crate::tools::motors::{Motors, SyncGroups, TriggeredMotor, Wave};
+use crate::tools::tile_map_grid::components::{GridCell, MainCell};
+use crate::tools::tile_map_grid::BACKGROUND_COLOR;
+use crate::tools::transport::Transport;
+
+const EXPORT_PADDING: f32 = 20.0;
+
+/// Snapshots the grid and encodes the animation on the IO task pool
+pub fn export_motor_animation_observer(
+    trigger: Trigger<ExportMotorAnimation>,
+    cells: Query<
+        (
+            &GridCell,
+            &Transform,
+            &Sprite,
+            Option<&Motors>,
+            Option<&AttachedMotorGraph>,
+            Option<&Wave>,
+            Option<&AudioMotor>,
+            Option<&TriggeredMotor>,
+        ),
+        With<MainCell>,
+    >,
+    graphs: Res<Assets<MotorGraph>>,
+    analyses: Res<Assets<AudioAnalysis>>,
+    transport: Res<Transport>,
+    sync: Res<SyncGroups>,
+) {
+    let settings = trigger.event().0.clone();
+    let columns = cells.iter().map(|(cell, ..)| cell.col + 1).max().unwrap_or(1);
+    // One copy of each analysis, however many cells follow it
+    let mut shared: HashMap<AssetId<AudioAnalysis>, Arc<AudioAnalysis>> = HashMap::new();
+    let cells: Vec<CellSnapshot> = cells
+        .iter()
+        .map(|(cell, transform, sprite, motors, graph, wave, audio, triggered)| CellSnapshot {
+            cell: cell.clone(),
+            transform: *transform,
+            size: sprite.custom_size.unwrap_or(Vec2::ONE),
+            // Plain cells mid-envelope go back to their colour from before the trigger
+            color: triggered
+                .and_then(|triggered| triggered.rest_color)
+                .filter(|_| motors.is_none())
+                .unwrap_or(sprite.color),
+            motors: motors.cloned(),
+            graph: graph.and_then(|graph| graphs.get(&graph.0)).cloned(),
+            wave: wave.cloned(),
+            triggered: triggered.cloned(),
+            audio: audio.and_then(|audio| {
+                let id = audio.analysis.id();
+                let analysis = match shared.get(&id) {
+                    Some(analysis) => analysis.clone(),
+                    None => {
+                        let analysis = Arc::new(analyses.get(id)?.clone());
+                        shared.insert(id, analysis.clone());
+                        analysis
+                    }
+                };
+                Some(AudioSnapshot {
+                    analysis,
+                    mapping: audio.mapping,
+                    target: audio.target,
+                    gain: audio.gain,
+                    columns,
+                })
+            }),
+        })
+        .collect();
+    let snapshot = GridSnapshot {
+        sync: sync.clone(),
+        ..GridSnapshot::new(cells, BACKGROUND_COLOR, EXPORT_PADDING)
+    };
+    let transport = transport.clone();
+
+    log::info!("Exporting {} frames to {}", settings.frame_count(), settings.output.display());
+    IoTaskPool::get()
+        .spawn(async move {
+            match export_animation(&snapshot, &transport, &settings) {
+                Ok(frames) => log::info!("Exported {frames} frames to {}", settings.output.display()),
+                Err(err) => log::error!("Export to {} failed: {err}", settings.output.display()),
+            }
+        })
+        .detach();
+}
@@ -0,0 +1,18 @@
+use crate::tools::motor_export::observers::export_motor_animation_observer;
+use crate::tools::motor_export::systems::export_shortcuts;
+use crate::ui::keyboard_free;
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct MotorExportPlugin;
+
+impl Plugin for MotorExportPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                export_shortcuts.run_if(in_state(GameState::GridAndMotors).and(keyboard_free)),
+            )
+            .add_observer(export_motor_animation_observer);
+    }
+}
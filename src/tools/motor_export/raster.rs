@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use image::{Rgba, RgbaImage};
+
+use crate::tools::motor_audio::{AudioAnalysis, AudioMapping};
+use crate::tools::motor_graph::{EvalContext, MotorGraph};
+use crate::tools::motors::{MotorTarget, Motors, SyncGroups, TriggeredMotor, Wave, MOTOR_BASE_COLOR};
+use crate::tools::tile_map_grid::components::GridCell;
+
+/// Everything needed to redraw one grid cell away from the ECS
+#[derive(Debug, Clone)]
+pub struct CellSnapshot {
+    pub cell: GridCell,
+    pub transform: Transform,
+    pub size: Vec2,
+    /// Color used when neither motors nor a graph drive the cell
+    pub color: Color,
+    pub motors: Option<Motors>,
+    pub graph: Option<MotorGraph>,
+    pub wave: Option<Wave>,
+    pub audio: Option<AudioSnapshot>,
+    /// Envelope voices running at the time of the snapshot; chained triggers still queued then are not replayed
+    pub triggered: Option<TriggeredMotor>,
+}
+
+/// Audio motor together with the analysis it follows, shared by the cells playing the same file
+#[derive(Debug, Clone)]
+pub struct AudioSnapshot {
+    pub analysis: Arc<AudioAnalysis>,
+    pub mapping: AudioMapping,
+    pub target: MotorTarget,
+    pub gain: f32,
+    /// Grid columns the spectrum bands spread over
+    pub columns: usize,
+}
+
+impl CellSnapshot {
+    /// Color and transform of the cell at transport time `t`, matching the live motor systems
+    pub fn evaluate(&self, t: f32, sync: &SyncGroups) -> (Color, Transform) {
+        let mut transform = self.transform;
+        let delay = self.wave.as_ref().map_or(0.0, |wave| wave.delay(&self.cell));
+        let mut color = self.color;
+        if let Some(motors) = &self.motors {
+            color = motors.apply(t - delay, sync, &mut transform);
+        }
+        if let Some(graph) = &self.graph {
+            color = graph.apply(&EvalContext::for_cell(t - delay, &self.cell), &mut transform);
+        }
+        if let Some(audio) = &self.audio {
+            let value = audio.mapping.value(&audio.analysis, t, self.cell.col, audio.columns) * audio.gain;
+            let mut hsla = if self.motors.is_some() { Hsla::from(color) } else { MOTOR_BASE_COLOR };
+            audio.target.apply(value, &mut hsla, &mut transform);
+            color = hsla.into();
+        }
+        if let Some(triggered) = &self.triggered {
+            let mut triggered = triggered.clone();
+            triggered.prune(t as f64);
+            if triggered.is_active() {
+                let mut hsla = if self.motors.is_some() { Hsla::from(color) } else { MOTOR_BASE_COLOR };
+                triggered.target.apply(triggered.value(t as f64), &mut hsla, &mut transform);
+                color = hsla.into();
+            }
+        }
+        (color, transform)
+    }
+}
+
+/// Frozen grid plus the view it is rendered with
+#[derive(Debug, Clone)]
+pub struct GridSnapshot {
+    pub cells: Vec<CellSnapshot>,
+    pub background: Color,
+    /// World-space area mapped onto the output image
+    pub bounds: Rect,
+    /// Group clocks at the time of the snapshot
+    pub sync: SyncGroups,
+}
+
+impl GridSnapshot {
+    pub fn new(cells: Vec<CellSnapshot>, background: Color, padding: f32) -> Self {
+        let bounds = cells
+            .iter()
+            .map(|cell| {
+                let half = cell.size * cell.transform.scale.truncate() * 0.5;
+                let center = cell.transform.translation.truncate();
+                Rect::from_corners(center - half, center + half)
+            })
+            .reduce(|a, b| a.union(b))
+            .unwrap_or(Rect::new(-1.0, -1.0, 1.0, 1.0))
+            .inflate(padding);
+        Self {
+            cells,
+            background,
+            bounds,
+            sync: SyncGroups::default(),
+        }
+    }
+
+    /// Rasterizes the grid at transport time `t` on the CPU
+    pub fn render(&self, t: f32, width: u32, height: u32) -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(width, height, to_rgba(self.background));
+        let pixels_per_unit = Vec2::new(width as f32, height as f32) / self.bounds.size();
+
+        let mut cells: Vec<(Color, Transform, Vec2)> = self
+            .cells
+            .iter()
+            .map(|cell| {
+                let (color, transform) = cell.evaluate(t, &self.sync);
+                (color, transform, cell.size)
+            })
+            .collect();
+        // Same painter's order as the sprite renderer
+        cells.sort_by(|a, b| a.1.translation.z.total_cmp(&b.1.translation.z));
+
+        for (color, transform, size) in cells {
+            fill_cell(&mut image, &self.bounds, pixels_per_unit, color, &transform, size);
+        }
+        image
+    }
+}
+
+fn fill_cell(image: &mut RgbaImage, bounds: &Rect, pixels_per_unit: Vec2, color: Color, transform: &Transform, size: Vec2) {
+    let half = size * 0.5;
+    let to_local = transform.compute_affine().inverse();
+
+    // Pixel bounding box of the rotated, scaled quad
+    let corners = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)]
+        .map(|corner| transform.transform_point((corner * half).extend(0.0)).truncate());
+    let to_pixel = |world: Vec2| Vec2::new(world.x - bounds.min.x, bounds.max.y - world.y) * pixels_per_unit;
+    let pixel_corners = corners.map(to_pixel);
+    let min = pixel_corners.iter().fold(Vec2::MAX, |acc, p| acc.min(*p)).max(Vec2::ZERO);
+    let max = pixel_corners
+        .iter()
+        .fold(Vec2::MIN, |acc, p| acc.max(*p))
+        .min(Vec2::new(image.width() as f32, image.height() as f32));
+    if min.x >= max.x || min.y >= max.y {
+        return;
+    }
+
+    let source = to_rgba(color);
+    let alpha = source[3] as f32 / 255.0;
+    for y in min.y as u32..max.y.ceil() as u32 {
+        for x in min.x as u32..max.x.ceil() as u32 {
+            let world = Vec2::new(
+                bounds.min.x + (x as f32 + 0.5) / pixels_per_unit.x,
+                bounds.max.y - (y as f32 + 0.5) / pixels_per_unit.y,
+            );
+            let local = to_local.transform_point3(world.extend(transform.translation.z)).truncate();
+            if local.x.abs() > half.x || local.y.abs() > half.y {
+                continue;
+            }
+            let target = image.get_pixel_mut(x, y);
+            for channel in 0..3 {
+                target[channel] = (source[channel] as f32 * alpha + target[channel] as f32 * (1.0 - alpha)).round() as u8;
+            }
+            target[3] = 255;
+        }
+    }
+}
+
+fn to_rgba(color: Color) -> Rgba<u8> {
+    Rgba(color.to_srgba().to_u8_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+    use crate::tools::motors::{Motor, MotorChannel};
+
+    fn cell(col: usize, motors: Option<Motors>) -> CellSnapshot {
+        CellSnapshot {
+            cell: GridCell { row: 0, col },
+            transform: Transform::from_xyz(col as f32 * 10.0, 0.0, 0.0),
+            size: Vec2::splat(10.0),
+            color: Color::WHITE,
+            motors,
+            graph: None,
+            wave: None,
+            audio: None,
+            triggered: None,
+        }
+    }
+
+    #[test]
+    fn motored_cells_change_between_frames() {
+        // A quarter cycle per second takes the lightness from 0 to 1 over the first second
+        let motors = Motors {
+            channels: vec![MotorChannel::new("light", Motor::new(TAU / 4.0), MotorTarget::Lightness)],
+        };
+        let snapshot = GridSnapshot::new(vec![cell(0, Some(motors)), cell(1, None)], Color::srgb(0.5, 0.5, 0.5), 0.0);
+
+        let start = snapshot.render(0.0, 20, 10);
+        let later = snapshot.render(1.0, 20, 10);
+        assert_eq!(start.get_pixel(5, 5), &Rgba([0, 0, 0, 255]));
+        assert_eq!(later.get_pixel(5, 5), &Rgba([255, 255, 255, 255]));
+        // The plain cell keeps its colour
+        assert_eq!(start.get_pixel(15, 5), &Rgba([255, 255, 255, 255]));
+        assert_eq!(start.get_pixel(15, 5), later.get_pixel(15, 5));
+    }
+
+    #[test]
+    fn audio_cells_follow_the_analysis() {
+        // Silent, then loud one analysis frame later
+        let frame = 1.0 / 60.0;
+        let analysis = AudioAnalysis {
+            duration: frame,
+            envelope: vec![0.0, 1.0],
+            bands: vec![Default::default(); 2],
+        };
+        let snapshot = GridSnapshot::new(
+            vec![CellSnapshot {
+                audio: Some(AudioSnapshot {
+                    analysis: Arc::new(analysis),
+                    mapping: AudioMapping::Envelope,
+                    target: MotorTarget::Lightness,
+                    gain: 1.0,
+                    columns: 1,
+                }),
+                ..cell(0, None)
+            }],
+            Color::BLACK,
+            0.0,
+        );
+        assert_eq!(snapshot.render(0.0, 4, 4).get_pixel(2, 2), &Rgba([0, 0, 0, 255]));
+        assert_eq!(snapshot.render(frame, 4, 4).get_pixel(2, 2), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn envelopes_play_while_their_voices_run() {
+        let mut triggered = TriggeredMotor {
+            target: MotorTarget::Lightness,
+            depth: 1.0,
+            ..default()
+        };
+        // One second each of attack, full level and release
+        let envelope = &mut triggered.envelope;
+        (envelope.attack, envelope.decay, envelope.sustain, envelope.hold, envelope.release) = (1.0, 0.0, 1.0, 1.0, 1.0);
+        triggered.trigger(1.0, 1.0);
+        let gray = Color::srgb(0.5, 0.5, 0.5);
+        let snapshot = GridSnapshot::new(
+            vec![CellSnapshot { color: gray, triggered: Some(triggered), ..cell(0, None) }],
+            Color::BLACK,
+            0.0,
+        );
+        let pixel = |t: f32| *snapshot.render(t, 4, 4).get_pixel(2, 2);
+        assert_eq!(pixel(0.5), to_rgba(gray));
+        assert_eq!(pixel(2.0), Rgba([255, 255, 255, 255]));
+        assert_eq!(pixel(4.5), to_rgba(gray));
+    }
+}
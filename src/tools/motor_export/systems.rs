@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+use crate::tools::motor_export::events::ExportMotorAnimation;
+use crate::tools::motor_export::export::{ExportFormat, ExportSettings};
+use crate::tools::transport::Transport;
+
+const EXPORT_DIR: &str = "exports";
+const EXPORT_FPS: u32 = 15;
+const EXPORT_SIZE: u32 = 400;
+/// Range exported when the transport has no loop region
+const DEFAULT_DURATION: f64 = 8.0;
+
+/// E exports a GIF, Shift+E a PNG sequence, covering the loop region when one is set
+pub fn export_shortcuts(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>, transport: Res<Transport>) {
+    if !keyboard.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    let png = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let (start, end) = transport.loop_region.unwrap_or((0.0, DEFAULT_DURATION));
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (format, output) = if png {
+        (ExportFormat::PngSequence, PathBuf::from(EXPORT_DIR).join(format!("motors_{stamp}")))
+    } else {
+        (ExportFormat::Gif, PathBuf::from(EXPORT_DIR).join(format!("motors_{stamp}.gif")))
+    };
+    commands.trigger(ExportMotorAnimation(ExportSettings {
+        format,
+        fps: EXPORT_FPS,
+        start,
+        end,
+        width: EXPORT_SIZE,
+        height: EXPORT_SIZE,
+        output,
+    }));
+}
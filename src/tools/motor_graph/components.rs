@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::tools::motor_graph::graph::{MotorGraph, MotorNode, NodeId};
+use crate::tools::motors::MotorTarget;
+
+/// Drives a grid cell's properties from a motor graph asset
+#[derive(Component, Debug, Clone)]
+pub struct AttachedMotorGraph(pub Handle<MotorGraph>);
+
+#[derive(Component)]
+pub struct MotorGraphEditorEntity;
+
+/// Container the node rows are rebuilt into
+#[derive(Component)]
+pub struct GraphNodeList;
+
+/// Container the saved graph buttons are rebuilt into
+#[derive(Component)]
+pub struct GraphLibraryList;
+
+#[derive(Component)]
+pub struct GraphEditorStatus;
+
+#[derive(Component, Debug, Clone)]
+pub enum GraphEditorButton {
+    AddNode(MotorNode),
+    SelectNode(NodeId),
+    /// Wires the selected node into `slot` of `node`
+    Connect { node: NodeId, slot: usize },
+    /// Routes the selected node to a cell property
+    AddOutput(MotorTarget),
+    ScaleSelected(f32),
+    RemoveLast,
+    Save,
+    Load(String),
+    /// Saves the graph and attaches its file to the selected cells
+    Assign,
+    Detach,
+    Clear,
+}
@@ -0,0 +1,432 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::motors::{MotorTarget, Waveform, MOTOR_BASE_COLOR};
+use crate::tools::tile_map_grid::components::GridCell;
+
+/// Index of a node inside [`MotorGraph::nodes`]
+pub type NodeId = usize;
+
+/// Value fed into a node slot: either a fixed number or another node's output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Input {
+    Const(f32),
+    Node(NodeId),
+}
+
+impl std::fmt::Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Const(value) => write!(f, "{value:.2}"),
+            Input::Node(id) => write!(f, "#{id}"),
+        }
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::Const(0.0)
+    }
+}
+
+/// How an LFO modulates its carrier oscillator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Modulation {
+    /// Amplitude modulation, `depth` scales the carrier's amplitude
+    #[default]
+    Am,
+    /// Frequency modulation, `depth` is the frequency deviation in Hz
+    Fm,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MotorNode {
+    Oscillator {
+        waveform: Waveform,
+        freq: f32,
+        amp: f32,
+        /// Phase offset in cycles
+        phase: f32,
+    },
+    /// Smooth value noise in [-1, 1], `rate` new values per second
+    Noise { rate: f32, seed: u32 },
+    Constant(f32),
+    Add(Input, Input),
+    Multiply(Input, Input),
+    /// Linear blend from `a` to `b` by `t`
+    Mix { a: Input, b: Input, t: Input },
+    Clamp { input: Input, min: f32, max: f32 },
+    Remap {
+        input: Input,
+        in_min: f32,
+        in_max: f32,
+        out_min: f32,
+        out_max: f32,
+    },
+    /// Low frequency oscillator modulating the `carrier` oscillator node
+    Lfo {
+        waveform: Waveform,
+        rate: f32,
+        depth: f32,
+        mode: Modulation,
+        carrier: NodeId,
+    },
+}
+
+impl MotorNode {
+    /// Short label used by the editor
+    pub fn label(&self) -> &'static str {
+        match self {
+            MotorNode::Oscillator { .. } => "Osc",
+            MotorNode::Noise { .. } => "Noise",
+            MotorNode::Constant(_) => "Const",
+            MotorNode::Add(..) => "Add",
+            MotorNode::Multiply(..) => "Mul",
+            MotorNode::Mix { .. } => "Mix",
+            MotorNode::Clamp { .. } => "Clamp",
+            MotorNode::Remap { .. } => "Remap",
+            MotorNode::Lfo { .. } => "LFO",
+        }
+    }
+
+    /// Names of the input slots another node can be wired into
+    pub fn slot_names(&self) -> &'static [&'static str] {
+        match self {
+            MotorNode::Add(..) | MotorNode::Multiply(..) => &["a", "b"],
+            MotorNode::Mix { .. } => &["a", "b", "t"],
+            MotorNode::Clamp { .. } | MotorNode::Remap { .. } => &["in"],
+            MotorNode::Lfo { .. } => &["carrier"],
+            _ => &[],
+        }
+    }
+
+    /// Wires `source` into the slot at `slot`, as listed by [`MotorNode::slot_names`]
+    pub fn connect(&mut self, slot: usize, source: NodeId) {
+        let input = Input::Node(source);
+        match (self, slot) {
+            (MotorNode::Add(a, _) | MotorNode::Multiply(a, _), 0) => *a = input,
+            (MotorNode::Add(_, b) | MotorNode::Multiply(_, b), 1) => *b = input,
+            (MotorNode::Mix { a, .. }, 0) => *a = input,
+            (MotorNode::Mix { b, .. }, 1) => *b = input,
+            (MotorNode::Mix { t, .. }, 2) => *t = input,
+            (MotorNode::Clamp { input: slot_input, .. } | MotorNode::Remap { input: slot_input, .. }, 0) => *slot_input = input,
+            (MotorNode::Lfo { carrier, .. }, 0) => *carrier = source,
+            _ => {}
+        }
+    }
+
+    /// Scales the node's main parameter: frequency, rate or constant value
+    pub fn scale_primary(&mut self, factor: f32) {
+        match self {
+            MotorNode::Oscillator { freq, .. } => *freq *= factor,
+            MotorNode::Noise { rate, .. } | MotorNode::Lfo { rate, .. } => *rate *= factor,
+            MotorNode::Constant(value) => *value *= factor,
+            _ => {}
+        }
+    }
+
+    /// One-line description of the node's parameters and links
+    pub fn describe(&self) -> String {
+        match self {
+            MotorNode::Oscillator { waveform, freq, amp, phase } => {
+                format!("{waveform:?} {freq:.2}Hz x{amp:.2} +{phase:.2}")
+            }
+            MotorNode::Noise { rate, seed } => format!("{rate:.2}/s seed {seed}"),
+            MotorNode::Constant(value) => format!("{value:.2}"),
+            MotorNode::Add(a, b) | MotorNode::Multiply(a, b) => format!("{a} {b}"),
+            MotorNode::Mix { a, b, t } => format!("{a} {b} t={t}"),
+            MotorNode::Clamp { input, min, max } => format!("{input} [{min:.2}, {max:.2}]"),
+            MotorNode::Remap { input, in_min, in_max, out_min, out_max } => {
+                format!("{input} [{in_min:.2}, {in_max:.2}] -> [{out_min:.2}, {out_max:.2}]")
+            }
+            MotorNode::Lfo { waveform, rate, depth, mode, carrier } => {
+                format!("{mode:?} {waveform:?} {rate:.2}Hz d={depth:.2} -> #{carrier}")
+            }
+        }
+    }
+
+    /// Nodes this node reads from
+    pub fn dependencies(&self) -> Vec<NodeId> {
+        let inputs: Vec<Input> = match self {
+            MotorNode::Add(a, b) | MotorNode::Multiply(a, b) => vec![*a, *b],
+            MotorNode::Mix { a, b, t } => vec![*a, *b, *t],
+            MotorNode::Clamp { input, .. } | MotorNode::Remap { input, .. } => vec![*input],
+            MotorNode::Lfo { carrier, .. } => vec![Input::Node(*carrier)],
+            _ => vec![],
+        };
+        inputs
+            .into_iter()
+            .filter_map(|input| match input {
+                Input::Node(id) => Some(id),
+                Input::Const(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Routes a node's output to a cell property
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphOutput {
+    pub node: NodeId,
+    pub target: MotorTarget,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    MissingNode(NodeId),
+    Cycle(NodeId),
+    CarrierNotOscillator(NodeId),
+    /// The node still feeds the second one
+    InUse(NodeId, NodeId),
+}
+
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::MissingNode(id) => write!(f, "node #{id} does not exist"),
+            GraphError::Cycle(id) => write!(f, "node #{id} is part of a cycle"),
+            GraphError::CarrierNotOscillator(id) => write!(f, "LFO #{id} must modulate an oscillator"),
+            GraphError::InUse(id, by) => write!(f, "node #{id} still feeds node #{by}"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// Per-evaluation inputs shared by every node
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvalContext {
+    /// Motor time in seconds
+    pub time: f32,
+    /// Mixed into noise seeds so cells sharing a graph don't look identical
+    pub salt: u32,
+}
+
+impl EvalContext {
+    /// Context for a grid cell, salted by its position
+    pub fn for_cell(time: f32, cell: &GridCell) -> Self {
+        Self {
+            time,
+            salt: (cell.row as u32).wrapping_mul(73_856_093) ^ (cell.col as u32).wrapping_mul(19_349_663),
+        }
+    }
+}
+
+/// Composable motor: a set of nodes whose outputs drive cell properties
+#[derive(Asset, TypePath, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MotorGraph {
+    pub nodes: Vec<MotorNode>,
+    pub outputs: Vec<GraphOutput>,
+}
+
+impl MotorGraph {
+    pub fn add_node(&mut self, node: MotorNode) -> NodeId {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Drops the newest node and the outputs it drives, refusing while an earlier node still reads it
+    pub fn remove_last(&mut self) -> Result<Option<MotorNode>, GraphError> {
+        let Some(last) = self.nodes.len().checked_sub(1) else {
+            return Ok(None);
+        };
+        if let Some(by) = self.nodes[..last].iter().position(|node| node.dependencies().contains(&last)) {
+            return Err(GraphError::InUse(last, by));
+        }
+        self.outputs.retain(|output| output.node < last);
+        Ok(self.nodes.pop())
+    }
+
+    /// Checks that every link points at an existing node and that there are no cycles
+    pub fn validate(&self) -> Result<(), GraphError> {
+        for (id, node) in self.nodes.iter().enumerate() {
+            for dep in node.dependencies() {
+                if dep >= self.nodes.len() {
+                    return Err(GraphError::MissingNode(dep));
+                }
+            }
+            if let MotorNode::Lfo { carrier, .. } = node {
+                if !matches!(self.nodes[*carrier], MotorNode::Oscillator { .. }) {
+                    return Err(GraphError::CarrierNotOscillator(id));
+                }
+            }
+        }
+        for output in &self.outputs {
+            if output.node >= self.nodes.len() {
+                return Err(GraphError::MissingNode(output.node));
+            }
+        }
+        // Depth-first search, 1 = on the current path, 2 = done
+        let mut marks = vec![0u8; self.nodes.len()];
+        for start in 0..self.nodes.len() {
+            self.visit(start, &mut marks)?;
+        }
+        Ok(())
+    }
+
+    fn visit(&self, id: NodeId, marks: &mut [u8]) -> Result<(), GraphError> {
+        match marks[id] {
+            1 => return Err(GraphError::Cycle(id)),
+            2 => return Ok(()),
+            _ => {}
+        }
+        marks[id] = 1;
+        for dep in self.nodes[id].dependencies() {
+            self.visit(dep, marks)?;
+        }
+        marks[id] = 2;
+        Ok(())
+    }
+
+    /// Evaluates every output, returning one `(target, value)` pair per output
+    pub fn evaluate(&self, ctx: &EvalContext) -> Vec<(MotorTarget, f32)> {
+        self.outputs
+            .iter()
+            .map(|output| (output.target, self.eval_node(output.node, ctx)))
+            .collect()
+    }
+
+    /// Cell color for `ctx`; outputs targeting scale or rotation are written into `transform`
+    pub fn apply(&self, ctx: &EvalContext, transform: &mut Transform) -> Color {
+        let mut color = MOTOR_BASE_COLOR;
+        for (target, value) in self.evaluate(ctx) {
+            target.apply(value, &mut color, transform);
+        }
+        color.into()
+    }
+
+    /// Output of a single node; graphs are expected to have passed [`MotorGraph::validate`]
+    pub fn eval_node(&self, id: NodeId, ctx: &EvalContext) -> f32 {
+        let Some(node) = self.nodes.get(id) else {
+            return 0.0;
+        };
+        match node {
+            MotorNode::Oscillator { waveform, freq, amp, phase } => {
+                amp * waveform.sample(ctx.time * freq + phase)
+            }
+            MotorNode::Noise { rate, seed } => value_noise(ctx.time * rate, seed ^ ctx.salt),
+            MotorNode::Constant(value) => *value,
+            MotorNode::Add(a, b) => self.input(a, ctx) + self.input(b, ctx),
+            MotorNode::Multiply(a, b) => self.input(a, ctx) * self.input(b, ctx),
+            MotorNode::Mix { a, b, t } => {
+                let t = self.input(t, ctx);
+                self.input(a, ctx) * (1.0 - t) + self.input(b, ctx) * t
+            }
+            MotorNode::Clamp { input, min, max } => self.input(input, ctx).clamp(*min, *max),
+            MotorNode::Remap { input, in_min, in_max, out_min, out_max } => {
+                let span = in_max - in_min;
+                if span == 0.0 {
+                    return *out_min;
+                }
+                let t = (self.input(input, ctx) - in_min) / span;
+                out_min + t * (out_max - out_min)
+            }
+            MotorNode::Lfo { waveform, rate, depth, mode, carrier } => {
+                let Some(MotorNode::Oscillator { waveform: carrier_wave, freq, amp, phase }) = self.nodes.get(*carrier) else {
+                    return 0.0;
+                };
+                let lfo = waveform.sample(ctx.time * rate);
+                match mode {
+                    Modulation::Am => amp * (1.0 + depth * lfo) * carrier_wave.sample(ctx.time * freq + phase),
+                    Modulation::Fm => {
+                        // Integral of `depth * lfo`, so the carrier phase stays continuous
+                        let deviation = if *rate > 0.0 {
+                            depth * waveform.integral(ctx.time * rate) / rate
+                        } else {
+                            0.0
+                        };
+                        amp * carrier_wave.sample(ctx.time * freq + phase + deviation)
+                    }
+                }
+            }
+        }
+    }
+
+    fn input(&self, input: &Input, ctx: &EvalContext) -> f32 {
+        match input {
+            Input::Const(value) => *value,
+            Input::Node(id) => self.eval_node(*id, ctx),
+        }
+    }
+}
+
+/// 1D value noise with smoothstep interpolation between hashed lattice points
+fn value_noise(x: f32, seed: u32) -> f32 {
+    let i = x.floor();
+    let f = x - i;
+    let a = hash(i as i32 as u32, seed);
+    let b = hash((i as i32 as u32).wrapping_add(1), seed);
+    let t = f * f * (3.0 - 2.0 * f);
+    a + (b - a) * t
+}
+
+fn hash(x: u32, seed: u32) -> f32 {
+    let mut h = x.wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0xffff) as f32 / 32767.5 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oscillator() -> MotorNode {
+        MotorNode::Oscillator { waveform: Waveform::Sine, freq: 1.0, amp: 1.0, phase: 0.0 }
+    }
+
+    fn lfo(carrier: NodeId) -> MotorNode {
+        MotorNode::Lfo { waveform: Waveform::Triangle, rate: 0.5, depth: 0.3, mode: Modulation::Fm, carrier }
+    }
+
+    fn graph(nodes: Vec<MotorNode>) -> MotorGraph {
+        MotorGraph { nodes, outputs: Vec::new() }
+    }
+
+    #[test]
+    fn accepts_an_lfo_on_an_oscillator() {
+        let mut graph = graph(vec![oscillator(), lfo(0)]);
+        graph.outputs.push(GraphOutput { node: 1, target: MotorTarget::Hue });
+        assert_eq!(graph.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_an_lfo_on_anything_else() {
+        let graph = graph(vec![MotorNode::Constant(0.5), lfo(0)]);
+        assert_eq!(graph.validate(), Err(GraphError::CarrierNotOscillator(1)));
+    }
+
+    #[test]
+    fn rejects_links_to_missing_nodes() {
+        let graph = graph(vec![MotorNode::Add(Input::Node(4), Input::Const(1.0))]);
+        assert_eq!(graph.validate(), Err(GraphError::MissingNode(4)));
+
+        let mut routed = MotorGraph::default();
+        routed.outputs.push(GraphOutput { node: 0, target: MotorTarget::Hue });
+        assert_eq!(routed.validate(), Err(GraphError::MissingNode(0)));
+    }
+
+    #[test]
+    fn removing_a_node_others_read_is_refused() {
+        let mut graph = graph(vec![oscillator(), lfo(2), oscillator()]);
+        graph.outputs.push(GraphOutput { node: 2, target: MotorTarget::Hue });
+        assert_eq!(graph.remove_last(), Err(GraphError::InUse(2, 1)));
+        assert_eq!(graph.nodes.len(), 3);
+
+        graph.nodes[1] = lfo(0);
+        assert_eq!(graph.remove_last(), Ok(Some(oscillator())));
+        assert!(graph.outputs.is_empty());
+        assert_eq!(graph.validate(), Ok(()));
+        assert_eq!(MotorGraph::default().remove_last(), Ok(None));
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let graph = graph(vec![
+            MotorNode::Add(Input::Node(1), Input::Const(0.0)),
+            MotorNode::Multiply(Input::Node(0), Input::Const(2.0)),
+        ]);
+        assert!(matches!(graph.validate(), Err(GraphError::Cycle(_))));
+    }
+}
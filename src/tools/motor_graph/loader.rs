@@ -0,0 +1,83 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::prelude::*;
+
+use crate::tools::motor_graph::graph::{GraphError, MotorGraph};
+
+/// Folder (relative to `assets/`) where the editor saves graphs
+pub const MOTOR_GRAPH_DIR: &str = "motor_graphs";
+pub const MOTOR_GRAPH_EXTENSION: &str = "motorgraph.ron";
+
+#[derive(Debug)]
+pub enum MotorGraphLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Invalid(GraphError),
+}
+
+impl std::fmt::Display for MotorGraphLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MotorGraphLoaderError::Io(err) => write!(f, "could not read motor graph: {err}"),
+            MotorGraphLoaderError::Ron(err) => write!(f, "could not parse motor graph: {err}"),
+            MotorGraphLoaderError::Invalid(err) => write!(f, "invalid motor graph: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for MotorGraphLoaderError {}
+
+/// Loads `*.motorgraph.ron` files as [`MotorGraph`] assets
+#[derive(Default)]
+pub struct MotorGraphLoader;
+
+impl AssetLoader for MotorGraphLoader {
+    type Asset = MotorGraph;
+    type Settings = ();
+    type Error = MotorGraphLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<MotorGraph, MotorGraphLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(MotorGraphLoaderError::Io)?;
+        let graph: MotorGraph = ron::de::from_bytes(&bytes).map_err(MotorGraphLoaderError::Ron)?;
+        graph.validate().map_err(MotorGraphLoaderError::Invalid)?;
+        Ok(graph)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[MOTOR_GRAPH_EXTENSION]
+    }
+}
+
+/// Asset path of a saved graph, e.g. `motor_graphs/ripple.motorgraph.ron`
+pub fn motor_graph_path(name: &str) -> String {
+    format!("{MOTOR_GRAPH_DIR}/{name}.{MOTOR_GRAPH_EXTENSION}")
+}
+
+/// Writes `graph` to `assets/motor_graphs/<name>.motorgraph.ron`
+pub fn save_motor_graph(name: &str, graph: &MotorGraph) -> Result<(), MotorGraphLoaderError> {
+    let dir = std::path::Path::new("assets").join(MOTOR_GRAPH_DIR);
+    std::fs::create_dir_all(&dir).map_err(MotorGraphLoaderError::Io)?;
+    let text = ron::ser::to_string_pretty(graph, ron::ser::PrettyConfig::default())
+        .map_err(|err| MotorGraphLoaderError::Io(std::io::Error::other(err)))?;
+    std::fs::write(std::path::Path::new("assets").join(motor_graph_path(name)), text)
+        .map_err(MotorGraphLoaderError::Io)
+}
+
+/// Names of the graphs saved in `assets/motor_graphs`, sorted alphabetically
+pub fn saved_motor_graphs() -> Vec<String> {
+    let suffix = format!(".{MOTOR_GRAPH_EXTENSION}");
+    let Ok(entries) = std::fs::read_dir(std::path::Path::new("assets").join(MOTOR_GRAPH_DIR)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(&suffix).map(str::to_owned))
+        .collect();
+    names.sort();
+    names
+}
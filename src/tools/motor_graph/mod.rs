@@ -0,0 +1,12 @@
+mod components;
+mod graph;
+mod loader;
+mod plugin;
+mod resources;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use plugin::MotorGraphPlugin;
+
+pub use components::AttachedMotorGraph;
+pub use graph::*;
@@ -0,0 +1,41 @@
+use crate::tools::motor_graph::graph::MotorGraph;
+use crate::tools::motor_graph::loader::MotorGraphLoader;
+use crate::tools::motor_graph::resources::GraphEditor;
+use crate::tools::motor_graph::systems::{
+    cleanup_graph_editor, finish_graph_load, graph_editor_buttons, motor_graphs_update,
+    refresh_graph_editor, spawn_graph_editor,
+};
+use crate::tools::motors::motors_update;
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct MotorGraphPlugin;
+
+impl Plugin for MotorGraphPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<MotorGraph>()
+            .init_asset_loader::<MotorGraphLoader>()
+            .init_resource::<GraphEditor>()
+            .add_systems(
+                OnEnter(GameState::GridAndMotors),
+                spawn_graph_editor,
+            )
+            .add_systems(
+                Update,
+                (
+                    graph_editor_buttons,
+                    finish_graph_load,
+                    refresh_graph_editor,
+                    // Both write the cell colour; an attached graph wins over the cell's own motors
+                    motor_graphs_update.after(motors_update),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::GridAndMotors)),
+            )
+            .add_systems(
+                OnExit(GameState::GridAndMotors),
+                cleanup_graph_editor
+            );
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+use crate::tools::motor_graph::graph::{MotorGraph, NodeId};
+
+/// Graph currently being composed in the editor panel
+#[derive(Resource, Debug, Clone)]
+pub struct GraphEditor {
+    pub graph: MotorGraph,
+    /// Name used when saving, also the file stem under `assets/motor_graphs`
+    pub name: String,
+    pub selected: Option<NodeId>,
+    /// Saved graph waiting to finish loading before it replaces `graph`
+    pub pending_load: Option<Handle<MotorGraph>>,
+    /// Saved graph names shown in the library list
+    pub library: Vec<String>,
+    pub status: String,
+}
+
+impl Default for GraphEditor {
+    fn default() -> Self {
+        Self {
+            graph: MotorGraph::default(),
+            name: "untitled".to_string(),
+            selected: None,
+            pending_load: None,
+            library: Vec::new(),
+            status: String::new(),
+        }
+    }
+}
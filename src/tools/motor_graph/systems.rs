@@ -0,0 +1,382 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::systems::loading::FontAssets;
+use crate::tools::motor_graph::components::{
+    AttachedMotorGraph, GraphEditorButton, GraphEditorStatus, GraphLibraryList, GraphNodeList,
+    MotorGraphEditorEntity,
+};
+use crate::tools::motor_graph::graph::{EvalContext, GraphOutput, Input, Modulation, MotorGraph, MotorNode};
+use crate::tools::motor_graph::loader::{motor_graph_path, save_motor_graph, saved_motor_graphs};
+use crate::tools::motor_graph::resources::GraphEditor;
+use crate::tools::motors::{MotorTarget, Waveform, Wave};
+use crate::tools::tile_map_grid::components::{GridCell, SelectedCell};
+use crate::tools::transport::Transport;
+use crate::ui::components::ButtonColors;
+use crate::ui::font_utils::text_geist_regular_with_font;
+
+const PANEL_WIDTH: f32 = 320.0;
+const SELECTED_COLOR: Color = Color::linear_rgb(0.2, 0.35, 0.6);
+
+pub fn motor_graphs_update(
+    graphs: Res<Assets<MotorGraph>>,
+    mut cells: Query<(&AttachedMotorGraph, &GridCell, Option<&Wave>, &mut Sprite, &mut Transform)>,
+    transport: Res<Transport>,
+) {
+    for (attached, grid_cell, wave, mut sprite, mut transform) in cells.iter_mut() {
+        let Some(graph) = graphs.get(&attached.0) else {
+            continue;
+        };
+        let delay = wave.map_or(0.0, |wave| wave.delay(grid_cell));
+        let ctx = EvalContext::for_cell(transport.seconds() - delay, grid_cell);
+        sprite.color = graph.apply(&ctx, &mut transform);
+    }
+}
+
+pub fn spawn_graph_editor(mut commands: Commands, fonts: Res<FontAssets>, mut editor: ResMut<GraphEditor>) {
+    editor.library = saved_motor_graphs();
+
+    let node_prototypes = [
+        MotorNode::Oscillator { waveform: Waveform::Sine, freq: 0.5, amp: 1.0, phase: 0.0 },
+        MotorNode::Noise { rate: 1.0, seed: 1 },
+        MotorNode::Constant(1.0),
+        MotorNode::Add(Input::Const(0.0), Input::Const(0.0)),
+        MotorNode::Multiply(Input::Const(1.0), Input::Const(1.0)),
+        MotorNode::Mix { a: Input::Const(0.0), b: Input::Const(1.0), t: Input::Const(0.5) },
+        MotorNode::Clamp { input: Input::Const(0.0), min: 0.0, max: 1.0 },
+        MotorNode::Remap { input: Input::Const(0.0), in_min: -1.0, in_max: 1.0, out_min: 0.0, out_max: 1.0 },
+        MotorNode::Lfo { waveform: Waveform::Sine, rate: 0.1, depth: 0.5, mode: Modulation::Am, carrier: 0 },
+        MotorNode::Lfo { waveform: Waveform::Sine, rate: 0.1, depth: 0.5, mode: Modulation::Fm, carrier: 0 },
+    ];
+    let targets = [
+        MotorTarget::Hue,
+        MotorTarget::Saturation,
+        MotorTarget::Lightness,
+        MotorTarget::Alpha,
+        MotorTarget::Scale,
+        MotorTarget::Rotation,
+    ];
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Px(PANEL_WIDTH),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(8.0),
+                overflow: Overflow::clip_y(),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            MotorGraphEditorEntity,
+        ))
+        .with_children(|panel| {
+            panel.spawn(text_geist_regular_with_font("Motor graph", 20.0, Color::WHITE, &fonts));
+
+            panel.spawn(wrap_row()).with_children(|row| {
+                for prototype in node_prototypes {
+                    let label = match &prototype {
+                        MotorNode::Lfo { mode, .. } => format!("LFO {mode:?}"),
+                        node => node.label().to_string(),
+                    };
+                    spawn_editor_button(row, &label, GraphEditorButton::AddNode(prototype), &fonts);
+                }
+            });
+
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                GraphNodeList,
+            ));
+
+            panel.spawn(text_geist_regular_with_font("Route selected node to", 14.0, Color::WHITE, &fonts));
+            panel.spawn(wrap_row()).with_children(|row| {
+                for target in targets {
+                    spawn_editor_button(row, &format!("{target:?}"), GraphEditorButton::AddOutput(target), &fonts);
+                }
+            });
+
+            panel.spawn(wrap_row()).with_children(|row| {
+                spawn_editor_button(row, "x2", GraphEditorButton::ScaleSelected(2.0), &fonts);
+                spawn_editor_button(row, "/2", GraphEditorButton::ScaleSelected(0.5), &fonts);
+                spawn_editor_button(row, "Undo node", GraphEditorButton::RemoveLast, &fonts);
+                spawn_editor_button(row, "Clear", GraphEditorButton::Clear, &fonts);
+            });
+            panel.spawn(wrap_row()).with_children(|row| {
+                spawn_editor_button(row, "Assign", GraphEditorButton::Assign, &fonts);
+                spawn_editor_button(row, "Detach", GraphEditorButton::Detach, &fonts);
+                spawn_editor_button(row, "Save", GraphEditorButton::Save, &fonts);
+            });
+
+            panel.spawn(text_geist_regular_with_font("Saved graphs", 14.0, Color::WHITE, &fonts));
+            panel.spawn((wrap_row(), GraphLibraryList));
+
+            panel.spawn((
+                text_geist_regular_with_font("", 12.0, Color::linear_rgb(0.9, 0.6, 0.3), &fonts),
+                GraphEditorStatus,
+            ));
+        });
+}
+
+fn wrap_row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        flex_wrap: FlexWrap::Wrap,
+        column_gap: Val::Px(4.0),
+        row_gap: Val::Px(4.0),
+        ..default()
+    }
+}
+
+fn spawn_editor_button(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    action: GraphEditorButton,
+    fonts: &FontAssets,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(ButtonColors::default().normal),
+            BorderRadius::all(Val::Px(3.0)),
+            ButtonColors::default(),
+            action,
+        ))
+        .with_children(|button| {
+            button.spawn(text_geist_regular_with_font(label, 12.0, Color::WHITE, fonts));
+        });
+}
+
+/// Rebuilds the node rows, library and status line whenever the edited graph changes
+pub fn refresh_graph_editor(
+    mut commands: Commands,
+    editor: Res<GraphEditor>,
+    fonts: Res<FontAssets>,
+    node_lists: Query<Entity, With<GraphNodeList>>,
+    libraries: Query<Entity, With<GraphLibraryList>>,
+    mut status: Query<&mut Text, With<GraphEditorStatus>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+
+    for list in node_lists.iter() {
+        commands.entity(list).despawn_related::<Children>().with_children(|list| {
+            for (id, node) in editor.graph.nodes.iter().enumerate() {
+                let routed: Vec<String> = editor
+                    .graph
+                    .outputs
+                    .iter()
+                    .filter(|output| output.node == id)
+                    .map(|output| format!("{:?}", output.target))
+                    .collect();
+                let mut label = format!("#{id} {} {}", node.label(), node.describe());
+                if !routed.is_empty() {
+                    label.push_str(&format!(" => {}", routed.join(", ")));
+                }
+
+                list.spawn(wrap_row()).with_children(|row| {
+                    spawn_editor_button(row, &label, GraphEditorButton::SelectNode(id), &fonts);
+                    for (slot, slot_name) in node.slot_names().iter().enumerate() {
+                        spawn_editor_button(row, &format!("<{slot_name}"), GraphEditorButton::Connect { node: id, slot }, &fonts);
+                    }
+                });
+            }
+        });
+    }
+
+    for library in libraries.iter() {
+        commands.entity(library).despawn_related::<Children>().with_children(|row| {
+            for name in &editor.library {
+                spawn_editor_button(row, name, GraphEditorButton::Load(name.clone()), &fonts);
+            }
+        });
+    }
+
+    for mut text in status.iter_mut() {
+        let selected = editor.selected.map_or("none".to_string(), |id| format!("#{id}"));
+        text.0 = format!("{} | selected: {selected}\n{}", editor.name, editor.status);
+    }
+}
+
+pub fn graph_editor_buttons(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ButtonColors, &GraphEditorButton),
+        Changed<Interaction>,
+    >,
+    mut editor: ResMut<GraphEditor>,
+    asset_server: Res<AssetServer>,
+    selected_cells: Query<Entity, (With<SelectedCell>, With<GridCell>)>,
+) {
+    for (interaction, mut color, button_colors, action) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = SELECTED_COLOR.into();
+                apply_editor_action(action, &mut commands, &mut editor, &asset_server, &selected_cells);
+            }
+            Interaction::Hovered => {
+                *color = button_colors.hovered.into();
+            }
+            Interaction::None => {
+                *color = button_colors.normal.into();
+            }
+        }
+    }
+}
+
+fn apply_editor_action(
+    action: &GraphEditorButton,
+    commands: &mut Commands,
+    editor: &mut GraphEditor,
+    asset_server: &AssetServer,
+    selected_cells: &Query<Entity, (With<SelectedCell>, With<GridCell>)>,
+) {
+    editor.status.clear();
+    match action {
+        GraphEditorButton::AddNode(prototype) => {
+            let mut node = prototype.clone();
+            if let (MotorNode::Lfo { carrier, .. }, Some(selected)) = (&mut node, editor.selected) {
+                *carrier = selected;
+            }
+            let id = editor.graph.add_node(node);
+            if let Err(err) = editor.graph.validate() {
+                editor.graph.nodes.pop();
+                editor.status = err.to_string();
+                return;
+            }
+            editor.selected = Some(id);
+        }
+        GraphEditorButton::SelectNode(id) => editor.selected = Some(*id),
+        GraphEditorButton::Connect { node, slot } => {
+            let Some(source) = editor.selected else {
+                editor.status = "Select a source node first".to_string();
+                return;
+            };
+            if source == *node {
+                editor.status = "A node can't feed itself".to_string();
+                return;
+            }
+            let previous = editor.graph.nodes[*node].clone();
+            editor.graph.nodes[*node].connect(*slot, source);
+            if let Err(err) = editor.graph.validate() {
+                editor.graph.nodes[*node] = previous;
+                editor.status = err.to_string();
+            }
+        }
+        GraphEditorButton::AddOutput(target) => {
+            let Some(node) = editor.selected else {
+                editor.status = "Select a node to route".to_string();
+                return;
+            };
+            editor.graph.outputs.retain(|output| output.target != *target);
+            editor.graph.outputs.push(GraphOutput { node, target: *target });
+        }
+        GraphEditorButton::ScaleSelected(factor) => {
+            if let Some(id) = editor.selected {
+                editor.graph.nodes[id].scale_primary(*factor);
+            }
+        }
+        GraphEditorButton::RemoveLast => match editor.graph.remove_last() {
+            Ok(_) => {
+                let len = editor.graph.nodes.len();
+                editor.selected = editor.selected.filter(|id| *id < len);
+            }
+            Err(err) => editor.status = err.to_string(),
+        },
+        GraphEditorButton::Save => {
+            save_editor_graph(editor, asset_server);
+        }
+        GraphEditorButton::Load(name) => {
+            editor.pending_load = Some(asset_server.load(motor_graph_path(name)));
+            editor.name = name.clone();
+        }
+        GraphEditorButton::Assign => {
+            if let Err(err) = editor.graph.validate() {
+                editor.status = err.to_string();
+                return;
+            }
+            // Attach the saved file rather than a copy, so later saves reach these cells too
+            if !save_editor_graph(editor, asset_server) {
+                return;
+            }
+            let handle: Handle<MotorGraph> = asset_server.load(motor_graph_path(&editor.name));
+            for entity in selected_cells.iter() {
+                commands.entity(entity).insert(AttachedMotorGraph(handle.clone()));
+                log::info!("Attached motor graph {} to {:?}", editor.name, entity);
+            }
+        }
+        GraphEditorButton::Detach => {
+            for entity in selected_cells.iter() {
+                commands.entity(entity).remove::<AttachedMotorGraph>();
+            }
+        }
+        GraphEditorButton::Clear => {
+            editor.graph = MotorGraph::default();
+            editor.name = "untitled".to_string();
+            editor.selected = None;
+        }
+    }
+}
+
+/// Writes the editor graph to its file, naming it first if needed; false when saving failed
+fn save_editor_graph(editor: &mut GraphEditor, asset_server: &AssetServer) -> bool {
+    if editor.name == "untitled" {
+        editor.name = format!("graph_{}", editor.library.len() + 1);
+    }
+    match save_motor_graph(&editor.name, &editor.graph) {
+        Ok(()) => {
+            // Cells attached to this file pick up the new version
+            asset_server.reload(motor_graph_path(&editor.name));
+            editor.library = saved_motor_graphs();
+            editor.status = format!("Saved {}", motor_graph_path(&editor.name));
+            true
+        }
+        Err(err) => {
+            editor.status = err.to_string();
+            false
+        }
+    }
+}
+
+/// Moves a graph picked from the library into the editor once the asset server has it
+pub fn finish_graph_load(
+    mut editor: ResMut<GraphEditor>,
+    graphs: Res<Assets<MotorGraph>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(handle) = editor.bypass_change_detection().pending_load.clone() else {
+        return;
+    };
+    if let Some(LoadState::Failed(err)) = asset_server.get_load_state(&handle) {
+        editor.pending_load = None;
+        editor.status = err.to_string();
+    } else if let Some(graph) = graphs.get(&handle) {
+        editor.graph = graph.clone();
+        editor.selected = None;
+        editor.pending_load = None;
+        editor.status = format!("Loaded {}", motor_graph_path(&editor.name));
+    }
+}
+
+pub fn cleanup_graph_editor(
+    mut commands: Commands,
+    entities: Query<Entity, With<MotorGraphEditorEntity>>,
+    mut editor: ResMut<GraphEditor>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    editor.pending_load = None;
+}
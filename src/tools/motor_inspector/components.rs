@@ -0,0 +1,117 @@
+use std::f64::consts::TAU;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::motors::{BlendMode, Motor, MotorTarget, SyncRatio, Waveform};
+
+#[derive(Component)]
+pub struct MotorInspectorEntity;
+
+/// Container the channel sections are rebuilt into
+#[derive(Component)]
+pub struct InspectorContent;
+
+#[derive(Component)]
+pub struct InspectorTitle;
+
+/// Numeric motor parameter editable from the inspector
+///
+/// Frequency is shown and edited in Hz, while [`Motor::freq`] is stored in radians per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MotorParam {
+    Frequency,
+    Phase,
+    Amplitude,
+}
+
+impl MotorParam {
+    pub const ALL: [MotorParam; 3] = [MotorParam::Frequency, MotorParam::Phase, MotorParam::Amplitude];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MotorParam::Frequency => "freq Hz",
+            MotorParam::Phase => "phase",
+            MotorParam::Amplitude => "amp",
+        }
+    }
+
+    /// Slider range, values typed in by hand may go outside it
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            MotorParam::Frequency => (0.0, 2.0),
+            MotorParam::Phase => (0.0, 1.0),
+            MotorParam::Amplitude => (0.0, 1.0),
+        }
+    }
+
+    /// Amount the -/+ buttons change the value by
+    pub fn step(self) -> f32 {
+        match self {
+            MotorParam::Frequency => 0.01,
+            MotorParam::Phase | MotorParam::Amplitude => 0.05,
+        }
+    }
+
+    pub fn get(self, motor: &Motor) -> f32 {
+        match self {
+            MotorParam::Frequency => (motor.freq / TAU) as f32,
+            MotorParam::Phase => motor.phase,
+            MotorParam::Amplitude => motor.amp,
+        }
+    }
+
+    pub fn set(self, motor: &mut Motor, value: f32) {
+        match self {
+            MotorParam::Frequency => motor.freq = value.max(0.0) as f64 * TAU,
+            MotorParam::Phase => motor.phase = value.rem_euclid(1.0),
+            MotorParam::Amplitude => motor.amp = value,
+        }
+    }
+}
+
+/// Clickable track setting a parameter from the cursor position
+#[derive(Component, Debug, Clone)]
+pub struct ParamSlider {
+    pub channel: String,
+    pub param: MotorParam,
+}
+
+/// Filled part of a [`ParamSlider`]
+#[derive(Component, Debug, Clone)]
+pub struct ParamSliderFill {
+    pub channel: String,
+    pub param: MotorParam,
+}
+
+/// Shows the parameter value, or "mixed" when the selected cells disagree
+#[derive(Component, Debug, Clone)]
+pub struct ParamValueText {
+    pub channel: String,
+    pub param: MotorParam,
+}
+
+/// Shows a sync group's clock frequency
+#[derive(Component, Debug, Clone)]
+pub struct SyncGroupFreqText(pub String);
+
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum InspectorButton {
+    Step { channel: String, param: MotorParam, delta: f32 },
+    /// Starts typing a value for the parameter
+    Edit { channel: String, param: MotorParam },
+    Waveform { channel: String, waveform: Waveform },
+    Target { channel: String, target: MotorTarget },
+    Blend { channel: String, blend: BlendMode },
+    /// Joins a sync group, or runs free on the channel's own frequency when `group` is `None`
+    Sync { channel: String, group: Option<String> },
+    SyncRatio { channel: String, ratio: SyncRatio },
+    GroupFreq { group: String, delta: f32 },
+    /// New sync group clocked at the first selected channel's frequency
+    AddSyncGroup,
+    ResyncAll,
+    MoveUp(String),
+    MoveDown(String),
+    Remove(String),
+    AddChannel,
+}
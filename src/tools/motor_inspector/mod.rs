@@ -0,0 +1,10 @@
+mod components;
+mod plugin;
+mod resources;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use plugin::MotorInspectorPlugin;
+
+pub use components::MotorParam;
+pub use resources::MotorInspector;
@@ -0,0 +1,36 @@
+use crate::tools::motor_inspector::resources::MotorInspector;
+use crate::tools::motor_inspector::systems::{
+    cleanup_motor_inspector, drag_param_sliders, inspector_buttons, numeric_entry,
+    rebuild_motor_inspector, refresh_inspector_values, spawn_motor_inspector,
+};
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct MotorInspectorPlugin;
+
+impl Plugin for MotorInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<MotorInspector>()
+            .add_systems(
+                OnEnter(GameState::GridAndMotors),
+                spawn_motor_inspector,
+            )
+            .add_systems(
+                Update,
+                (
+                    rebuild_motor_inspector,
+                    inspector_buttons,
+                    drag_param_sliders,
+                    numeric_entry,
+                    refresh_inspector_values,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::GridAndMotors)),
+            )
+            .add_systems(
+                OnExit(GameState::GridAndMotors),
+                cleanup_motor_inspector
+            );
+    }
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::tools::motor_inspector::components::MotorParam;
+
+/// Value being typed into a parameter field
+#[derive(Debug, Clone)]
+pub struct NumericEdit {
+    pub channel: String,
+    pub param: MotorParam,
+    pub buffer: String,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct MotorInspector {
+    /// Selected cells and their channel names the panel was last built for
+    pub layout: Vec<(Entity, Vec<String>)>,
+    /// Sync group names the panel was last built for
+    pub groups: Vec<String>,
+    pub editing: Option<NumericEdit>,
+}
@@ -0,0 +1,654 @@
+use std::f64::consts::TAU;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+use crate::systems::loading::FontAssets;
+use crate::tools::motor_inspector::components::{
+    InspectorButton, InspectorContent, InspectorTitle, MotorInspectorEntity, MotorParam,
+    ParamSlider, ParamSliderFill, ParamValueText, SyncGroupFreqText,
+};
+use crate::tools::motor_inspector::resources::{MotorInspector, NumericEdit};
+use crate::tools::motor_scope::{motor_scope, ScopeSource};
+use crate::tools::motors::{
+    BlendMode, Motor, MotorChannel, MotorTarget, Motors, ResyncMotors, SyncGroups, SyncMember, SyncRatio, Waveform,
+};
+use crate::tools::transport::Transport;
+use crate::tools::tile_map_grid::components::{GridCell, SelectedCell};
+use crate::ui::components::ButtonColors;
+use crate::ui::font_utils::text_geist_regular_with_font;
+use crate::ui::KeyboardFocus;
+
+const PANEL_WIDTH: f32 = 280.0;
+const KEYBOARD_OWNER: &str = "motor_inspector";
+const ACTIVE_COLOR: Color = Color::linear_rgb(0.2, 0.35, 0.6);
+const TRACK_COLOR: Color = Color::linear_rgb(0.2, 0.2, 0.2);
+const FILL_COLOR: Color = Color::linear_rgb(0.35, 0.55, 0.9);
+const MIXED_FILL_COLOR: Color = Color::linear_rgb(0.45, 0.45, 0.45);
+
+/// Value shared by every selected cell that has the channel
+enum Shared<T> {
+    None,
+    Same(T),
+    Mixed,
+}
+
+fn shared<T: PartialEq>(values: impl IntoIterator<Item = T>) -> Shared<T> {
+    let mut result = Shared::None;
+    for value in values {
+        result = match result {
+            Shared::None => Shared::Same(value),
+            Shared::Same(current) if current == value => Shared::Same(current),
+            _ => return Shared::Mixed,
+        };
+    }
+    result
+}
+
+fn channels_named<'a>(
+    selected: impl IntoIterator<Item = &'a Motors>,
+    name: &'a str,
+) -> impl Iterator<Item = &'a MotorChannel> {
+    selected.into_iter().filter_map(move |motors| motors.get(name))
+}
+
+pub fn spawn_motor_inspector(mut commands: Commands, fonts: Res<FontAssets>, mut inspector: ResMut<MotorInspector>) {
+    // Force a rebuild for whatever is selected when the space opens
+    inspector.layout.clear();
+    inspector.editing = None;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(80.0),
+                width: Val::Px(PANEL_WIDTH),
+                max_height: Val::Percent(85.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(8.0),
+                overflow: Overflow::clip_y(),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            MotorInspectorEntity,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                text_geist_regular_with_font("Motors", 18.0, Color::WHITE, &fonts),
+                InspectorTitle,
+            ));
+            panel.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                InspectorContent,
+            ));
+        });
+}
+
+fn row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        flex_wrap: FlexWrap::Wrap,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(4.0),
+        row_gap: Val::Px(4.0),
+        ..default()
+    }
+}
+
+fn spawn_inspector_button(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    action: InspectorButton,
+    fonts: &FontAssets,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(ButtonColors::default().normal),
+            BorderRadius::all(Val::Px(3.0)),
+            ButtonColors::default(),
+            action,
+        ))
+        .with_children(|button| {
+            button.spawn(text_geist_regular_with_font(label, 12.0, Color::WHITE, fonts));
+        });
+}
+
+/// Rebuilds the channel sections when the selection or its channel list changes
+pub fn rebuild_motor_inspector(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    mut inspector: ResMut<MotorInspector>,
+    selected: Query<(Entity, Option<&Motors>), (With<SelectedCell>, With<GridCell>)>,
+    contents: Query<Entity, With<InspectorContent>>,
+    mut titles: Query<&mut Text, With<InspectorTitle>>,
+    sync: Res<SyncGroups>,
+) {
+    let mut layout: Vec<(Entity, Vec<String>)> = selected
+        .iter()
+        .map(|(entity, motors)| {
+            let names = motors.map_or(Vec::new(), |motors| {
+                motors.channels.iter().map(|channel| channel.name.clone()).collect()
+            });
+            (entity, names)
+        })
+        .collect();
+    layout.sort_by_key(|(entity, _)| *entity);
+    let groups: Vec<String> = sync.groups.iter().map(|group| group.name.clone()).collect();
+    if layout == inspector.layout && groups == inspector.groups {
+        return;
+    }
+
+    // Channel names in the order they first appear across the selection
+    let mut names: Vec<String> = Vec::new();
+    for (_, cell_names) in &layout {
+        for name in cell_names {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    for mut title in titles.iter_mut() {
+        title.0 = match layout.len() {
+            0 => "Motors - select a cell".to_string(),
+            1 => "Motors - 1 cell".to_string(),
+            n => format!("Motors - {n} cells"),
+        };
+    }
+
+    let cell_count = layout.len();
+    for content in contents.iter() {
+        commands.entity(content).despawn_related::<Children>().with_children(|content| {
+            for name in &names {
+                let owners = layout.iter().filter(|(_, cell_names)| cell_names.contains(name)).count();
+                spawn_channel_section(content, name, owners, cell_count, &groups, &fonts);
+            }
+            if cell_count > 0 {
+                content.spawn(row()).with_children(|row| {
+                    spawn_inspector_button(row, "+ channel", InspectorButton::AddChannel, &fonts);
+                    spawn_inspector_button(row, "+ sync group", InspectorButton::AddSyncGroup, &fonts);
+                });
+            }
+            if !groups.is_empty() {
+                spawn_sync_groups_section(content, &groups, &fonts);
+            }
+        });
+    }
+
+    inspector.editing = None;
+    inspector.layout = layout;
+    inspector.groups = groups;
+}
+
+fn spawn_channel_section(
+    parent: &mut ChildSpawnerCommands,
+    name: &str,
+    owners: usize,
+    cell_count: usize,
+    groups: &[String],
+    fonts: &FontAssets,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|section| {
+            section.spawn(row()).with_children(|header| {
+                let label = if owners == cell_count {
+                    name.to_string()
+                } else {
+                    format!("{name} ({owners}/{cell_count})")
+                };
+                header.spawn(text_geist_regular_with_font(&label, 14.0, Color::WHITE, fonts));
+                spawn_inspector_button(header, "up", InspectorButton::MoveUp(name.to_string()), fonts);
+                spawn_inspector_button(header, "down", InspectorButton::MoveDown(name.to_string()), fonts);
+                spawn_inspector_button(header, "x", InspectorButton::Remove(name.to_string()), fonts);
+            });
+            section.spawn(motor_scope(ScopeSource::Channel(name.to_string()), 240.0, 48.0));
+
+            for param in MotorParam::ALL {
+                section.spawn(row()).with_children(|param_row| {
+                    param_row.spawn((
+                        text_geist_regular_with_font(param.label(), 12.0, Color::WHITE, fonts),
+                        Node { width: Val::Px(48.0), ..default() },
+                    ));
+                    param_row
+                        .spawn((
+                            Button,
+                            Node {
+                                width: Val::Px(100.0),
+                                height: Val::Px(10.0),
+                                ..default()
+                            },
+                            BackgroundColor(TRACK_COLOR),
+                            RelativeCursorPosition::default(),
+                            ParamSlider { channel: name.to_string(), param },
+                        ))
+                        .with_children(|track| {
+                            track.spawn((
+                                Node {
+                                    width: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                BackgroundColor(FILL_COLOR),
+                                ParamSliderFill { channel: name.to_string(), param },
+                            ));
+                        });
+                    spawn_inspector_button(
+                        param_row,
+                        "-",
+                        InspectorButton::Step { channel: name.to_string(), param, delta: -param.step() },
+                        fonts,
+                    );
+                    param_row
+                        .spawn((
+                            Button,
+                            Node {
+                                width: Val::Px(48.0),
+                                ..default()
+                            },
+                            BackgroundColor(ButtonColors::default().normal),
+                            ButtonColors::default(),
+                            InspectorButton::Edit { channel: name.to_string(), param },
+                        ))
+                        .with_children(|field| {
+                            field.spawn((
+                                text_geist_regular_with_font("", 12.0, Color::WHITE, fonts),
+                                ParamValueText { channel: name.to_string(), param },
+                            ));
+                        });
+                    spawn_inspector_button(
+                        param_row,
+                        "+",
+                        InspectorButton::Step { channel: name.to_string(), param, delta: param.step() },
+                        fonts,
+                    );
+                });
+            }
+
+            section.spawn(row()).with_children(|waveforms| {
+                for waveform in [Waveform::Sine, Waveform::Triangle, Waveform::Square, Waveform::Saw] {
+                    spawn_inspector_button(
+                        waveforms,
+                        &format!("{waveform:?}"),
+                        InspectorButton::Waveform { channel: name.to_string(), waveform },
+                        fonts,
+                    );
+                }
+            });
+            section.spawn(row()).with_children(|targets| {
+                for target in MotorTarget::ALL {
+                    spawn_inspector_button(
+                        targets,
+                        &format!("{target:?}"),
+                        InspectorButton::Target { channel: name.to_string(), target },
+                        fonts,
+                    );
+                }
+            });
+            section.spawn(row()).with_children(|blends| {
+                for blend in [BlendMode::Replace, BlendMode::Add, BlendMode::Multiply, BlendMode::Max] {
+                    spawn_inspector_button(
+                        blends,
+                        &format!("{blend:?}"),
+                        InspectorButton::Blend { channel: name.to_string(), blend },
+                        fonts,
+                    );
+                }
+            });
+            if groups.is_empty() {
+                return;
+            }
+            section.spawn(row()).with_children(|sync| {
+                spawn_inspector_button(sync, "free", InspectorButton::Sync { channel: name.to_string(), group: None }, fonts);
+                for group in groups {
+                    spawn_inspector_button(
+                        sync,
+                        group,
+                        InspectorButton::Sync { channel: name.to_string(), group: Some(group.clone()) },
+                        fonts,
+                    );
+                }
+            });
+            section.spawn(row()).with_children(|ratios| {
+                for ratio in SyncRatio::COMMON {
+                    spawn_inspector_button(
+                        ratios,
+                        &ratio.to_string(),
+                        InspectorButton::SyncRatio { channel: name.to_string(), ratio },
+                        fonts,
+                    );
+                }
+            });
+        });
+}
+
+/// Group clocks with their frequency and a button restarting them all together
+fn spawn_sync_groups_section(parent: &mut ChildSpawnerCommands, groups: &[String], fonts: &FontAssets) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|section| {
+            section.spawn(row()).with_children(|header| {
+                header.spawn(text_geist_regular_with_font("Sync groups", 14.0, Color::WHITE, fonts));
+                spawn_inspector_button(header, "resync all", InspectorButton::ResyncAll, fonts);
+            });
+            for group in groups {
+                section.spawn(row()).with_children(|group_row| {
+                    group_row.spawn((
+                        text_geist_regular_with_font(group, 12.0, Color::WHITE, fonts),
+                        Node { width: Val::Px(64.0), ..default() },
+                    ));
+                    let step = MotorParam::Frequency.step();
+                    spawn_inspector_button(group_row, "-", InspectorButton::GroupFreq { group: group.clone(), delta: -step }, fonts);
+                    group_row.spawn((
+                        text_geist_regular_with_font("", 12.0, Color::WHITE, fonts),
+                        Node { width: Val::Px(56.0), ..default() },
+                        SyncGroupFreqText(group.clone()),
+                    ));
+                    spawn_inspector_button(group_row, "+", InspectorButton::GroupFreq { group: group.clone(), delta: step }, fonts);
+                });
+            }
+        });
+}
+
+pub fn inspector_buttons(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &InspectorButton), Changed<Interaction>>,
+    mut inspector: ResMut<MotorInspector>,
+    mut selected: Query<(Entity, Option<&mut Motors>), (With<SelectedCell>, With<GridCell>)>,
+    mut sync: ResMut<SyncGroups>,
+    transport: Res<Transport>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            InspectorButton::Edit { channel, param } => {
+                inspector.editing = Some(NumericEdit {
+                    channel: channel.clone(),
+                    param: *param,
+                    buffer: String::new(),
+                });
+            }
+            InspectorButton::AddChannel => {
+                let taken: Vec<String> = selected
+                    .iter()
+                    .filter_map(|(_, motors)| motors)
+                    .flat_map(|motors| motors.channels.iter().map(|channel| channel.name.clone()))
+                    .collect();
+                let name = (1..)
+                    .map(|n| format!("channel {n}"))
+                    .find(|name| !taken.contains(name))
+                    .unwrap_or_default();
+                for (entity, motors) in selected.iter_mut() {
+                    let channel = MotorChannel::new(name.clone(), Motor::default(), MotorTarget::Hue);
+                    match motors {
+                        Some(mut motors) => motors.add(channel),
+                        None => {
+                            commands.entity(entity).insert(Motors { channels: vec![channel] });
+                        }
+                    }
+                }
+            }
+            InspectorButton::AddSyncGroup => {
+                let freq = selected
+                    .iter()
+                    .filter_map(|(_, motors)| motors?.channels.first().map(|channel| channel.motor.freq))
+                    .next()
+                    .unwrap_or(Motor::default().freq);
+                let name = sync.add(freq, transport.seconds());
+                log::info!("Added sync group {name}");
+            }
+            InspectorButton::GroupFreq { group, delta } => {
+                if let Some(group) = sync.get_mut(group) {
+                    // Stepped in Hz like the channel frequencies
+                    group.freq = (group.freq / TAU + *delta as f64).max(0.0) * TAU;
+                }
+            }
+            InspectorButton::ResyncAll => commands.trigger(ResyncMotors),
+            InspectorButton::Remove(channel) => {
+                for (entity, motors) in selected.iter_mut() {
+                    let Some(mut motors) = motors else {
+                        continue;
+                    };
+                    motors.remove(channel);
+                    if motors.is_empty() {
+                        commands.entity(entity).remove::<Motors>();
+                    }
+                }
+            }
+            _ => {
+                for (_, motors) in selected.iter_mut() {
+                    if let Some(mut motors) = motors {
+                        apply_channel_action(action, &mut motors);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn apply_channel_action(action: &InspectorButton, motors: &mut Motors) {
+    match action {
+        InspectorButton::Step { channel, param, delta } => {
+            if let Some(channel) = motors.get_mut(channel) {
+                let value = param.get(&channel.motor) + delta;
+                param.set(&mut channel.motor, value);
+            }
+        }
+        InspectorButton::Waveform { channel, waveform } => {
+            if let Some(channel) = motors.get_mut(channel) {
+                channel.motor.waveform = *waveform;
+            }
+        }
+        InspectorButton::Target { channel, target } => {
+            if let Some(channel) = motors.get_mut(channel) {
+                channel.target = *target;
+            }
+        }
+        InspectorButton::Blend { channel, blend } => {
+            if let Some(channel) = motors.get_mut(channel) {
+                channel.blend = *blend;
+            }
+        }
+        InspectorButton::Sync { channel, group } => {
+            if let Some(channel) = motors.get_mut(channel) {
+                let ratio = channel.sync.as_ref().map_or(SyncRatio::ONE, |member| member.ratio);
+                channel.sync = group.clone().map(|group| SyncMember { group, ratio });
+            }
+        }
+        InspectorButton::SyncRatio { channel, ratio } => {
+            if let Some(member) = motors.get_mut(channel).and_then(|channel| channel.sync.as_mut()) {
+                member.ratio = *ratio;
+            }
+        }
+        InspectorButton::MoveUp(channel) => {
+            if let Some(index) = motors.channels.iter().position(|c| &c.name == channel) {
+                if index > 0 {
+                    motors.reorder(index, index - 1);
+                }
+            }
+        }
+        InspectorButton::MoveDown(channel) => {
+            if let Some(index) = motors.channels.iter().position(|c| &c.name == channel) {
+                motors.reorder(index, index + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Sets the parameter from the cursor position while a slider is held down
+pub fn drag_param_sliders(
+    sliders: Query<(&Interaction, &RelativeCursorPosition, &ParamSlider)>,
+    mut selected: Query<&mut Motors, (With<SelectedCell>, With<GridCell>)>,
+) {
+    for (interaction, cursor, slider) in sliders.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let (min, max) = slider.param.range();
+        let value = min + position.x.clamp(0.0, 1.0) * (max - min);
+        for mut motors in selected.iter_mut() {
+            if let Some(channel) = motors.get_mut(&slider.channel) {
+                slider.param.set(&mut channel.motor, value);
+            }
+        }
+    }
+}
+
+/// Typed entry for the parameter field that was clicked last
+pub fn numeric_entry(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut inspector: ResMut<MotorInspector>,
+    mut focus: ResMut<KeyboardFocus>,
+    mut selected: Query<&mut Motors, (With<SelectedCell>, With<GridCell>)>,
+) {
+    if inspector.editing.is_none() {
+        focus.release(KEYBOARD_OWNER);
+        keyboard_events.clear();
+        return;
+    }
+    focus.claim(KEYBOARD_OWNER);
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let Some(edit) = inspector.editing.as_mut() else {
+            return;
+        };
+        match &event.logical_key {
+            Key::Character(chars) => {
+                for c in chars.chars().filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-') {
+                    edit.buffer.push(c);
+                }
+            }
+            Key::Backspace => {
+                edit.buffer.pop();
+            }
+            Key::Escape => inspector.editing = None,
+            Key::Enter => {
+                if let Ok(value) = edit.buffer.parse::<f32>() {
+                    for mut motors in selected.iter_mut() {
+                        if let Some(channel) = motors.get_mut(&edit.channel) {
+                            edit.param.set(&mut channel.motor, value);
+                        }
+                    }
+                }
+                inspector.editing = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Keeps value labels, slider fills and option highlights in sync with the selection
+pub fn refresh_inspector_values(
+    inspector: Res<MotorInspector>,
+    selected: Query<&Motors, (With<SelectedCell>, With<GridCell>)>,
+    mut value_texts: Query<(&mut Text, &ParamValueText)>,
+    mut fills: Query<(&mut Node, &mut BackgroundColor, &ParamSliderFill), Without<InspectorButton>>,
+    mut buttons: Query<(&Interaction, &mut BackgroundColor, &ButtonColors, &InspectorButton), Without<ParamSliderFill>>,
+    mut group_texts: Query<(&mut Text, &SyncGroupFreqText), Without<ParamValueText>>,
+    sync: Res<SyncGroups>,
+) {
+    for (mut text, field) in value_texts.iter_mut() {
+        let editing = inspector
+            .editing
+            .as_ref()
+            .filter(|edit| edit.channel == field.channel && edit.param == field.param);
+        text.0 = match editing {
+            Some(edit) => format!("{}_", edit.buffer),
+            None => match shared(channels_named(selected.iter(), &field.channel).map(|c| field.param.get(&c.motor))) {
+                Shared::Same(value) => format!("{value:.2}"),
+                Shared::Mixed => "mixed".to_string(),
+                Shared::None => String::new(),
+            },
+        };
+    }
+
+    for (mut text, field) in group_texts.iter_mut() {
+        if let Some(group) = sync.get(&field.0) {
+            text.0 = format!("{:.2} Hz", group.freq / TAU);
+        }
+    }
+
+    for (mut node, mut color, fill) in fills.iter_mut() {
+        let values: Vec<f32> = channels_named(selected.iter(), &fill.channel)
+            .map(|c| fill.param.get(&c.motor))
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        let mixed = matches!(shared(values.iter().copied()), Shared::Mixed);
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let (min, max) = fill.param.range();
+        node.width = Val::Percent(((mean - min) / (max - min)).clamp(0.0, 1.0) * 100.0);
+        *color = if mixed { MIXED_FILL_COLOR } else { FILL_COLOR }.into();
+    }
+
+    for (interaction, mut color, button_colors, action) in buttons.iter_mut() {
+        let active = match action {
+            InspectorButton::Waveform { channel, waveform } => {
+                matches!(shared(channels_named(selected.iter(), channel).map(|c| c.motor.waveform)), Shared::Same(w) if w == *waveform)
+            }
+            InspectorButton::Target { channel, target } => {
+                matches!(shared(channels_named(selected.iter(), channel).map(|c| c.target)), Shared::Same(t) if t == *target)
+            }
+            InspectorButton::Blend { channel, blend } => {
+                matches!(shared(channels_named(selected.iter(), channel).map(|c| c.blend)), Shared::Same(b) if b == *blend)
+            }
+            InspectorButton::Sync { channel, group } => {
+                let groups = channels_named(selected.iter(), channel).map(|c| c.sync.as_ref().map(|member| &member.group));
+                matches!(shared(groups), Shared::Same(g) if g == group.as_ref())
+            }
+            InspectorButton::SyncRatio { channel, ratio } => {
+                let ratios = channels_named(selected.iter(), channel).map(|c| c.sync.as_ref().map(|member| member.ratio));
+                matches!(shared(ratios), Shared::Same(Some(r)) if r == *ratio)
+            }
+            _ => false,
+        };
+        let next = if active {
+            ACTIVE_COLOR
+        } else if *interaction == Interaction::Hovered {
+            button_colors.hovered
+        } else {
+            button_colors.normal
+        };
+        if color.0 != next {
+            color.0 = next;
+        }
+    }
+}
+
+pub fn cleanup_motor_inspector(
+    mut commands: Commands,
+    entities: Query<Entity, With<MotorInspectorEntity>>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+/// Seconds of output shown in the rolling part of a scope
+const DEFAULT_WINDOW: f32 = 4.0;
+
+/// Where a scope reads its motor from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScopeSource {
+    /// Palette preset with this name
+    Preset(String),
+    /// Channel with this name on the first selected cell that has it
+    Channel(String),
+}
+
+/// Plot of a motor's recent output next to one period of its waveform
+#[derive(Component, Debug, Clone)]
+pub struct MotorScope {
+    pub source: ScopeSource,
+    /// Image resolution in pixels
+    pub size: UVec2,
+    pub window: f32,
+}
+
+/// Scope widget node `width` x `height` pixels big, ready to spawn inside any UI panel
+pub fn motor_scope(source: ScopeSource, width: f32, height: f32) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            flex_shrink: 0.0,
+            ..default()
+        },
+        MotorScope {
+            source,
+            size: UVec2::new(width as u32, height as u32),
+            window: DEFAULT_WINDOW,
+        },
+    )
+}
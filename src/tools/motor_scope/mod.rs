@@ -0,0 +1,8 @@
+mod components;
+mod plot;
+mod plugin;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use components::{motor_scope, ScopeSource};
+pub use plugin::MotorScopePlugin;
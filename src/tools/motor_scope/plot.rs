@@ -0,0 +1,94 @@
+//! CPU plotting for scope images. UI panels draw over gizmos and world meshes,
+//! so scopes are textures shown with an `ImageNode` instead.
+
+use crate::tools::motors::{MotorChannel, SyncGroups};
+
+pub const BACKGROUND: [u8; 4] = [16, 16, 16, 255];
+const AXIS: [u8; 4] = [56, 56, 56, 255];
+const TRACE: [u8; 4] = [90, 140, 230, 255];
+const MARKER: [u8; 4] = [240, 160, 40, 255];
+
+struct Canvas<'a> {
+    data: &'a mut [u8],
+    width: usize,
+    height: usize,
+}
+
+impl Canvas<'_> {
+    fn put(&mut self, x: usize, y: usize, color: [u8; 4]) {
+        if x < self.width && y < self.height {
+            let index = (y * self.width + x) * 4;
+            self.data[index..index + 4].copy_from_slice(&color);
+        }
+    }
+
+    fn vline(&mut self, x: usize, from: usize, to: usize, color: [u8; 4]) {
+        for y in from.min(to)..=from.max(to) {
+            self.put(x, y, color);
+        }
+    }
+
+    fn hline(&mut self, from: usize, to: usize, y: usize, color: [u8; 4]) {
+        for x in from..to {
+            self.put(x, y, color);
+        }
+    }
+
+    /// Pixel row for a value in [-scale, scale], top row is the maximum
+    fn row(&self, value: f32, scale: f32) -> usize {
+        let normalized = ((1.0 - value / scale) * 0.5).clamp(0.0, 1.0);
+        (normalized * (self.height - 1) as f32).round() as usize
+    }
+
+    /// Connected trace over columns `from..to`, `sample` maps 0..1 across the span to a value
+    fn trace(&mut self, from: usize, to: usize, scale: f32, sample: impl Fn(f32) -> f32) {
+        let span = (to - from).max(2) - 1;
+        let mut previous = None;
+        for x in from..to {
+            let row = self.row(sample((x - from) as f32 / span as f32), scale);
+            self.vline(x, previous.unwrap_or(row), row, TRACE);
+            previous = Some(row);
+        }
+    }
+
+    /// Vertical line with a dot where the trace is
+    fn marker(&mut self, x: usize, row: usize) {
+        self.vline(x, 0, self.height - 1, MARKER);
+        for dx in 0..3 {
+            for dy in 0..3 {
+                self.put((x + dx).saturating_sub(1), (row + dy).saturating_sub(1), MARKER);
+            }
+        }
+    }
+}
+
+pub fn clear(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&BACKGROUND);
+    }
+}
+
+/// Last `window` seconds up to transport time `t` on the left two thirds, one waveform period on the right;
+/// both mark where the transport is now
+pub fn plot_scope(data: &mut [u8], width: usize, height: usize, channel: &MotorChannel, sync: &SyncGroups, t: f32, window: f32) {
+    clear(data);
+    if width < 8 || height < 4 {
+        return;
+    }
+    let mut canvas = Canvas { data, width, height };
+    let split = width * 2 / 3;
+    let scale = channel.motor.amp.abs().max(1.0);
+    let center = canvas.row(0.0, scale);
+    canvas.hline(0, width, center, AXIS);
+    canvas.vline(split, 0, height - 1, AXIS);
+
+    canvas.trace(0, split, scale, |u| channel.value(t - window * (1.0 - u), sync));
+    canvas.marker(split - 1, canvas.row(channel.value(t, sync), scale));
+
+    // The period view shows the bare waveform, the marker carries the phase offset
+    let motor = &channel.motor;
+    canvas.trace(split + 1, width, scale, |u| motor.value_at_phase(u - motor.phase));
+    let position = (channel.cycles(t, sync) + motor.phase).rem_euclid(1.0);
+    let x = split + 1 + (position * (width - split - 2) as f32).round() as usize;
+    canvas.marker(x, canvas.row(channel.value(t, sync), scale));
+}
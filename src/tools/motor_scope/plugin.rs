@@ -0,0 +1,16 @@
+use crate::tools::motor_scope::systems::{init_motor_scopes, update_motor_scopes};
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct MotorScopePlugin;
+
+impl Plugin for MotorScopePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (init_motor_scopes, update_motor_scopes)
+                .chain()
+                .run_if(in_state(GameState::GridAndMotors)),
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::tools::motor_scope::components::{MotorScope, ScopeSource};
+use crate::tools::motor_scope::plot::{clear, plot_scope, BACKGROUND};
+use crate::tools::motors::{MotorPreset, MotorPresetLibrary, Motors, SyncGroups};
+use crate::tools::tile_map_grid::components::{GridCell, SelectedCell};
+use crate::tools::transport::Transport;
+
+/// Gives new scopes an image to draw into
+pub fn init_motor_scopes(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    scopes: Query<(Entity, &MotorScope), Added<MotorScope>>,
+) {
+    for (entity, scope) in scopes.iter() {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: scope.size.x.max(1),
+                height: scope.size.y.max(1),
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &BACKGROUND,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.sampler = ImageSampler::nearest();
+        commands.entity(entity).insert(ImageNode::new(images.add(image)));
+    }
+}
+
+pub fn update_motor_scopes(
+    scopes: Query<(&MotorScope, &ImageNode)>,
+    mut images: ResMut<Assets<Image>>,
+    library: Res<MotorPresetLibrary>,
+    selected: Query<&Motors, (With<SelectedCell>, With<GridCell>)>,
+    sync: Res<SyncGroups>,
+    transport: Res<Transport>,
+) {
+    let t = transport.seconds();
+    for (scope, node) in scopes.iter() {
+        let channel = match &scope.source {
+            ScopeSource::Preset(name) => library.get(name).map(MotorPreset::to_channel),
+            ScopeSource::Channel(name) => selected.iter().find_map(|motors| motors.get(name)).cloned(),
+        };
+        let Some(data) = images.get_mut(&node.image).and_then(|image| image.data.as_mut()) else {
+            continue;
+        };
+        match channel {
+            Some(channel) => plot_scope(
+                data,
+                scope.size.x as usize,
+                scope.size.y as usize,
+                &channel,
+                &sync,
+                t,
+                scope.window,
+            ),
+            None => clear(data),
+        }
+    }
+}
@@ -1,18 +1,66 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::motors::{MotorFadeSettings, SyncGroups, SyncMember, Waveform};
+use crate::tools::tile_map_grid::components::GridCell;
 
 #[derive(Component)]
 pub struct MotorsContainer;
 
-#[derive(Component, Debug, Clone)]
-pub struct Background;
-
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Motor {
     pub freq: f64,    
+    /// Phase offset in cycles
+    pub phase: f32,
+    pub amp: f32,
+    pub waveform: Waveform,
+}
+
+impl Default for Motor {
+    fn default() -> Self {
+        Self {
+            freq: 1.0,
+            phase: 0.0,
+            amp: 1.0,
+            waveform: Waveform::Sine,
+        }
+    }
+}
+
+impl Motor {
+    pub fn new(freq: f64) -> Self {
+        Self { freq, ..default() }
+    }
+
+    /// Motor output at time `t`, in [-amp, amp]; `freq` is in radians per second
+    pub fn value(&self, t: f32) -> f32 {
+        self.value_at_phase(t * self.freq as f32 / std::f32::consts::TAU)
+    }
+
+    /// Motor output at `cycles` into the waveform, offset by the motor's own phase
+    pub fn value_at_phase(&self, cycles: f32) -> f32 {
+        self.amp * self.waveform.sample(cycles + self.phase)
+    }
 }
+/// Palette button applying the preset with this name
 #[derive(Component, Debug, Clone)]
 pub struct MotorButton {
-    pub freq: f64,
+    pub preset: String,
+}
+
+/// Row of preset buttons in the palette, rebuilt when the library changes
+#[derive(Component)]
+pub struct MotorPresetList;
+
+#[derive(Component)]
+pub struct MotorPresetStatus;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetToolButton {
+    Save,
+    Duplicate,
+    Rename,
+    Delete,
 }
 
 #[derive(Component)]
@@ -21,3 +69,323 @@ pub struct BackButton;
 #[derive(Component)]
 pub struct MotorsEntity;
 
+/// Sprite showing the motor output texture
+#[derive(Component)]
+pub struct MotorTextureSprite;
+
+/// Cell property a motor value is written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MotorTarget {
+    #[default]
+    Hue,
+    Saturation,
+    Lightness,
+    Alpha,
+    Scale,
+    Rotation,
+}
+
+/// Color motored cells start from before their channels are applied
+pub const MOTOR_BASE_COLOR: Hsla = Hsla::new(0.0, 0.8, 0.6, 1.0);
+
+impl MotorTarget {
+    pub const ALL: [MotorTarget; 6] = [
+        MotorTarget::Hue,
+        MotorTarget::Saturation,
+        MotorTarget::Lightness,
+        MotorTarget::Alpha,
+        MotorTarget::Scale,
+        MotorTarget::Rotation,
+    ];
+
+    /// Position in [`MotorTarget::ALL`]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Writes a motor value, roughly in [-1, 1], to the targeted property
+    pub fn apply(self, value: f32, color: &mut Hsla, transform: &mut Transform) {
+        match self {
+            MotorTarget::Hue => color.hue = (value * 360.0).abs() % 360.0,
+            MotorTarget::Saturation => color.saturation = value.abs().clamp(0.0, 1.0),
+            MotorTarget::Lightness => color.lightness = value.abs().clamp(0.0, 1.0),
+            MotorTarget::Alpha => color.alpha = value.abs().clamp(0.0, 1.0),
+            MotorTarget::Scale => {
+                let scale = (1.0 + value).max(0.0);
+                transform.scale = Vec3::new(scale, scale, transform.scale.z);
+            }
+            MotorTarget::Rotation => {
+                transform.rotation = Quat::from_rotation_z(value * std::f32::consts::PI);
+            }
+        }
+    }
+}
+
+/// How a channel combines with earlier channels driving the same property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Discards whatever earlier channels wrote
+    Replace,
+    #[default]
+    Add,
+    Multiply,
+    Max,
+}
+
+impl BlendMode {
+    /// Combines `value` into the property's accumulated value, if any
+    pub fn blend(self, current: Option<f32>, value: f32) -> f32 {
+        let Some(current) = current else {
+            return value;
+        };
+        match self {
+            BlendMode::Replace => value,
+            BlendMode::Add => current + value,
+            BlendMode::Multiply => current * value,
+            BlendMode::Max => current.max(value),
+        }
+    }
+}
+
+/// Named motor driving one property of a cell
+#[derive(Debug, Clone)]
+pub struct MotorChannel {
+    pub name: String,
+    pub motor: Motor,
+    pub target: MotorTarget,
+    pub blend: BlendMode,
+    pub sync: Option<SyncMember>,
+}
+
+impl MotorChannel {
+    pub fn new(name: impl Into<String>, motor: Motor, target: MotorTarget) -> Self {
+        Self {
+            name: name.into(),
+            motor,
+            target,
+            blend: BlendMode::default(),
+            sync: None,
+        }
+    }
+
+    /// Output at transport time `t`, following the sync group clock when the channel is a member
+    pub fn value(&self, t: f32, groups: &SyncGroups) -> f32 {
+        self.motor.value_at_phase(self.cycles(t, groups))
+    }
+
+    /// Cycles elapsed at transport time `t`, before the motor's own phase offset
+    pub fn cycles(&self, t: f32, groups: &SyncGroups) -> f32 {
+        let member = self.sync.as_ref().and_then(|member| Some((member, groups.get(&member.group)?)));
+        match member {
+            Some((member, group)) => group.phase(t) * member.ratio.factor(),
+            None => t * self.motor.freq as f32 / std::f32::consts::TAU,
+        }
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+}
+
+/// Ordered motor channels on a grid cell, later channels blend over earlier ones
+#[derive(Component, Debug, Clone, Default)]
+pub struct Motors {
+    pub channels: Vec<MotorChannel>,
+}
+
+impl Motors {
+    pub fn get(&self, name: &str) -> Option<&MotorChannel> {
+        self.channels.iter().find(|channel| channel.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut MotorChannel> {
+        self.channels.iter_mut().find(|channel| channel.name == name)
+    }
+
+    /// Appends `channel`, replacing any existing channel with the same name in place
+    pub fn add(&mut self, channel: MotorChannel) {
+        if let Some(existing) = self.get_mut(&channel.name) {
+            *existing = channel;
+        } else {
+            self.channels.push(channel);
+        }
+    }
+
+    /// Removes the channel called `name`, returning it if it existed
+    pub fn remove(&mut self, name: &str) -> Option<MotorChannel> {
+        let index = self.channels.iter().position(|channel| channel.name == name)?;
+        Some(self.channels.remove(index))
+    }
+
+    /// Moves the channel at `from` to `to`, shifting the channels in between
+    pub fn reorder(&mut self, from: usize, to: usize) {
+        if from >= self.channels.len() || to >= self.channels.len() {
+            return;
+        }
+        let channel = self.channels.remove(from);
+        self.channels.insert(to, channel);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Cell color at time `t`; channels targeting scale or rotation are written into `transform`
+    pub fn apply(&self, t: f32, groups: &SyncGroups, transform: &mut Transform) -> Color {
+        let mut color = MOTOR_BASE_COLOR;
+        for (target, value) in MotorTarget::ALL.into_iter().zip(self.blended(t, groups)) {
+            if let Some(value) = value {
+                target.apply(value, &mut color, transform);
+            }
+        }
+        color.into()
+    }
+
+    /// Blended value per target, indexed like [`MotorTarget::ALL`]; allocation free for per-frame use
+    pub fn blended(&self, t: f32, groups: &SyncGroups) -> [Option<f32>; MotorTarget::ALL.len()] {
+        let mut values = [None; MotorTarget::ALL.len()];
+        for channel in &self.channels {
+            let slot = &mut values[channel.target.index()];
+            *slot = Some(channel.blend.blend(*slot, channel.value(t, groups)));
+        }
+        values
+    }
+}
+
+/// How the distance between a cell and the wave source is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaveShape {
+    /// Straight-line distance, rings spread out from the source
+    #[default]
+    Radial,
+    /// Only the row offset counts, fronts are horizontal lines
+    Row,
+    /// Only the column offset counts, fronts are vertical lines
+    Column,
+    /// Row offset plus column offset, fronts are diamonds
+    Manhattan,
+}
+
+impl WaveShape {
+    /// Returns the next shape, wrapping around after `Manhattan`
+    pub fn next(self) -> Self {
+        match self {
+            WaveShape::Radial => WaveShape::Row,
+            WaveShape::Row => WaveShape::Column,
+            WaveShape::Column => WaveShape::Manhattan,
+            WaveShape::Manhattan => WaveShape::Radial,
+        }
+    }
+}
+
+/// Delays a cell's motor by its grid distance to a source cell
+#[derive(Component, Debug, Clone)]
+pub struct Wave {
+    pub source_row: usize,
+    pub source_col: usize,
+    pub shape: WaveShape,
+    /// Cells travelled per second
+    pub speed: f32,
+}
+
+impl Wave {
+    /// Distance from the source cell to `cell`, in cells
+    pub fn distance(&self, cell: &GridCell) -> f32 {
+        let d_row = (cell.row as f32 - self.source_row as f32).abs();
+        let d_col = (cell.col as f32 - self.source_col as f32).abs();
+        match self.shape {
+            WaveShape::Radial => (d_row * d_row + d_col * d_col).sqrt(),
+            WaveShape::Row => d_row,
+            WaveShape::Column => d_col,
+            WaveShape::Manhattan => d_row + d_col,
+        }
+    }
+
+    /// Seconds it takes the wave to reach `cell` from the source
+    pub fn delay(&self, cell: &GridCell) -> f32 {
+        if self.speed <= 0.0 {
+            return 0.0;
+        }
+        self.distance(cell) / self.speed
+    }
+}
+
+/// Fires this cell's triggered motor `delay` seconds after the cell at `row`, `col` fires
+#[derive(Component, Debug, Clone)]
+pub struct TriggerFrom {
+    pub row: usize,
+    pub col: usize,
+    pub delay: f32,
+}
+
+/// Look a cell had before motors were attached, restored when the last channel is removed
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MotorBase {
+    pub color: Color,
+    pub scale: Vec3,
+    pub rotation: Quat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadeDirection {
+    /// Crossfading from `from` into the motor output
+    In,
+    /// Easing from `from` back to the [`MotorBase`]
+    Out,
+}
+
+/// Running attach or detach transition
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MotorFade {
+    pub direction: FadeDirection,
+    /// Transport time the fade started at, in seconds
+    pub started: f64,
+    /// What the cell looked like when the fade started
+    pub from: MotorBase,
+}
+
+impl MotorFade {
+    /// Eased progress in [0, 1]; a fade the transport has jumped back before counts as finished
+    pub fn progress(&self, now: f64, settings: &MotorFadeSettings) -> f32 {
+        if settings.duration <= 0.0 || now < self.started {
+            return 1.0;
+        }
+        let linear = ((now - self.started) as f32 / settings.duration).clamp(0.0, 1.0);
+        settings.easing.sample_clamped(linear)
+    }
+
+    /// Blends `from` towards the given look by `progress`
+    pub fn blend(&self, progress: f32, color: Color, transform: &mut Transform) -> Color {
+        transform.scale = self.from.scale.lerp(transform.scale, progress);
+        transform.rotation = self.from.rotation.slerp(transform.rotation, progress);
+        self.from.color.mix(&color, progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_follow_transport_time() {
+        let settings = MotorFadeSettings {
+            duration: 2.0,
+            easing: EaseFunction::Linear,
+        };
+        let fade = MotorFade {
+            direction: FadeDirection::In,
+            started: 10.0,
+            from: MotorBase {
+                color: Color::WHITE,
+                scale: Vec3::ONE,
+                rotation: Quat::IDENTITY,
+            },
+        };
+        assert_eq!(fade.progress(10.0, &settings), 0.0);
+        assert_eq!(fade.progress(11.0, &settings), 0.5);
+        assert_eq!(fade.progress(15.0, &settings), 1.0);
+        // Seeking back before the fade started skips it
+        assert_eq!(fade.progress(4.0, &settings), 1.0);
+    }
+}
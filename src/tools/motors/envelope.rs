@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::motors::MotorTarget;
+
+/// Attack-decay-sustain-release shape of a one-shot, times in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Adsr {
+    pub attack: f32,
+    pub decay: f32,
+    /// Level held after the decay, in [0, 1]
+    pub sustain: f32,
+    /// How long the sustain is held before the release, one-shots have no note-off
+    pub hold: f32,
+    pub release: f32,
+}
+
+impl Default for Adsr {
+    fn default() -> Self {
+        Self {
+            attack: 0.02,
+            decay: 0.15,
+            sustain: 0.6,
+            hold: 0.2,
+            release: 0.6,
+        }
+    }
+}
+
+impl Adsr {
+    pub fn duration(&self) -> f32 {
+        self.attack + self.decay + self.hold + self.release
+    }
+
+    /// Level `age` seconds after the trigger; the attack rises from `start_level` so retriggers don't jump
+    pub fn level(&self, age: f32, start_level: f32) -> f32 {
+        if age < 0.0 {
+            return start_level;
+        }
+        if age < self.attack {
+            return start_level + (1.0 - start_level) * age / self.attack;
+        }
+        let age = age - self.attack;
+        if age < self.decay {
+            return 1.0 + (self.sustain - 1.0) * age / self.decay;
+        }
+        let age = age - self.decay - self.hold;
+        if age < 0.0 {
+            return self.sustain;
+        }
+        if age < self.release {
+            return self.sustain * (1.0 - age / self.release);
+        }
+        0.0
+    }
+}
+
+/// One running envelope
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Voice {
+    /// Transport time of the trigger, in seconds
+    pub started: f64,
+    pub velocity: f32,
+    pub start_level: f32,
+}
+
+impl Voice {
+    pub fn level(&self, adsr: &Adsr, now: f64) -> f32 {
+        adsr.level((now - self.started) as f32, self.start_level) * self.velocity
+    }
+}
+
+/// Event-triggered envelope written onto one property of a grid cell
+#[derive(Component, Debug, Clone)]
+pub struct TriggeredMotor {
+    pub envelope: Adsr,
+    pub target: MotorTarget,
+    /// Output at full level and velocity
+    pub depth: f32,
+    /// 1 restarts the running envelope from its current level; more layers envelopes,
+    /// stealing the quietest voice once all are busy
+    pub max_voices: usize,
+    pub voices: Vec<Voice>,
+    /// Sprite color before the first voice started, restored on plain cells once all voices end
+    pub rest_color: Option<Color>,
+}
+
+impl Default for TriggeredMotor {
+    fn default() -> Self {
+        Self {
+            envelope: Adsr::default(),
+            target: MotorTarget::Lightness,
+            depth: 0.8,
+            max_voices: 1,
+            voices: Vec::new(),
+            rest_color: None,
+        }
+    }
+}
+
+impl TriggeredMotor {
+    pub fn trigger(&mut self, now: f64, velocity: f32) {
+        let velocity = velocity.clamp(0.0, 1.0);
+        if self.max_voices <= 1 {
+            let start_level = self.voices.first().map_or(0.0, |voice| {
+                self.envelope.level((now - voice.started) as f32, voice.start_level)
+            });
+            self.voices = vec![Voice { started: now, velocity, start_level }];
+            return;
+        }
+        if self.voices.len() >= self.max_voices {
+            // Quietest voice goes first, the oldest one on ties
+            let envelope = self.envelope;
+            if let Some(index) = self
+                .voices
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.level(&envelope, now)
+                        .total_cmp(&b.level(&envelope, now))
+                        .then(a.started.total_cmp(&b.started))
+                })
+                .map(|(index, _)| index)
+            {
+                self.voices.remove(index);
+            }
+        }
+        self.voices.push(Voice { started: now, velocity, start_level: 0.0 });
+    }
+
+    /// Sum of the running voices, capped at full level
+    pub fn value(&self, now: f64) -> f32 {
+        let level: f32 = self.voices.iter().map(|voice| voice.level(&self.envelope, now)).sum();
+        level.min(1.0) * self.depth
+    }
+
+    /// Drops voices whose release has finished, and those the transport has jumped back before
+    pub fn prune(&mut self, now: f64) {
+        let duration = self.envelope.duration() as f64;
+        self.voices.retain(|voice| (0.0..duration).contains(&(now - voice.started)));
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.voices.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADSR: Adsr = Adsr {
+        attack: 1.0,
+        decay: 1.0,
+        sustain: 0.5,
+        hold: 1.0,
+        release: 2.0,
+    };
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn level_walks_through_the_stages() {
+        assert!(close(ADSR.duration(), 5.0));
+        assert!(close(ADSR.level(-1.0, 0.2), 0.2));
+        // Attack, from silence and from a retrigger's level
+        assert!(close(ADSR.level(0.5, 0.0), 0.5));
+        assert!(close(ADSR.level(0.5, 0.5), 0.75));
+        // Decay to the sustain level, then the hold
+        assert!(close(ADSR.level(1.5, 0.0), 0.75));
+        assert!(close(ADSR.level(2.5, 0.0), 0.5));
+        // Release
+        assert!(close(ADSR.level(4.0, 0.0), 0.25));
+        assert!(close(ADSR.level(5.0, 0.0), 0.0));
+        assert!(close(ADSR.level(9.0, 0.0), 0.0));
+    }
+
+    #[test]
+    fn retrigger_starts_from_the_current_level() {
+        let mut motor = TriggeredMotor { envelope: ADSR, depth: 1.0, ..default() };
+        motor.trigger(0.0, 1.0);
+        assert!(close(motor.value(1.0), 1.0));
+        motor.trigger(4.0, 1.0);
+        assert_eq!(motor.voices.len(), 1);
+        assert!(close(motor.value(4.0), 0.25));
+    }
+
+    #[test]
+    fn voices_are_stolen_quietest_first_and_pruned() {
+        let mut motor = TriggeredMotor { envelope: ADSR, depth: 1.0, max_voices: 2, ..default() };
+        motor.trigger(0.0, 1.0);
+        motor.trigger(1.0, 0.2);
+        // The quiet second voice goes, not the older loud one
+        motor.trigger(2.0, 1.0);
+        let starts: Vec<f64> = motor.voices.iter().map(|voice| voice.started).collect();
+        assert_eq!(starts, vec![0.0, 2.0]);
+
+        motor.prune(5.5);
+        assert_eq!(motor.voices.len(), 1);
+        // Jumping back before a voice started drops it too
+        motor.prune(1.0);
+        assert!(!motor.is_active());
+    }
+}
@@ -1,8 +1,28 @@
 use bevy::prelude::*;
 
+/// Toggles the current palette preset on one grid cell
 #[derive(Event)]
 pub struct ToggleMotor {
     pub row: usize,
     pub col: usize,
 }
 
+/// Starts a wave from the given cell through every motored grid cell
+#[derive(Event)]
+pub struct EmitWave {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Fires the triggered motor on one grid cell
+#[derive(Event, Debug, Clone)]
+pub struct TriggerMotor {
+    pub row: usize,
+    pub col: usize,
+    /// Envelope level scale in [0, 1]
+    pub velocity: f32,
+}
+
+/// Restarts every sync group clock at the current transport time
+#[derive(Event, Debug, Clone)]
+pub struct ResyncMotors;
@@ -1,13 +1,24 @@
 mod components;
+mod envelope;
 mod events;
 mod interactions;
 mod observers;
 mod plugin;
+mod presets;
+mod resources;
+mod sync;
 mod systems;
+mod waveform;
 
 // Re-export the plugin for easy access
 pub use plugin::MotorsPlugin;
 
 // Re-export commonly used components and events if needed by other modules
 pub use components::*;
+pub use envelope::TriggeredMotor;
 pub use events::*;
+pub use resources::*;
+pub use sync::*;
+pub use systems::motors_update;
+pub use presets::*;
+pub use waveform::Waveform;
@@ -1,23 +1,116 @@
 use bevy::prelude::*;
 
-use crate::tools::motors::{Motor, ToggleMotor};
+use crate::tools::motors::systems::toggle_preset_channel;
+use crate::tools::motors::{
+    EmitWave, MotorPresetLibrary, Motors, PendingTriggers, ResyncMotors, SyncGroups, ToggleMotor, TriggerFrom, TriggerMotor,
+    TriggeredMotor, Wave, WaveSettings,
+};
+use crate::tools::transport::Transport;
+use crate::tools::tile_map_grid::components::{GridCell, MainCell};
 
 
 
+/// Emits a wave from the clicked cell while Alt is held, so plain clicks only select
+pub fn emit_wave_on_cell_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cells: Query<&GridCell, With<MainCell>>,
+) {
+    if !keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+        return;
+    }
+    let Ok(cell) = cells.get(trigger.target()) else {
+        return;
+    };
+    commands.trigger(EmitWave {
+        row: cell.row,
+        col: cell.col,
+    });
+}
 
-// pub fn toggle_motor_observer(
-//     trigger: Trigger<ToggleMotor>,
-//     mut commands: Commands,
-//     mut grid_query: Query<(Entity, &mut Transform, &Motor, &mut Sprite)>,
-//     time: Res<Time>,
-// ) {
-//     let event = trigger.event();
-//     for (entity, mut transform, motor, mut sprite) in grid_query.iter_mut() {
-//         let freq = motor.freq;
-//         let time_factor = (time.elapsed_secs() * freq as f32).sin();
-//         let hue = (time_factor * 360.0).abs() % 360.0;
-//         let new_color = Color::hsl(hue, 0.8, 0.6);
+pub fn emit_wave_observer(
+    trigger: Trigger<EmitWave>,
+    mut commands: Commands,
+    motored_cells: Query<Entity, (With<Motors>, With<GridCell>)>,
+    settings: Res<WaveSettings>,
+) {
+    let event = trigger.event();
+    log::info!("Emitting {:?} wave from row={}; col={};", settings.shape, event.row, event.col);
+    for entity in motored_cells.iter() {
+        commands.entity(entity).insert(Wave {
+            source_row: event.row,
+            source_col: event.col,
+            shape: settings.shape,
+            speed: settings.speed,
+        });
+    }
+}
 
-//         sprite.color = new_color;
-//     }
-// }
+/// Toggles the current palette preset on the cell at `row`, `col`
+pub fn toggle_motor_observer(
+    trigger: Trigger<ToggleMotor>,
+    mut commands: Commands,
+    library: Res<MotorPresetLibrary>,
+    mut cells: Query<(Entity, &GridCell, Option<&mut Motors>), With<MainCell>>,
+) {
+    let event = trigger.event();
+    let Some(preset) = library.current() else {
+        log::info!("No preset picked, ignoring toggle of row={}; col={};", event.row, event.col);
+        return;
+    };
+    for (entity, cell, motors) in cells.iter_mut() {
+        if cell.row == event.row && cell.col == event.col {
+            toggle_preset_channel(&mut commands, preset, entity, motors);
+        }
+    }
+}
+
+pub fn trigger_motor_on_cell_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    cells: Query<&GridCell, (With<MainCell>, With<TriggeredMotor>)>,
+) {
+    let Ok(cell) = cells.get(trigger.target()) else {
+        return;
+    };
+    commands.trigger(TriggerMotor {
+        row: cell.row,
+        col: cell.col,
+        velocity: 1.0,
+    });
+}
+
+/// Fires the envelope on the cell and queues the cells that follow it
+pub fn trigger_motor_observer(
+    trigger: Trigger<TriggerMotor>,
+    transport: Res<Transport>,
+    mut cells: Query<(&GridCell, &mut TriggeredMotor)>,
+    followers: Query<(&GridCell, &TriggerFrom)>,
+    mut pending: ResMut<PendingTriggers>,
+) {
+    let event = trigger.event();
+    let now = transport.time;
+    for (cell, mut triggered) in cells.iter_mut() {
+        if cell.row == event.row && cell.col == event.col {
+            triggered.trigger(now, event.velocity);
+        }
+    }
+    for (cell, from) in followers.iter() {
+        if from.row == event.row && from.col == event.col {
+            pending.queue.push((
+                now + from.delay as f64,
+                TriggerMotor {
+                    row: cell.row,
+                    col: cell.col,
+                    velocity: event.velocity,
+                },
+            ));
+        }
+    }
+}
+
+pub fn resync_motors_observer(_trigger: Trigger<ResyncMotors>, mut sync: ResMut<SyncGroups>, transport: Res<Transport>) {
+    sync.resync(transport.seconds());
+    log::info!("Resynced {} sync groups", sync.groups.len());
+}
@@ -1,5 +1,18 @@
-use crate::tools::motors::systems::{motors_update, cleanup_motors};
+use crate::tools::motors::observers::{
+    emit_wave_observer, emit_wave_on_cell_click, resync_motors_observer, toggle_motor_observer, trigger_motor_observer,
+    trigger_motor_on_cell_click,
+};
+use crate::tools::motors::systems::{
+    advance_motor_fades, cleanup_motors, fire_pending_triggers, motor_palette_buttons, motor_texture_update,
+    motors_update, preset_rename_entry, preset_tool_buttons, refresh_preset_palette, resync_shortcut, start_motor_fades,
+    toggle_motor_output, trigger_motor_shortcuts, triggered_motors_update, wave_settings_input,
+};
 use crate::tools::motors::systems::startup as motors_startup;
+use crate::tools::motors::{
+    MotorFadeSettings, MotorOutput, MotorPresetLibrary, PendingTriggers, PresetRename, SyncGroups,
+    WaveSettings,
+};
+use crate::ui::keyboard_free;
 use crate::GameState;
 use bevy::prelude::*;
 
@@ -8,17 +21,43 @@ pub struct MotorsPlugin;
 impl Plugin for MotorsPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<WaveSettings>()
+            .init_resource::<MotorPresetLibrary>()
+            .init_resource::<PresetRename>()
+            .init_resource::<PendingTriggers>()
+            .init_resource::<MotorOutput>()
+            .init_resource::<MotorFadeSettings>()
+            .init_resource::<SyncGroups>()
             .add_systems(
                 OnEnter(GameState::GridAndMotors), 
                 motors_startup,
             )
             .add_systems(
                 Update,
-                motors_update.run_if(in_state(GameState::GridAndMotors))
+                (
+                    (motor_palette_buttons, preset_tool_buttons, preset_rename_entry, refresh_preset_palette).chain(),
+                    (
+                        fire_pending_triggers,
+                        start_motor_fades,
+                        motors_update,
+                        advance_motor_fades,
+                        motor_texture_update,
+                        triggered_motors_update,
+                    )
+                        .chain(),
+                    (wave_settings_input, trigger_motor_shortcuts, toggle_motor_output, resync_shortcut).run_if(keyboard_free),
+                )
+                    .run_if(in_state(GameState::GridAndMotors))
             )
             .add_systems(
                 OnExit(GameState::GridAndMotors),
                 cleanup_motors
-            );
+            )
+            .add_observer(emit_wave_on_cell_click)
+            .add_observer(emit_wave_observer)
+            .add_observer(toggle_motor_observer)
+            .add_observer(trigger_motor_on_cell_click)
+            .add_observer(trigger_motor_observer)
+            .add_observer(resync_motors_observer);
     }
 }
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tools::motors::{BlendMode, Motor, MotorChannel, MotorTarget, Waveform};
+
+/// Folder (relative to `assets/`) holding user presets, shared by every document
+pub const MOTOR_PRESET_DIR: &str = "motor_presets";
+pub const MOTOR_PRESET_EXTENSION: &str = "motorpreset.ron";
+
+/// Named motor configuration: waveform, parameters and target binding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MotorPreset {
+    pub name: String,
+    pub motor: Motor,
+    pub target: MotorTarget,
+    #[serde(default)]
+    pub blend: BlendMode,
+}
+
+impl MotorPreset {
+    pub fn new(name: impl Into<String>, motor: Motor, target: MotorTarget) -> Self {
+        Self {
+            name: name.into(),
+            motor,
+            target,
+            blend: BlendMode::default(),
+        }
+    }
+
+    /// Preset captured from a channel already on a cell
+    pub fn from_channel(name: impl Into<String>, channel: &MotorChannel) -> Self {
+        Self {
+            name: name.into(),
+            motor: channel.motor.clone(),
+            target: channel.target,
+            blend: channel.blend,
+        }
+    }
+
+    /// Channel named after the preset, so toggling it again removes it
+    pub fn to_channel(&self) -> MotorChannel {
+        MotorChannel::new(self.name.clone(), self.motor.clone(), self.target).with_blend(self.blend)
+    }
+}
+
+/// Read-only presets that ship with the app
+pub fn builtin_presets() -> Vec<MotorPreset> {
+    vec![
+        MotorPreset::new("Slow hue", Motor::new(0.05), MotorTarget::Hue),
+        MotorPreset::new("Breathe", Motor::new(0.15), MotorTarget::Lightness),
+        MotorPreset::new(
+            "Pulse",
+            Motor {
+                waveform: Waveform::Triangle,
+                ..Motor::new(0.3)
+            },
+            MotorTarget::Scale,
+        ),
+    ]
+}
+
+#[derive(Debug)]
+pub enum MotorPresetError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Serialize(ron::Error),
+}
+
+impl std::fmt::Display for MotorPresetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MotorPresetError::Io(err) => write!(f, "could not access motor preset: {err}"),
+            MotorPresetError::Ron(err) => write!(f, "could not parse motor preset: {err}"),
+            MotorPresetError::Serialize(err) => write!(f, "could not write motor preset: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for MotorPresetError {}
+
+fn preset_dir() -> PathBuf {
+    Path::new("assets").join(MOTOR_PRESET_DIR)
+}
+
+/// File for a preset name, with characters that are awkward in file names replaced
+pub fn motor_preset_file(name: &str) -> PathBuf {
+    let stem: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    preset_dir().join(format!("{stem}.{MOTOR_PRESET_EXTENSION}"))
+}
+
+/// Writes `preset` to `assets/motor_presets/<name>.motorpreset.ron`
+pub fn save_motor_preset(preset: &MotorPreset) -> Result<(), MotorPresetError> {
+    std::fs::create_dir_all(preset_dir()).map_err(MotorPresetError::Io)?;
+    let text = ron::ser::to_string_pretty(preset, ron::ser::PrettyConfig::default())
+        .map_err(MotorPresetError::Serialize)?;
+    std::fs::write(motor_preset_file(&preset.name), text).map_err(MotorPresetError::Io)
+}
+
+pub fn delete_motor_preset(name: &str) -> Result<(), MotorPresetError> {
+    std::fs::remove_file(motor_preset_file(name)).map_err(MotorPresetError::Io)
+}
+
+/// Presets saved in `assets/motor_presets`, sorted by name; unreadable files are skipped with a warning
+pub fn load_user_presets() -> Vec<MotorPreset> {
+    let suffix = format!(".{MOTOR_PRESET_EXTENSION}");
+    let Ok(entries) = std::fs::read_dir(preset_dir()) else {
+        return Vec::new();
+    };
+    let mut presets: Vec<MotorPreset> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.ends_with(&suffix)))
+        .filter_map(|path| {
+            let loaded = std::fs::read(&path)
+                .map_err(MotorPresetError::Io)
+                .and_then(|bytes| ron::de::from_bytes::<MotorPreset>(&bytes).map_err(MotorPresetError::Ron));
+            match loaded {
+                Ok(preset) => Some(preset),
+                Err(err) => {
+                    log::warn!("Skipping {}: {err}", path.display());
+                    None
+                }
+            }
+        })
+        .collect();
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    presets
+}
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::tools::motors::presets::{
+    builtin_presets, delete_motor_preset, load_user_presets, motor_preset_file, save_motor_preset, MotorPreset,
+    MotorPresetError,
+};
+use crate::tools::motors::{TriggerMotor, WaveShape};
+
+/// Shape and speed used for waves emitted by Alt-clicking a grid cell
+#[derive(Resource, Debug, Clone)]
+pub struct WaveSettings {
+    pub shape: WaveShape,
+    /// Cells travelled per second
+    pub speed: f32,
+}
+
+impl Default for WaveSettings {
+    fn default() -> Self {
+        Self {
+            shape: WaveShape::Radial,
+            speed: 4.0,
+        }
+    }
+}
+
+/// Built-in and user presets shown in the palette
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MotorPresetLibrary {
+    /// Built-ins first, then user presets sorted by name
+    pub presets: Vec<MotorPreset>,
+    pub builtin_count: usize,
+    /// Preset last clicked in the palette, used by the toolbar buttons
+    pub current: Option<String>,
+    pub status: String,
+}
+
+impl MotorPresetLibrary {
+    /// Re-reads user presets from disk, keeping the current preset if it still exists
+    pub fn reload(&mut self) {
+        let builtins = builtin_presets();
+        self.builtin_count = builtins.len();
+        self.presets = builtins;
+        self.presets.extend(load_user_presets());
+        if self.current.as_ref().is_some_and(|name| self.get(name).is_none()) {
+            self.current = None;
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MotorPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    pub fn current(&self) -> Option<&MotorPreset> {
+        self.current.as_deref().and_then(|name| self.get(name))
+    }
+
+    pub fn is_builtin(&self, name: &str) -> bool {
+        self.presets[..self.builtin_count].iter().any(|preset| preset.name == name)
+    }
+
+    /// `base`, or `base 2`, `base 3`... if that name is taken
+    pub fn unique_name(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut n = 2;
+        while self.get(&name).is_some() || motor_preset_file(&name).exists() {
+            name = format!("{base} {n}");
+            n += 1;
+        }
+        name
+    }
+
+    /// Saves `preset` as a user preset and makes it current
+    pub fn store(&mut self, preset: MotorPreset) -> Result<(), MotorPresetError> {
+        save_motor_preset(&preset)?;
+        self.current = Some(preset.name);
+        self.reload();
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), MotorPresetError> {
+        let Some(mut preset) = self.get(from).cloned() else {
+            return Ok(());
+        };
+        preset.name = to.to_string();
+        save_motor_preset(&preset)?;
+        if motor_preset_file(from) != motor_preset_file(to) {
+            delete_motor_preset(from)?;
+        }
+        self.current = Some(preset.name);
+        self.reload();
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<(), MotorPresetError> {
+        delete_motor_preset(name)?;
+        self.reload();
+        Ok(())
+    }
+}
+
+/// Text being typed as the new name of the current preset
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PresetRename {
+    pub buffer: Option<String>,
+}
+
+/// Chained triggers waiting for their delay to pass
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingTriggers {
+    /// Transport time to fire at, and the trigger
+    pub queue: Vec<(f64, TriggerMotor)>,
+}
+
+/// Where motored cell colors are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MotorOutputMode {
+    /// Each cell's own sprite color and transform
+    #[default]
+    Sprites,
+    /// One texel per cell in a single texture, for very large grids
+    Texture,
+}
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MotorOutput {
+    pub mode: MotorOutputMode,
+    pub image: Option<Handle<Image>>,
+    /// Grid columns and rows the texture was made for
+    pub size: UVec2,
+}
+
+/// How long attaching or detaching motors takes to blend in or out
+#[derive(Resource, Debug, Clone)]
+pub struct MotorFadeSettings {
+    /// Seconds, 0 switches instantly
+    pub duration: f32,
+    pub easing: EaseFunction,
+}
+
+impl Default for MotorFadeSettings {
+    fn default() -> Self {
+        Self {
+            duration: 0.6,
+            easing: EaseFunction::SmoothStep,
+        }
+    }
+}
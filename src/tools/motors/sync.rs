@@ -0,0 +1,96 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+/// Speed of a group member relative to the group clock, 3:2 runs three cycles for every two of the clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncRatio {
+    pub cycles: u32,
+    pub per: u32,
+}
+
+impl SyncRatio {
+    pub const ONE: SyncRatio = SyncRatio::new(1, 1);
+    /// Ratios offered in the inspector
+    pub const COMMON: [SyncRatio; 5] = [
+        SyncRatio::new(1, 2),
+        SyncRatio::ONE,
+        SyncRatio::new(3, 2),
+        SyncRatio::new(2, 1),
+        SyncRatio::new(3, 1),
+    ];
+
+    pub const fn new(cycles: u32, per: u32) -> Self {
+        Self { cycles, per }
+    }
+
+    pub fn factor(self) -> f32 {
+        self.cycles as f32 / self.per.max(1) as f32
+    }
+}
+
+impl std::fmt::Display for SyncRatio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.cycles, self.per)
+    }
+}
+
+/// Locks a motor channel's phase to a sync group; the motor's own phase becomes its offset within the group
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncMember {
+    pub group: String,
+    pub ratio: SyncRatio,
+}
+
+/// Named clock that member channels follow instead of their own frequency
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncGroup {
+    pub name: String,
+    /// Radians per second, like [`Motor::freq`](crate::tools::motors::Motor)
+    pub freq: f64,
+    /// Transport time the clock was last reset at
+    pub epoch: f32,
+}
+
+impl SyncGroup {
+    /// Master phase in cycles at transport time `t`
+    pub fn phase(&self, t: f32) -> f32 {
+        (t - self.epoch) * self.freq as f32 / TAU
+    }
+}
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SyncGroups {
+    pub groups: Vec<SyncGroup>,
+}
+
+impl SyncGroups {
+    pub fn get(&self, name: &str) -> Option<&SyncGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut SyncGroup> {
+        self.groups.iter_mut().find(|group| group.name == name)
+    }
+
+    /// Adds a group with the next free `group N` name, its clock starting at `t`
+    pub fn add(&mut self, freq: f64, t: f32) -> String {
+        let name = (1..)
+            .map(|n| format!("group {n}"))
+            .find(|name| self.get(name).is_none())
+            .unwrap_or_default();
+        self.groups.push(SyncGroup {
+            name: name.clone(),
+            freq,
+            epoch: t,
+        });
+        name
+    }
+
+    /// Restarts every group clock at `t`, bringing all members back to their offsets
+    pub fn resync(&mut self, t: f32) {
+        for group in self.groups.iter_mut() {
+            group.epoch = t;
+        }
+    }
+}
@@ -1,122 +1,734 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
 use bevy::prelude::*;
-use bevy_picking::prelude::{Pickable, Pointer, Click};
-use crate::tools::motors::{Background, Motor, MotorButton, MotorsEntity};
-use crate::GameState;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use crate::systems::loading::FontAssets;
+use crate::tools::motor_scope::{motor_scope, ScopeSource};
+use crate::tools::motors::{
+    FadeDirection, Motor, MotorBase, MotorButton, MotorFade, MotorFadeSettings, MotorOutput, MotorOutputMode, MotorPreset, MotorPresetLibrary, MotorPresetList, MotorPresetStatus, Motors, MotorsContainer,
+    MotorTextureSprite, MotorsEntity, PendingTriggers, PresetRename, PresetToolButton, ResyncMotors, SyncGroups, TriggerFrom, TriggeredMotor, Wave,
+    WaveSettings, MOTOR_BASE_COLOR, motor_preset_file,
+};
+use crate::tools::tile_map_grid::components::{GridCell, MainCell, SelectedCell};
+use crate::tools::transport::Transport;
+use crate::ui::components::ButtonColors;
+use crate::ui::font_utils::text_geist_regular_with_font;
+use crate::ui::KeyboardFocus;
 
 
 
+// Keeps the palette clear of the inspector (left) and graph editor (right) panels
+const PALETTE_LEFT: f32 = 280.0;
+const PALETTE_RIGHT: f32 = 320.0;
+const PALETTE_HEIGHT: f32 = 110.0;
+const KEYBOARD_OWNER: &str = "motor_presets";
+/// Gap between cells chained with Shift+T
+const CHAIN_DELAY: f32 = 0.12;
+
 pub fn startup(
     mut commands: Commands,   
-    windows: Query<&Window>,
-) {
-    if let Ok(window) = windows.single() {
-        let window_size = Vec2::new(window.width(), window.height());
-        
-        // Camera is managed by TileMapGridPlugin in GridAndMotors space
-        
-        commands.spawn((
-            Sprite::from_color(Color::hsla(90.0, 0.6, 0.2, 1.0), Vec2::new(window_size.x, window_size.y)),
-            Transform::from_translation(Vec3::new(0.0, 0.0, -1.0)),
-            Background,
-            MotorsEntity,
-        ));
-        commands.spawn((
-            Text2d::new("Motors"),
-            Transform::from_translation(Vec3::new(0.0, window_size.y / 2.0 - 50.0, 0.0)),
-            MotorsEntity,
-        ));
-        
-        // Spawn back button
-        spawn_motors_ui(&mut commands, window_size);
-        
-        // Create multiple motor buttons with different frequencies and colors
-        let motor_configs = [
-            (0.05, Color::hsla(20.0, 0.6, 0.2, 1.0)),   // Slow red motor
-            (0.15, Color::hsla(120.0, 0.6, 0.2, 1.0)),  // Medium green motor
-            (0.3, Color::hsla(240.0, 0.6, 0.2, 1.0)),   // Fast blue motor
-        ];
-        
-        let start_x = -window_size.x / 2.0 + 100.0;
-        for (i, (freq, color)) in motor_configs.iter().enumerate() {
-            let x = start_x + (i as f32 * 120.0);
-            commands.spawn((
-                Sprite::from_color(*color, Vec2::new(100.0, 100.0)),
-                Transform::from_translation(Vec3::new(x, 0.0, -1.0)),
-                Pickable::default(),
-                MotorButton { freq: *freq },
-                Motor { freq: *freq }, // Each motor button has its own motor effect
-                MotorsEntity,
-            ))
-             .observe(click_on_motor());
-        }
-    }
+    fonts: Res<FontAssets>,
+    mut library: ResMut<MotorPresetLibrary>,
+) {
+    // Camera is managed by the GridAndMotors space
+    // Presets live on disk, so pick up any saved from another document
+    library.reload();
+    spawn_motors_ui(&mut commands, &fonts);
 }
 
 
 
 pub fn motors_update(
-    mut motor_button_query: Query<(Entity, &Motor, &mut Sprite), With<MotorButton>>,
-            mut grid_cell_query: Query<(Entity, &Motor, &mut Sprite), (Without<MotorButton>, With<crate::tools::tile_map_grid::components::GridCell>)>,
-    time: Res<Time>,
+    mut motor_button_query: Query<(&Motor, &mut BackgroundColor), With<MotorButton>>,
+    mut grid_cell_query: Query<
+        (Ref<Motors>, &mut Sprite, &mut Transform, &GridCell, Option<Ref<Wave>>, Option<&MotorFade>),
+        Without<MotorButton>,
+    >,
+    transport: Res<Transport>,
+    output: Res<MotorOutput>,
+    fade_settings: Res<MotorFadeSettings>,
+    sync: Res<SyncGroups>,
 ) {
+    let t = transport.seconds();
     // Update motor buttons with full color animation (same as grid cells)
-    for (_entity, motor, mut sprite) in motor_button_query.iter_mut() {
-        let freq = motor.freq;
-        let time_factor = (time.elapsed_secs() * freq as f32).sin();
-        let hue = (time_factor * 360.0).abs() % 360.0;
-        let new_color = Color::hsl(hue, 0.8, 0.6);
-        sprite.color = new_color;
-    }
-    
-    // Update grid cells with motors (with logging)
-    for (entity, motor, mut sprite) in grid_cell_query.iter_mut() {
-        let freq = motor.freq;
-        let time_factor = (time.elapsed_secs() * freq as f32).sin();
-        let hue = (time_factor * 360.0).abs() % 360.0;
-        let new_color = Color::hsl(hue, 0.8, 0.6);
-        sprite.color = new_color;
-        log::info!("Motor update (grid cell): entity={:?}, freq={}", entity, freq);
-    }
-}
-
-
-fn click_on_motor() -> impl Fn(Trigger<Pointer<Click>>, Commands, Query<&MotorButton>, Query<(Entity, Option<&Motor>), (With<crate::tools::tile_map_grid::components::SelectedCell>, With<crate::tools::tile_map_grid::components::GridCell>, With<Sprite>)>) {
-    move |ev, mut commands, motor_buttons, selected_grid_cells| {
-        let clicked_entity = ev.target();
-        log::info!("Motor button clicked: {:?}", clicked_entity);
-        
-        // Get the motor button's parameters
-        if let Ok(motor_button) = motor_buttons.get(clicked_entity) {
-            // Find selected grid cell sprites and toggle Motor component
-            for (selected_entity, existing_motor) in selected_grid_cells.iter() {
-                if let Some(_motor) = existing_motor {
-                    // Motor exists, remove it
-                    commands.entity(selected_entity).remove::<Motor>();
-                    log::info!("Removed Motor component from selected grid cell sprite: {:?}", selected_entity);
-                } else {
-                    // Motor doesn't exist, add it
-                    commands.entity(selected_entity).insert(Motor { freq: motor_button.freq });
-                    log::info!("Added Motor component to selected grid cell sprite: {:?} with freq: {}", selected_entity, motor_button.freq);
-                }
+    for (motor, mut background) in motor_button_query.iter_mut() {
+        background.0 = motor_color(motor, t);
+    }
+    if output.mode == MotorOutputMode::Texture {
+        return;
+    }
+
+    // While the transport is paused only cells whose motors or wave changed need evaluating
+    let time_moved = transport.is_changed() || sync.is_changed();
+    let now = transport.time;
+    grid_cell_query.par_iter_mut().for_each(|(motors, mut sprite, mut transform, grid_cell, wave, fade)| {
+        let wave_changed = wave.as_ref().is_some_and(|wave| wave.is_changed());
+        if !time_moved && !motors.is_changed() && !wave_changed && fade.is_none() {
+            return;
+        }
+        // Update grid cells with motors, delayed by their distance to the wave source
+        let delay = wave.map_or(0.0, |wave| wave.delay(grid_cell));
+        let mut new_transform = *transform;
+        let mut color = motors.apply(t - delay, &sync, &mut new_transform);
+        if let Some(fade) = fade.filter(|fade| fade.direction == FadeDirection::In) {
+            color = fade.blend(fade.progress(now, &fade_settings), color, &mut new_transform);
+        }
+        // Only write real changes so unchanged cells stay out of change detection and render extraction
+        if sprite.color != color {
+            sprite.color = color;
+        }
+        if *transform != new_transform {
+            *transform = new_transform;
+        }
+    });
+}
+
+/// Starts a fade-in on cells that just got motors and a fade-out on cells that lost them
+///
+/// Fades run on transport time like the motors they blend, so they hold while paused.
+pub fn start_motor_fades(
+    mut commands: Commands,
+    transport: Res<Transport>,
+    attached: Query<(Entity, &Sprite, &Transform, Option<&MotorBase>), Added<Motors>>,
+    mut detached: RemovedComponents<Motors>,
+    cells: Query<(&Sprite, &Transform), (With<MotorBase>, Without<Motors>)>,
+) {
+    let now = transport.time;
+    for (entity, sprite, transform, base) in attached.iter() {
+        let current = MotorBase {
+            color: sprite.color,
+            scale: transform.scale,
+            rotation: transform.rotation,
+        };
+        // A cell re-attached mid fade-out keeps the base it had originally
+        if base.is_none() {
+            commands.entity(entity).insert(current);
+        }
+        commands.entity(entity).insert(MotorFade {
+            direction: FadeDirection::In,
+            started: now,
+            from: current,
+        });
+    }
+    for entity in detached.read() {
+        // Despawned cells and cells whose motors were replaced in the same frame have nothing to fade
+        let Ok((sprite, transform)) = cells.get(entity) else {
+            continue;
+        };
+        commands.entity(entity).insert(MotorFade {
+            direction: FadeDirection::Out,
+            started: now,
+            from: MotorBase {
+                color: sprite.color,
+                scale: transform.scale,
+                rotation: transform.rotation,
+            },
+        });
+    }
+}
+
+/// Runs fade-outs and drops fades that have finished
+pub fn advance_motor_fades(
+    mut commands: Commands,
+    transport: Res<Transport>,
+    settings: Res<MotorFadeSettings>,
+    mut cells: Query<(Entity, &MotorFade, &MotorBase, &mut Sprite, &mut Transform)>,
+) {
+    let now = transport.time;
+    for (entity, fade, base, mut sprite, mut transform) in cells.iter_mut() {
+        let progress = fade.progress(now, &settings);
+        if fade.direction == FadeDirection::Out {
+            let mut target = *transform;
+            target.scale = base.scale;
+            target.rotation = base.rotation;
+            sprite.color = fade.blend(progress, base.color, &mut target);
+            *transform = target;
+        }
+        if progress >= 1.0 {
+            match fade.direction {
+                FadeDirection::In => commands.entity(entity).remove::<MotorFade>(),
+                FadeDirection::Out => commands.entity(entity).remove::<(MotorFade, MotorBase)>(),
+            };
+        }
+    }
+}
+
+/// O switches between per-cell sprite colors and one texture holding every motored cell's color
+pub fn toggle_motor_output(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut output: ResMut<MotorOutput>,
+    mut images: ResMut<Assets<Image>>,
+    cells: Query<(&GridCell, &Transform, &Sprite), With<MainCell>>,
+    texture_sprites: Query<Entity, With<MotorTextureSprite>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyO) {
+        return;
+    }
+    if output.mode == MotorOutputMode::Texture {
+        output.mode = MotorOutputMode::Sprites;
+        output.image = None;
+        for entity in texture_sprites.iter() {
+            commands.entity(entity).despawn();
+        }
+        log::info!("Motor output: sprites");
+        return;
+    }
+
+    let Some(size) = cells.iter().map(|(cell, ..)| UVec2::new(cell.col as u32 + 1, cell.row as u32 + 1)).reduce(UVec2::max) else {
+        return;
+    };
+    let (min, max) = cells.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), (_, transform, _)| {
+        let position = transform.translation.truncate();
+        (min.min(position), max.max(position))
+    });
+    let cell_size = cells.iter().next().and_then(|(_, _, sprite)| sprite.custom_size).unwrap_or(Vec2::ONE);
+    // Cell centers span (size - 1) steps, fall back to the cell size on single row or column grids
+    let step = Vec2::new(
+        if size.x > 1 { (max.x - min.x) / (size.x - 1) as f32 } else { cell_size.x },
+        if size.y > 1 { (max.y - min.y) / (size.y - 1) as f32 } else { cell_size.y },
+    );
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    let handle = images.add(image);
+    commands.spawn((
+        Sprite {
+            image: handle.clone(),
+            custom_size: Some(step * size.as_vec2()),
+            ..default()
+        },
+        // Above the cells, clicks still go through to them
+        Transform::from_translation(((min + max) / 2.0).extend(0.5)),
+        Pickable::IGNORE,
+        MotorTextureSprite,
+        MotorsEntity,
+    ));
+    output.mode = MotorOutputMode::Texture;
+    output.image = Some(handle);
+    output.size = size;
+    log::info!("Motor output: {}x{} texture", size.x, size.y);
+}
+
+/// Writes every motored cell's color into the output texture, one texel per cell;
+/// scale and rotation targets have no effect in this mode
+pub fn motor_texture_update(
+    output: Res<MotorOutput>,
+    mut images: ResMut<Assets<Image>>,
+    cells: Query<(&Motors, &GridCell, Option<&Wave>)>,
+    transport: Res<Transport>,
+    sync: Res<SyncGroups>,
+) {
+    if output.mode != MotorOutputMode::Texture {
+        return;
+    }
+    let Some(data) = output.image.as_ref().and_then(|handle| images.get_mut(handle)).and_then(|image| image.data.as_mut()) else {
+        return;
+    };
+    let t = transport.seconds();
+    let width = output.size.x as usize;
+    data.fill(0);
+    let mut scratch = Transform::default();
+    for (motors, cell, wave) in cells.iter() {
+        // Texture rows run top to bottom, grid rows bottom to top
+        let Some(texel_row) = (output.size.y as usize).checked_sub(cell.row + 1) else {
+            continue;
+        };
+        let index = (texel_row * width + cell.col) * 4;
+        let Some(texel) = data.get_mut(index..index + 4) else {
+            continue;
+        };
+        let delay = wave.map_or(0.0, |wave| wave.delay(cell));
+        texel.copy_from_slice(&motors.apply(t - delay, &sync, &mut scratch).to_srgba().to_u8_array());
+    }
+}
+
+fn motor_color(motor: &Motor, t: f32) -> Color {
+    let mut color = MOTOR_BASE_COLOR;
+    color.hue = (motor.value(t) * 360.0).abs() % 360.0;
+    color.into()
+}
+
+/// Writes each triggered envelope on top of whatever the cell's motor channels produced this frame
+///
+/// Envelopes run on transport time, so they freeze while paused and end when playback jumps back past their start.
+pub fn triggered_motors_update(
+    transport: Res<Transport>,
+    mut cells: Query<(&mut TriggeredMotor, Option<&Motors>, &mut Sprite, &mut Transform)>,
+) {
+    let now = transport.time;
+    for (mut triggered, motors, mut sprite, mut transform) in cells.iter_mut() {
+        if !triggered.is_active() {
+            continue;
+        }
+        let rest_color = *triggered.rest_color.get_or_insert(sprite.color);
+        triggered.prune(now);
+        let mut color = if motors.is_some() { Hsla::from(sprite.color) } else { MOTOR_BASE_COLOR };
+        triggered.target.apply(triggered.value(now), &mut color, &mut transform);
+        sprite.color = color.into();
+        if !triggered.is_active() {
+            triggered.rest_color = None;
+            if motors.is_none() {
+                sprite.color = rest_color;
             }
+        }
+    }
+}
+
+pub fn fire_pending_triggers(
+    mut commands: Commands,
+    transport: Res<Transport>,
+    mut pending: ResMut<PendingTriggers>,
+    mut last: Local<f64>,
+) {
+    let now = transport.time;
+    let previous = std::mem::replace(&mut *last, now);
+    if pending.queue.is_empty() {
+        return;
+    }
+    if now < previous {
+        // Seeked back or looped, queued triggers keep the delay they had left
+        for (at, _) in pending.queue.iter_mut() {
+            *at += now - previous;
+        }
+    }
+    let (due, waiting): (Vec<_>, Vec<_>) = pending.queue.drain(..).partition(|(at, _)| *at <= now);
+    pending.queue = waiting;
+    for (_, trigger) in due {
+        commands.trigger(trigger);
+    }
+}
+
+/// T toggles a triggered motor on the selection, Shift+T chains the selected cells left to right
+pub fn trigger_motor_shortcuts(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    selected: Query<(Entity, &GridCell, Option<&TriggeredMotor>), With<SelectedCell>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+    if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let mut cells: Vec<(Entity, GridCell)> = selected.iter().map(|(entity, cell, _)| (entity, cell.clone())).collect();
+        cells.sort_by_key(|(_, cell)| (cell.col, cell.row));
+        for pair in cells.windows(2) {
+            let (previous, (entity, _)) = (&pair[0].1, &pair[1]);
+            commands.entity(*entity).insert(TriggerFrom {
+                row: previous.row,
+                col: previous.col,
+                delay: CHAIN_DELAY,
+            });
+        }
+        log::info!("Chained {} cells", cells.len());
+        return;
+    }
+    for (entity, _, existing) in selected.iter() {
+        if existing.is_some() {
+            commands.entity(entity).remove::<(TriggeredMotor, TriggerFrom)>();
         } else {
-            log::warn!("Clicked entity is not a motor button: {:?}", clicked_entity);
+            commands.entity(entity).insert(TriggeredMotor::default());
         }
     }
 }
 
-fn spawn_motors_ui(commands: &mut Commands, window_size: Vec2) {
-    // Back button is managed by TileMapGrid plugin in GridAndMotors space
-    
-    // Motor buttons are spawned here
-    // (This function currently doesn't spawn motor buttons - they might be spawned elsewhere)
+/// R restarts every sync group clock
+pub fn resync_shortcut(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>) {
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        commands.trigger(ResyncMotors);
+    }
+}
+
+pub fn wave_settings_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<WaveSettings>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyW) {
+        settings.shape = settings.shape.next();
+        log::info!("Wave shape: {:?}", settings.shape);
+    }
+    if keyboard.just_pressed(KeyCode::ArrowUp) {
+        settings.speed += 1.0;
+        log::info!("Wave speed: {}", settings.speed);
+    }
+    if keyboard.just_pressed(KeyCode::ArrowDown) {
+        settings.speed = (settings.speed - 1.0).max(1.0);
+        log::info!("Wave speed: {}", settings.speed);
+    }
+}
+
+
+pub fn motor_palette_buttons(
+    mut commands: Commands,
+    mut interaction_query: Query<(&Interaction, &MotorButton, &mut BorderColor), Changed<Interaction>>,
+    mut library: ResMut<MotorPresetLibrary>,
+    mut selected_grid_cells: Query<(Entity, Option<&mut Motors>), (With<SelectedCell>, With<GridCell>, With<Sprite>)>,
+) {
+    for (interaction, motor_button, mut border) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *border = BorderColor(Color::WHITE);
+                let Some(preset) = library.get(&motor_button.preset).cloned() else {
+                    continue;
+                };
+                toggle_channel_on_selection(&mut commands, &preset, &mut selected_grid_cells);
+                library.current = Some(preset.name);
+            }
+            Interaction::Hovered => {
+                *border = BorderColor(Color::linear_rgb(0.6, 0.6, 0.6));
+            }
+            Interaction::None => {
+                *border = BorderColor(preset_border(&library, &motor_button.preset));
+            }
+        }
+    }
+}
+
+fn preset_border(library: &MotorPresetLibrary, preset: &str) -> Color {
+    if library.current.as_deref() == Some(preset) {
+        Color::WHITE
+    } else {
+        Color::NONE
+    }
+}
+
+fn toggle_channel_on_selection(
+    commands: &mut Commands,
+    preset: &MotorPreset,
+    selected_grid_cells: &mut Query<(Entity, Option<&mut Motors>), (With<SelectedCell>, With<GridCell>, With<Sprite>)>,
+) {
+    // Toggle this preset's channel on every selected grid cell, leaving other channels alone
+    for (selected_entity, existing_motors) in selected_grid_cells.iter_mut() {
+        toggle_preset_channel(commands, preset, selected_entity, existing_motors);
+    }
+}
+
+/// Adds the preset's channel to `entity`, or removes it if the cell already has it
+pub fn toggle_preset_channel(
+    commands: &mut Commands,
+    preset: &MotorPreset,
+    entity: Entity,
+    existing_motors: Option<Mut<Motors>>,
+) {
+    let channel_name = &preset.name;
+    match existing_motors {
+        Some(mut motors) => {
+            if motors.remove(channel_name).is_some() {
+                log::info!("Removed motor channel {} from grid cell sprite: {:?}", channel_name, entity);
+                if motors.is_empty() {
+                    commands.entity(entity).remove::<Motors>();
+                }
+            } else {
+                motors.add(preset.to_channel());
+                log::info!("Added motor channel {} to grid cell sprite: {:?}", channel_name, entity);
+            }
+        }
+        None => {
+            commands.entity(entity).insert(Motors { channels: vec![preset.to_channel()] });
+            log::info!("Added motor channel {} to grid cell sprite: {:?}", channel_name, entity);
+        }
+    }
+}
+
+pub fn preset_tool_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ButtonColors, &PresetToolButton),
+        Changed<Interaction>,
+    >,
+    mut library: ResMut<MotorPresetLibrary>,
+    mut rename: ResMut<PresetRename>,
+    selected: Query<&Motors, (With<SelectedCell>, With<GridCell>)>,
+) {
+    for (interaction, mut color, button_colors, action) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = Color::WHITE.with_alpha(0.3).into();
+                apply_preset_action(*action, &mut library, &mut rename, &selected);
+            }
+            Interaction::Hovered => {
+                *color = button_colors.hovered.into();
+            }
+            Interaction::None => {
+                *color = button_colors.normal.into();
+            }
+        }
+    }
+}
+
+fn apply_preset_action(
+    action: PresetToolButton,
+    library: &mut MotorPresetLibrary,
+    rename: &mut PresetRename,
+    selected: &Query<&Motors, (With<SelectedCell>, With<GridCell>)>,
+) {
+    let current = library.current().cloned();
+    let result = match action {
+        PresetToolButton::Save => {
+            // Prefer the channel the current preset put on the selection, so tweaks made in the inspector are kept
+            let channel = selected.iter().find_map(|motors| {
+                current
+                    .as_ref()
+                    .and_then(|preset| motors.get(&preset.name))
+                    .or_else(|| motors.channels.first())
+            });
+            let Some(channel) = channel else {
+                library.status = "Select a cell with a motor to save".to_string();
+                return;
+            };
+            let name = match &current {
+                Some(preset) if preset.name == channel.name && !library.is_builtin(&preset.name) => preset.name.clone(),
+                _ => library.unique_name(&channel.name),
+            };
+            library
+                .store(MotorPreset::from_channel(name.clone(), channel))
+                .map(|_| format!("Saved {name}"))
+        }
+        PresetToolButton::Duplicate => {
+            let Some(mut preset) = current else {
+                library.status = "Pick a preset to duplicate".to_string();
+                return;
+            };
+            preset.name = library.unique_name(&format!("{} copy", preset.name));
+            let name = preset.name.clone();
+            library.store(preset).map(|_| format!("Duplicated as {name}"))
+        }
+        PresetToolButton::Rename => match current {
+            Some(preset) if !library.is_builtin(&preset.name) => {
+                rename.buffer = Some(preset.name);
+                return;
+            }
+            Some(_) => Ok("Built-in presets are read-only, duplicate first".to_string()),
+            None => Ok("Pick a preset to rename".to_string()),
+        },
+        PresetToolButton::Delete => match current {
+            Some(preset) if !library.is_builtin(&preset.name) => {
+                library.delete(&preset.name).map(|_| format!("Deleted {}", preset.name))
+            }
+            Some(_) => Ok("Built-in presets can't be deleted".to_string()),
+            None => Ok("Pick a preset to delete".to_string()),
+        },
+    };
+    library.status = result.unwrap_or_else(|err| err.to_string());
+}
+
+/// Typed name for the preset being renamed; Enter commits, Escape cancels
+pub fn preset_rename_entry(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut rename: ResMut<PresetRename>,
+    mut library: ResMut<MotorPresetLibrary>,
+    mut focus: ResMut<KeyboardFocus>,
+) {
+    let Some(buffer) = rename.buffer.as_mut() else {
+        focus.release(KEYBOARD_OWNER);
+        keyboard_events.clear();
+        return;
+    };
+    focus.claim(KEYBOARD_OWNER);
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(chars) => buffer.extend(chars.chars().filter(|c| !c.is_control())),
+            Key::Space => buffer.push(' '),
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Escape => {
+                rename.buffer = None;
+                return;
+            }
+            Key::Enter => {
+                let name = buffer.trim().to_string();
+                rename.buffer = None;
+                let Some(current) = library.current.clone() else {
+                    return;
+                };
+                library.status = if name.is_empty() || name == current {
+                    String::new()
+                } else if library.get(&name).is_some() {
+                    format!("A preset called {name} already exists")
+                } else if motor_preset_file(&name) != motor_preset_file(&current) && motor_preset_file(&name).exists() {
+                    // Names differing only in characters files can't hold share a file
+                    format!("Another preset is already saved as {}", motor_preset_file(&name).display())
+                } else {
+                    match library.rename(&current, &name) {
+                        Ok(()) => format!("Renamed to {name}"),
+                        Err(err) => err.to_string(),
+                    }
+                };
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Docked palette of motor presets along the bottom edge of the window
+fn spawn_motors_ui(commands: &mut Commands, fonts: &FontAssets) {
+    // Back button is managed by the GridAndMotors space
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(PALETTE_LEFT),
+                right: Val::Px(PALETTE_RIGHT),
+                bottom: Val::Px(0.0),
+                height: Val::Px(PALETTE_HEIGHT),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(12.0)),
+                column_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            MotorsContainer,
+            MotorsEntity,
+        ))
+        .with_children(|palette| {
+            palette
+                .spawn(Node {
+                    width: Val::Px(150.0),
+                    flex_shrink: 0.0,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                })
+                .with_children(|toolbar| {
+                    toolbar.spawn(text_geist_regular_with_font("Motors", 18.0, Color::WHITE, fonts));
+                    toolbar
+                        .spawn(Node {
+                            flex_wrap: FlexWrap::Wrap,
+                            column_gap: Val::Px(4.0),
+                            row_gap: Val::Px(4.0),
+                            ..default()
+                        })
+                        .with_children(|buttons| {
+                            for (label, action) in [
+                                ("save", PresetToolButton::Save),
+                                ("duplicate", PresetToolButton::Duplicate),
+                                ("rename", PresetToolButton::Rename),
+                                ("delete", PresetToolButton::Delete),
+                            ] {
+                                spawn_tool_button(buttons, label, action, fonts);
+                            }
+                        });
+                    toolbar.spawn((
+                        text_geist_regular_with_font("", 11.0, Color::linear_rgb(0.7, 0.7, 0.7), fonts),
+                        MotorPresetStatus,
+                    ));
+                });
+            palette.spawn((
+                Node {
+                    flex_grow: 1.0,
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(12.0),
+                    overflow: Overflow::clip_x(),
+                    ..default()
+                },
+                MotorPresetList,
+            ));
+        });
+}
+
+fn spawn_tool_button(parent: &mut ChildSpawnerCommands, label: &str, action: PresetToolButton, fonts: &FontAssets) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(ButtonColors::default().normal),
+            BorderRadius::all(Val::Px(3.0)),
+            ButtonColors::default(),
+            action,
+        ))
+        .with_children(|button| {
+            button.spawn(text_geist_regular_with_font(label, 12.0, Color::WHITE, fonts));
+        });
+}
+
+/// Rebuilds the preset buttons when the library changes and keeps the status line current
+pub fn refresh_preset_palette(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    library: Res<MotorPresetLibrary>,
+    rename: Res<PresetRename>,
+    lists: Query<Entity, With<MotorPresetList>>,
+    mut statuses: Query<&mut Text, With<MotorPresetStatus>>,
+) {
+    if rename.is_changed() || library.is_changed() {
+        for mut status in statuses.iter_mut() {
+            status.0 = match &rename.buffer {
+                Some(buffer) => format!("Name: {buffer}_"),
+                None => library.status.clone(),
+            };
+        }
+    }
+    if !library.is_changed() {
+        return;
+    }
+
+    for list in lists.iter() {
+        commands.entity(list).despawn_related::<Children>().with_children(|list| {
+            for (index, preset) in library.presets.iter().enumerate() {
+                let label = if index < library.builtin_count {
+                    format!("{}\n{:?}", preset.name, preset.target)
+                } else {
+                    format!("{}\n{:?} *", preset.name, preset.target)
+                };
+                list.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(80.0),
+                        height: Val::Px(80.0),
+                        flex_shrink: 0.0,
+                        border: UiRect::all(Val::Px(2.0)),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    BackgroundColor(MOTOR_BASE_COLOR.into()),
+                    BorderColor(preset_border(&library, &preset.name)),
+                    BorderRadius::all(Val::Px(6.0)),
+                    MotorButton { preset: preset.name.clone() },
+                    preset.motor.clone(), // Each preset button previews its own motor
+                ))
+                .with_children(|button| {
+                    button.spawn(motor_scope(ScopeSource::Preset(preset.name.clone()), 68.0, 24.0));
+                    button.spawn(text_geist_regular_with_font(&label, 12.0, Color::BLACK, &fonts));
+                });
+            }
+        });
+    }
 }
 
 pub fn cleanup_motors(
     mut commands: Commands,
     query: Query<Entity, With<MotorsEntity>>,
+    mut output: ResMut<MotorOutput>,
+    mut sync: ResMut<SyncGroups>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
+    // The output texture sprite was just despawned with the palette
+    *output = MotorOutput::default();
+    // Channels referencing the groups leave with their cells
+    sync.groups.clear();
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Periodic shape a motor oscillates with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Waveform {
+    #[default]
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+}
+
+impl Waveform {
+    /// Samples the waveform at `phase`, measured in cycles; the result is in [-1, 1]
+    pub fn sample(self, phase: f32) -> f32 {
+        let p = phase.rem_euclid(1.0);
+        match self {
+            Waveform::Sine => (p * TAU).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (p - 0.5).abs(),
+            Waveform::Square => if p < 0.5 { 1.0 } else { -1.0 },
+            Waveform::Saw => 2.0 * p - 1.0,
+        }
+    }
+
+    /// Integral of [`sample`](Self::sample) from 0 to `phase`, both measured in cycles
+    ///
+    /// Every shape averages to zero over a cycle, so this is periodic too.
+    pub fn integral(self, phase: f32) -> f32 {
+        let p = phase.rem_euclid(1.0);
+        match self {
+            Waveform::Sine => (1.0 - (p * TAU).cos()) / TAU,
+            Waveform::Triangle => if p < 0.5 { 2.0 * p * p - p } else { -2.0 * p * p + 3.0 * p - 1.0 },
+            Waveform::Square => if p < 0.5 { p } else { 1.0 - p },
+            Waveform::Saw => p * p - p,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integral_matches_the_summed_samples() {
+        const STEPS: usize = 2000;
+        for waveform in [Waveform::Sine, Waveform::Triangle, Waveform::Square, Waveform::Saw] {
+            for phase in [0.1, 0.3, 0.5, 0.7, 0.95, 1.25] {
+                let step = phase / STEPS as f32;
+                let summed: f32 = (0..STEPS).map(|i| waveform.sample((i as f32 + 0.5) * step) * step).sum();
+                let integral = waveform.integral(phase);
+                assert!((summed - integral).abs() < 1e-3, "{waveform:?} at {phase}: {summed} vs {integral}");
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Decoder for the subset of OSC 1.0 sent by common controllers (TouchOSC, Max, SuperCollider)
+
+/// Single OSC argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+    Impulse,
+}
+
+impl OscArg {
+    /// Numeric value of the argument, booleans and impulses count as 0/1
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            OscArg::Int(value) => Some(*value as f32),
+            OscArg::Long(value) => Some(*value as f32),
+            OscArg::Float(value) => Some(*value),
+            OscArg::Double(value) => Some(*value as f32),
+            OscArg::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            OscArg::Impulse => Some(1.0),
+            OscArg::String(_) | OscArg::Blob(_) | OscArg::Nil => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    /// First argument as a number, if any
+    pub fn value(&self) -> Option<f32> {
+        self.args.first().and_then(OscArg::as_f32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscError {
+    Truncated,
+    BadString,
+    BadAddress,
+    UnknownType(char),
+}
+
+impl std::fmt::Display for OscError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OscError::Truncated => write!(f, "packet ends early"),
+            OscError::BadString => write!(f, "string is not null terminated UTF-8"),
+            OscError::BadAddress => write!(f, "address must start with '/'"),
+            OscError::UnknownType(tag) => write!(f, "unsupported type tag '{tag}'"),
+        }
+    }
+}
+
+impl std::error::Error for OscError {}
+
+/// Messages in a UDP packet, with bundles flattened in order
+pub fn decode_packet(bytes: &[u8]) -> Result<Vec<OscMessage>, OscError> {
+    let mut messages = Vec::new();
+    decode_into(bytes, &mut messages)?;
+    Ok(messages)
+}
+
+fn decode_into(bytes: &[u8], messages: &mut Vec<OscMessage>) -> Result<(), OscError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if bytes.starts_with(b"#bundle\0") {
+        reader.pos = 8;
+        // Time tag is ignored, bundle contents are applied as soon as they arrive
+        reader.take(8)?;
+        while reader.pos < bytes.len() {
+            let size = reader.i32()?;
+            let size = usize::try_from(size).map_err(|_| OscError::Truncated)?;
+            decode_into(reader.take(size)?, messages)?;
+        }
+        return Ok(());
+    }
+
+    let address = reader.string()?;
+    if !address.starts_with('/') {
+        return Err(OscError::BadAddress);
+    }
+    // Type tags are optional in very old senders
+    let tags = if reader.pos < bytes.len() { reader.string()? } else { ",".to_string() };
+    let mut args = Vec::new();
+    for tag in tags.chars().skip(1) {
+        args.push(match tag {
+            'i' => OscArg::Int(reader.i32()?),
+            'h' => OscArg::Long(i64::from_be_bytes(reader.array()?)),
+            'f' => OscArg::Float(f32::from_be_bytes(reader.array()?)),
+            'd' => OscArg::Double(f64::from_be_bytes(reader.array()?)),
+            's' | 'S' => OscArg::String(reader.string()?),
+            'b' => {
+                let size = usize::try_from(reader.i32()?).map_err(|_| OscError::Truncated)?;
+                let blob = reader.take(size)?.to_vec();
+                reader.align();
+                OscArg::Blob(blob)
+            }
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            'N' => OscArg::Nil,
+            'I' => OscArg::Impulse,
+            other => return Err(OscError::UnknownType(other)),
+        });
+    }
+    messages.push(OscMessage { address, args });
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OscError> {
+        let end = self.pos.checked_add(len).ok_or(OscError::Truncated)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(OscError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], OscError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn i32(&mut self) -> Result<i32, OscError> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    /// Skips padding up to the next multiple of four bytes
+    fn align(&mut self) {
+        self.pos = self.pos.next_multiple_of(4).min(self.bytes.len());
+    }
+
+    fn string(&mut self) -> Result<String, OscError> {
+        let rest = self.bytes.get(self.pos..).ok_or(OscError::Truncated)?;
+        let len = rest.iter().position(|byte| *byte == 0).ok_or(OscError::BadString)?;
+        let text = std::str::from_utf8(&rest[..len]).map_err(|_| OscError::BadString)?.to_string();
+        self.pos += len + 1;
+        self.align();
+        Ok(text)
+    }
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct OscEntity;
+
+#[derive(Component)]
+pub struct OscStatusText;
+
+/// Button showing the listening port, clicking it lets the port be typed in
+#[derive(Component)]
+pub struct OscPortButton;
+
+#[derive(Component)]
+pub struct OscPortText;
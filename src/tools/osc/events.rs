@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+use crate::tools::osc::codec::OscMessage;
+
+/// Decoded message from the OSC socket
+#[derive(Event, Debug, Clone)]
+pub struct OscReceived(pub OscMessage);
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tools::motor_inspector::MotorParam;
+use crate::tools::osc::OscSettings;
+use crate::tools::transport::TransportCommand;
+
+/// Learned bindings, relative to `assets/`
+pub const OSC_BINDINGS_FILE: &str = "osc_bindings.ron";
+/// Server settings, relative to `assets/`
+pub const OSC_SETTINGS_FILE: &str = "osc_settings.ron";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransportControl {
+    Play,
+    Pause,
+    Toggle,
+    Stop,
+    Seek,
+    Rate,
+    Bpm,
+}
+
+impl TransportControl {
+    /// Command for an incoming value; controls that need a value ignore messages without one
+    pub fn command(self, value: Option<f32>) -> Option<TransportCommand> {
+        match self {
+            TransportControl::Play => Some(TransportCommand::Play),
+            TransportControl::Pause => Some(TransportCommand::Pause),
+            // Buttons send 1 on press and 0 on release, only react to the press
+            TransportControl::Toggle => (value.unwrap_or(1.0) > 0.0).then_some(TransportCommand::TogglePlay),
+            TransportControl::Stop => Some(TransportCommand::Stop),
+            TransportControl::Seek => value.map(|seconds| TransportCommand::Seek(seconds.max(0.0) as f64)),
+            TransportControl::Rate => value.map(|rate| TransportCommand::SetRate(rate as f64)),
+            TransportControl::Bpm => Some(TransportCommand::SetBpm(value)),
+        }
+    }
+}
+
+/// What an OSC address controls
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OscAction {
+    /// Parameter of the motor channel called `channel` on every cell that has it
+    MotorParam { channel: String, param: MotorParam },
+    /// Toggles the current palette preset on a cell
+    ToggleCell { row: usize, col: usize },
+    /// Emits a wave from a cell
+    CellWave { row: usize, col: usize },
+    /// Fires a cell's triggered motor, the value is the velocity
+    TriggerCell { row: usize, col: usize },
+    Transport(TransportControl),
+}
+
+impl OscAction {
+    /// Built-in address patterns:
+    /// `/motor/<channel>/freq|phase|amp`, `/cell/<row>/<col>/toggle|wave|trigger` and
+    /// `/transport/play|pause|toggle|stop|seek|rate|bpm`
+    pub fn parse(address: &str) -> Option<OscAction> {
+        let parts: Vec<&str> = address.trim_start_matches('/').split('/').collect();
+        match parts.as_slice() {
+            ["motor", channel, param] => {
+                let param = match *param {
+                    "freq" | "frequency" => MotorParam::Frequency,
+                    "phase" => MotorParam::Phase,
+                    "amp" | "amplitude" => MotorParam::Amplitude,
+                    _ => return None,
+                };
+                Some(OscAction::MotorParam {
+                    channel: channel.to_string(),
+                    param,
+                })
+            }
+            ["cell", row, col, action] => {
+                let row = row.parse().ok()?;
+                let col = col.parse().ok()?;
+                match *action {
+                    "toggle" => Some(OscAction::ToggleCell { row, col }),
+                    "wave" => Some(OscAction::CellWave { row, col }),
+                    "trigger" => Some(OscAction::TriggerCell { row, col }),
+                    _ => None,
+                }
+            }
+            ["transport", control] => Some(OscAction::Transport(match *control {
+                "play" => TransportControl::Play,
+                "pause" => TransportControl::Pause,
+                "toggle" => TransportControl::Toggle,
+                "stop" => TransportControl::Stop,
+                "seek" => TransportControl::Seek,
+                "rate" => TransportControl::Rate,
+                "bpm" => TransportControl::Bpm,
+                _ => return None,
+            })),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            OscAction::MotorParam { channel, param } => format!("{channel} {}", param.label()),
+            OscAction::ToggleCell { row, col } => format!("toggle cell {row},{col}"),
+            OscAction::CellWave { row, col } => format!("wave from cell {row},{col}"),
+            OscAction::TriggerCell { row, col } => format!("trigger cell {row},{col}"),
+            OscAction::Transport(control) => format!("transport {control:?}"),
+        }
+    }
+}
+
+/// Address assigned in learn mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OscBinding {
+    pub action: OscAction,
+    /// Incoming values are 0..1 controller positions, scaled onto the parameter's slider range
+    pub normalized: bool,
+}
+
+/// Addresses can't contain spaces, so `/motor/slow_hue/freq` also reaches a channel called "Slow hue"
+pub fn channel_matches(name: &str, id: &str) -> bool {
+    name.eq_ignore_ascii_case(id) || name.replace(' ', "_").eq_ignore_ascii_case(id)
+}
+
+pub fn load_osc_bindings() -> BTreeMap<String, OscBinding> {
+    let path = Path::new("assets").join(OSC_BINDINGS_FILE);
+    let Ok(bytes) = std::fs::read(&path) else {
+        return BTreeMap::new();
+    };
+    ron::de::from_bytes(&bytes).unwrap_or_else(|err| {
+        log::warn!("Ignoring {}: {err}", path.display());
+        BTreeMap::new()
+    })
+}
+
+pub fn save_osc_bindings(bindings: &BTreeMap<String, OscBinding>) -> std::io::Result<()> {
+    let text = ron::ser::to_string_pretty(bindings, ron::ser::PrettyConfig::default()).map_err(std::io::Error::other)?;
+    std::fs::create_dir_all("assets")?;
+    std::fs::write(Path::new("assets").join(OSC_BINDINGS_FILE), text)
+}
+
+pub fn load_osc_settings() -> Option<OscSettings> {
+    let path = Path::new("assets").join(OSC_SETTINGS_FILE);
+    let bytes = std::fs::read(&path).ok()?;
+    match ron::de::from_bytes(&bytes) {
+        Ok(settings) => Some(settings),
+        Err(err) => {
+            log::warn!("Ignoring {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+pub fn save_osc_settings(settings: &OscSettings) -> std::io::Result<()> {
+    let text = ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default()).map_err(std::io::Error::other)?;
+    std::fs::create_dir_all("assets")?;
+    std::fs::write(Path::new("assets").join(OSC_SETTINGS_FILE), text)
+}
@@ -0,0 +1,14 @@
+mod codec;
+mod components;
+mod events;
+mod mapping;
+mod observers;
+mod plugin;
+mod resources;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use plugin::OscPlugin;
+
+pub use events::OscReceived;
+pub use resources::*;
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use crate::tools::motors::{EmitWave, Motors, ToggleMotor, TriggerMotor};
+use crate::tools::osc::mapping::{channel_matches, save_osc_bindings, OscAction, OscBinding};
+use crate::tools::osc::{OscBindings, OscLearn, OscReceived, OscServer};
+use crate::tools::tile_map_grid::components::{GridCell, MainCell};
+
+pub fn osc_message_observer(
+    trigger: Trigger<OscReceived>,
+    mut commands: Commands,
+    mut server: ResMut<OscServer>,
+    mut bindings: ResMut<OscBindings>,
+    mut learn: ResMut<OscLearn>,
+    mut cells: Query<&mut Motors, With<GridCell>>,
+) {
+    let message = &trigger.event().0;
+
+    if learn.active {
+        let Some(action) = learn.target.take() else {
+            server.status = format!("Learn: pick a cell or parameter for {}", message.address);
+            return;
+        };
+        let normalized = matches!(action, OscAction::MotorParam { .. });
+        server.status = format!("Learned {} -> {}", message.address, action.describe());
+        log::info!("{}", server.status);
+        bindings.0.insert(message.address.clone(), OscBinding { action, normalized });
+        // One binding per learn, press L again for the next
+        learn.active = false;
+        if let Err(err) = save_osc_bindings(&bindings.0) {
+            server.status = format!("Could not save OSC bindings: {err}");
+        }
+        return;
+    }
+
+    let (action, normalized) = match bindings.0.get(&message.address) {
+        Some(binding) => (binding.action.clone(), binding.normalized),
+        None => match OscAction::parse(&message.address) {
+            Some(action) => (action, false),
+            None => {
+                server.status = format!("Unmapped OSC address {}", message.address);
+                return;
+            }
+        },
+    };
+    let value = message.value();
+    // Buttons send 1 on press and 0 on release
+    let pressed = value.unwrap_or(1.0) > 0.0;
+
+    match action {
+        OscAction::MotorParam { channel, param } => {
+            let Some(mut value) = value else {
+                return;
+            };
+            if normalized {
+                let (min, max) = param.range();
+                value = min + value.clamp(0.0, 1.0) * (max - min);
+            }
+            for mut motors in cells.iter_mut() {
+                for motor_channel in motors.channels.iter_mut().filter(|c| channel_matches(&c.name, &channel)) {
+                    param.set(&mut motor_channel.motor, value);
+                }
+            }
+        }
+        OscAction::ToggleCell { row, col } => {
+            if pressed {
+                commands.trigger(ToggleMotor { row, col });
+            }
+        }
+        OscAction::CellWave { row, col } => {
+            if pressed {
+                commands.trigger(EmitWave { row, col });
+            }
+        }
+        OscAction::TriggerCell { row, col } => {
+            // Note-off style zero velocity messages are ignored, envelopes are one-shots
+            if pressed {
+                let velocity = value.unwrap_or(1.0).clamp(0.0, 1.0);
+                commands.trigger(TriggerMotor { row, col, velocity });
+            }
+        }
+        OscAction::Transport(control) => {
+            if let Some(command) = control.command(value) {
+                commands.trigger(command);
+            }
+        }
+    }
+}
+
+/// In learn mode, clicking a grid cell makes toggling it the next learned action
+pub fn learn_cell_on_click(
+    trigger: Trigger<Pointer<Click>>,
+    mut learn: ResMut<OscLearn>,
+    mut server: ResMut<OscServer>,
+    cells: Query<&GridCell, With<MainCell>>,
+) {
+    if !learn.active {
+        return;
+    }
+    let Ok(cell) = cells.get(trigger.target()) else {
+        return;
+    };
+    let action = OscAction::ToggleCell {
+        row: cell.row,
+        col: cell.col,
+    };
+    server.status = format!("Learn: send an address for {}", action.describe());
+    learn.target = Some(action);
+}
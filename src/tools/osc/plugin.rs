@@ -0,0 +1,39 @@
+use crate::tools::osc::observers::{learn_cell_on_click, osc_message_observer};
+use crate::tools::osc::systems::{
+    capture_learn_target, osc_learn_input, osc_port_button, osc_port_entry, receive_osc, start_osc_server,
+    stop_osc_server, update_osc_port_text, update_osc_status,
+};
+use crate::tools::osc::{OscBindings, OscLearn, OscPortEntry, OscServer, OscSettings};
+use crate::ui::keyboard_free;
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct OscPlugin;
+
+impl Plugin for OscPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<OscSettings>()
+            .init_resource::<OscServer>()
+            .init_resource::<OscBindings>()
+            .init_resource::<OscLearn>()
+            .init_resource::<OscPortEntry>()
+            .add_systems(OnEnter(GameState::GridAndMotors), start_osc_server)
+            .add_systems(
+                Update,
+                (
+                    (osc_port_button, osc_port_entry).chain(),
+                    receive_osc,
+                    osc_learn_input.run_if(keyboard_free),
+                    capture_learn_target,
+                    update_osc_port_text,
+                    update_osc_status,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::GridAndMotors)),
+            )
+            .add_systems(OnExit(GameState::GridAndMotors), stop_osc_server)
+            .add_observer(osc_message_observer)
+            .add_observer(learn_cell_on_click);
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+use std::net::UdpSocket;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::osc::mapping::{load_osc_settings, OscAction, OscBinding};
+
+pub const DEFAULT_OSC_PORT: u16 = 9000;
+
+/// UDP port the OSC server listens on
+///
+/// Set from the port field of the OSC bar and saved between runs; `OSC_PORT` overrides the saved value.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OscSettings {
+    pub port: u16,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        let saved = load_osc_settings().map_or(DEFAULT_OSC_PORT, |settings| settings.port);
+        let port = std::env::var("OSC_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(saved);
+        Self { port }
+    }
+}
+
+/// Socket the server reads from while the GridAndMotors space is open
+#[derive(Resource, Debug, Default)]
+pub struct OscServer {
+    pub socket: Option<UdpSocket>,
+    pub port: u16,
+    pub status: String,
+}
+
+/// Addresses assigned in learn mode, these take precedence over the built-in patterns
+#[derive(Resource, Debug, Clone, Default)]
+pub struct OscBindings(pub BTreeMap<String, OscBinding>);
+
+/// While active, the next incoming address is bound to `target`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct OscLearn {
+    pub active: bool,
+    pub target: Option<OscAction>,
+}
+
+/// Digits typed into the port field while it is being edited
+#[derive(Resource, Debug, Clone, Default)]
+pub struct OscPortEntry {
+    pub buffer: Option<String>,
+}
@@ -0,0 +1,343 @@
+use std::net::UdpSocket;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+use crate::systems::loading::FontAssets;
+use crate::tools::motor_inspector::MotorInspector;
+use crate::tools::osc::codec::decode_packet;
+use crate::tools::osc::components::{OscEntity, OscPortButton, OscPortText, OscStatusText};
+use crate::tools::osc::mapping::{load_osc_bindings, save_osc_settings, OscAction};
+use crate::tools::osc::{OscBindings, OscLearn, OscPortEntry, OscReceived, OscServer, OscSettings};
+use crate::ui::components::ButtonColors;
+use crate::ui::font_utils::text_geist_regular_with_font;
+use crate::ui::KeyboardFocus;
+
+/// Largest datagram read in one go, bigger packets are truncated by the OS
+const MAX_PACKET_SIZE: usize = 8192;
+const KEYBOARD_OWNER: &str = "osc";
+
+pub fn start_osc_server(
+    mut commands: Commands,
+    fonts: Res<FontAssets>,
+    settings: Res<OscSettings>,
+    mut server: ResMut<OscServer>,
+    mut bindings: ResMut<OscBindings>,
+    mut learn: ResMut<OscLearn>,
+) {
+    bindings.0 = load_osc_bindings();
+    *learn = OscLearn::default();
+    open_socket(&mut server, settings.port);
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(56.0),
+                left: Val::Px(120.0),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            BorderRadius::all(Val::Px(5.0)),
+            OscEntity,
+        ))
+        .with_children(|bar| {
+            bar.spawn((
+                Button,
+                Node {
+                    margin: UiRect::right(Val::Px(8.0)),
+                    padding: UiRect::horizontal(Val::Px(6.0)),
+                    ..default()
+                },
+                BackgroundColor(ButtonColors::default().normal),
+                ButtonColors::default(),
+                OscPortButton,
+            ))
+            .with_children(|button| {
+                button.spawn((
+                    text_geist_regular_with_font("", 12.0, Color::WHITE, &fonts),
+                    OscPortText,
+                ));
+            });
+            bar.spawn((
+                text_geist_regular_with_font("", 12.0, Color::linear_rgb(0.7, 0.7, 0.7), &fonts),
+                OscStatusText,
+            ));
+        });
+}
+
+fn bind_socket(port: u16) -> std::io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(("0.0.0.0", port))?;
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+/// Replaces the server socket with one listening on `port`, reporting the outcome in the status
+fn open_socket(server: &mut OscServer, port: u16) {
+    // The old socket has to go first when rebinding the same port
+    server.socket = None;
+    server.port = port;
+    server.socket = match bind_socket(port) {
+        Ok(socket) => {
+            server.status = format!("OSC listening on udp {port}");
+            Some(socket)
+        }
+        Err(err) => {
+            server.status = format!("OSC port {port} unavailable: {err}");
+            None
+        }
+    };
+    log::info!("{}", server.status);
+}
+
+/// Drains every datagram that arrived since the last frame
+pub fn receive_osc(mut commands: Commands, mut server: ResMut<OscServer>) {
+    let Some(socket) = server.socket.as_ref() else {
+        return;
+    };
+    let mut buffer = [0u8; MAX_PACKET_SIZE];
+    let mut errors = Vec::new();
+    loop {
+        match socket.recv_from(&mut buffer) {
+            Ok((len, from)) => match decode_packet(&buffer[..len]) {
+                Ok(messages) => {
+                    for message in messages {
+                        commands.trigger(OscReceived(message));
+                    }
+                }
+                Err(err) => errors.push(format!("Bad OSC packet from {from}: {err}")),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(err) => {
+                errors.push(format!("OSC socket error: {err}"));
+                break;
+            }
+        }
+    }
+    if let Some(error) = errors.pop() {
+        log::warn!("{error}");
+        server.status = error;
+    }
+}
+
+/// L toggles learn mode
+pub fn osc_learn_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut learn: ResMut<OscLearn>,
+    mut server: ResMut<OscServer>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+    learn.active = !learn.active;
+    learn.target = None;
+    server.status = if learn.active {
+        "Learn: click a cell or a motor parameter, then move a control".to_string()
+    } else {
+        format!("OSC listening on udp {}", server.port)
+    };
+}
+
+/// In learn mode, clicking a parameter value in the inspector makes it the next learned action
+pub fn capture_learn_target(
+    inspector: Res<MotorInspector>,
+    mut learn: ResMut<OscLearn>,
+    mut server: ResMut<OscServer>,
+) {
+    if !learn.active || !inspector.is_changed() {
+        return;
+    }
+    let Some(edit) = inspector.editing.as_ref() else {
+        return;
+    };
+    let action = OscAction::MotorParam {
+        channel: edit.channel.clone(),
+        param: edit.param,
+    };
+    if learn.target.as_ref() != Some(&action) {
+        server.status = format!("Learn: send an address for {}", action.describe());
+        learn.target = Some(action);
+    }
+}
+
+pub fn osc_port_button(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &ButtonColors), (Changed<Interaction>, With<OscPortButton>)>,
+    mut entry: ResMut<OscPortEntry>,
+) {
+    for (interaction, mut color, button_colors) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => entry.buffer = Some(String::new()),
+            Interaction::Hovered => *color = button_colors.hovered.into(),
+            Interaction::None => *color = button_colors.normal.into(),
+        }
+    }
+}
+
+/// Typed port number; Enter saves it and rebinds the server, Escape cancels
+pub fn osc_port_entry(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut entry: ResMut<OscPortEntry>,
+    mut settings: ResMut<OscSettings>,
+    mut server: ResMut<OscServer>,
+    mut focus: ResMut<KeyboardFocus>,
+) {
+    let Some(buffer) = entry.buffer.as_mut() else {
+        focus.release(KEYBOARD_OWNER);
+        keyboard_events.clear();
+        return;
+    };
+    focus.claim(KEYBOARD_OWNER);
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(chars) => buffer.extend(chars.chars().filter(char::is_ascii_digit)),
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Escape => {
+                entry.buffer = None;
+                return;
+            }
+            Key::Enter => {
+                let typed = buffer.trim().to_string();
+                entry.buffer = None;
+                if typed.is_empty() {
+                    return;
+                }
+                match typed.parse::<u16>() {
+                    Ok(port) if port > 0 => {
+                        settings.port = port;
+                        open_socket(&mut server, port);
+                        if let Err(err) = save_osc_settings(&settings) {
+                            server.status = format!("Could not save OSC settings: {err}");
+                        }
+                    }
+                    _ => server.status = format!("{typed} is not a UDP port"),
+                }
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn update_osc_port_text(
+    server: Res<OscServer>,
+    entry: Res<OscPortEntry>,
+    mut texts: Query<&mut Text, With<OscPortText>>,
+) {
+    if !server.is_changed() && !entry.is_changed() {
+        return;
+    }
+    let label = match &entry.buffer {
+        Some(buffer) => format!("port {buffer}_"),
+        None => format!("port {}", server.port),
+    };
+    for mut text in texts.iter_mut() {
+        text.0 = label.clone();
+    }
+}
+
+pub fn update_osc_status(server: Res<OscServer>, mut texts: Query<&mut Text, With<OscStatusText>>) {
+    if !server.is_changed() {
+        return;
+    }
+    for mut text in texts.iter_mut() {
+        text.0 = server.status.clone();
+    }
+}
+
+pub fn stop_osc_server(
+    mut commands: Commands,
+    mut server: ResMut<OscServer>,
+    mut learn: ResMut<OscLearn>,
+    mut entry: ResMut<OscPortEntry>,
+    mut focus: ResMut<KeyboardFocus>,
+    query: Query<Entity, With<OscEntity>>,
+) {
+    // Dropping the socket frees the port for other apps while the space is closed
+    server.socket = None;
+    *learn = OscLearn::default();
+    entry.buffer = None;
+    focus.release(KEYBOARD_OWNER);
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::tools::motor_inspector::MotorParam;
+    use crate::tools::motors::{Motor, MotorChannel, MotorTarget, Motors, ToggleMotor};
+    use crate::tools::osc::observers::osc_message_observer;
+    use crate::tools::tile_map_grid::components::GridCell;
+
+    /// Null terminated and padded to a multiple of four bytes
+    fn push_osc_string(packet: &mut Vec<u8>, text: &str) {
+        packet.extend_from_slice(text.as_bytes());
+        packet.push(0);
+        while !packet.len().is_multiple_of(4) {
+            packet.push(0);
+        }
+    }
+
+    fn float_message(address: &str, value: f32) -> Vec<u8> {
+        let mut packet = Vec::new();
+        push_osc_string(&mut packet, address);
+        push_osc_string(&mut packet, ",f");
+        packet.extend_from_slice(&value.to_be_bytes());
+        packet
+    }
+
+    #[derive(Resource, Default)]
+    struct Toggled(Vec<(usize, usize)>);
+
+    #[test]
+    fn udp_messages_reach_motors_and_cells() {
+        let socket = bind_socket(0).expect("bind OSC server");
+        let port = socket.local_addr().unwrap().port();
+
+        let mut app = App::new();
+        app.insert_resource(OscServer { socket: Some(socket), port, status: String::new() })
+            .init_resource::<OscBindings>()
+            .init_resource::<OscLearn>()
+            .init_resource::<Toggled>()
+            .add_observer(osc_message_observer)
+            .add_observer(|trigger: Trigger<ToggleMotor>, mut toggled: ResMut<Toggled>| {
+                toggled.0.push((trigger.event().row, trigger.event().col));
+            });
+        let cell = app
+            .world_mut()
+            .spawn((
+                GridCell { row: 0, col: 0 },
+                Motors { channels: vec![MotorChannel::new("hue", Motor::default(), MotorTarget::Hue)] },
+            ))
+            .id();
+
+        let client = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        client.send_to(&float_message("/motor/hue/freq", 1.5), ("127.0.0.1", port)).unwrap();
+        client.send_to(&float_message("/cell/2/3/toggle", 1.0), ("127.0.0.1", port)).unwrap();
+
+        // Loopback delivery is quick but not synchronous
+        for _ in 0..200 {
+            app.world_mut().run_system_once(receive_osc).unwrap();
+            if !app.world().resource::<Toggled>().0.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(app.world().resource::<Toggled>().0, vec![(2, 3)]);
+        let motors = app.world().get::<Motors>(cell).unwrap();
+        let freq = MotorParam::Frequency.get(&motors.channels[0].motor);
+        assert!((freq - 1.5).abs() < 1e-5, "frequency is {freq} Hz");
+    }
+}
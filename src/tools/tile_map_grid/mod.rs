@@ -11,6 +11,7 @@ use std::fmt::Debug;
 const CELL_SIZE: f32 = 20.0;
 const GAP_SIZE: f32 = 0.0;
 const N: usize = 20;
+pub const BACKGROUND_COLOR: Color = Color::linear_rgb(0.1, 0.2, 0.3);
 
 #[derive(Component)]
 struct TileMapGridEntity;
@@ -40,7 +41,7 @@ fn startup(mut commands: Commands) {
     
     // Add background
     commands.spawn((
-        Sprite::from_color(Color::linear_rgb(0.1, 0.2, 0.3), Vec2::new(800.0, 600.0)),
+        Sprite::from_color(BACKGROUND_COLOR, Vec2::new(800.0, 600.0)),
         Transform::from_translation(Vec3::new(0.0, 0.0, -1.0)),
         TileMapGridEntity,
     ));
@@ -88,9 +89,10 @@ fn toggle_color_on<E: Debug + Clone + Reflect>() -> impl Fn(Trigger<E>, Commands
         (
         Query<(&mut Sprite, &Transform, &GridCell, Option<&SelectedCell>), With<MainCell>>, 
         Query<&mut Sprite, With<MainCell>>, Query<Entity, With<MainCell>>)>, 
-        Query<Entity, With<Selector>>
+        Query<(Entity, &GridCell), With<Selector>>,
+        Res<ButtonInput<KeyCode>>,
     ) {
-    move |ev, mut commands, mut param_set, selector_entities| {
+    move |ev, mut commands, mut param_set, selector_entities, keyboard| {
         log::info!("Cell clicked");
         let clicked_entity = ev.target();
         
@@ -103,13 +105,24 @@ fn toggle_color_on<E: Debug + Clone + Reflect>() -> impl Fn(Trigger<E>, Commands
             (*transform, grid_cell.clone(), selected_cell.is_some())
         };
         
-        // Remove any existing selector entities (blue borders)
-        for entity in selector_entities.iter() {
-            commands.entity(entity).despawn();
-        }
-        
-        // Remove SelectedCell component from all main cells
-        {
+        // Shift-click adds or removes the clicked cell without touching the rest of the selection
+        let extend_selection = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if extend_selection {
+            if is_selected {
+                commands.entity(clicked_entity).remove::<SelectedCell>();
+                for (entity, selector_cell) in selector_entities.iter() {
+                    if selector_cell.row == grid_cell.row && selector_cell.col == grid_cell.col {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            }
+        } else {
+            // Remove any existing selector entities (blue borders)
+            for (entity, _) in selector_entities.iter() {
+                commands.entity(entity).despawn();
+            }
+            
+            // Remove SelectedCell component from all main cells
             let main_cell_entities = param_set.p2();
             for entity in main_cell_entities.iter() {
                 commands.entity(entity).remove::<SelectedCell>();
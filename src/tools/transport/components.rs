@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// Text readout of the transport state
+#[derive(Component)]
+pub struct TransportBarText;
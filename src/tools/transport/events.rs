@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+/// Request to change the transport, triggered by keyboard shortcuts and remote control
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum TransportCommand {
+    Play,
+    Pause,
+    TogglePlay,
+    Stop,
+    Seek(f64),
+    SetRate(f64),
+    SetLoop(Option<(f64, f64)>),
+    SetBpm(Option<f32>),
+}
@@ -0,0 +1,13 @@
+mod components;
+mod events;
+mod observers;
+mod plugin;
+mod resources;
+mod systems;
+
+// Re-export the plugin for easy access
+pub use plugin::TransportPlugin;
+
+pub use events::*;
+pub use resources::*;
+pub use systems::spawn_transport_bar;
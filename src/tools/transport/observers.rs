@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::tools::transport::{BeatGrid, Transport, TransportCommand};
+
+pub fn transport_command_observer(
+    trigger: Trigger<TransportCommand>,
+    mut transport: ResMut<Transport>,
+) {
+    let command = trigger.event();
+    log::info!("Transport command: {:?}", command);
+    match command {
+        TransportCommand::Play => transport.play(),
+        TransportCommand::Pause => transport.pause(),
+        TransportCommand::TogglePlay => {
+            if transport.is_playing() {
+                transport.pause();
+            } else {
+                transport.play();
+            }
+        }
+        TransportCommand::Stop => transport.stop(),
+        TransportCommand::Seek(time) => transport.seek(*time),
+        TransportCommand::SetRate(rate) => transport.set_rate(*rate),
+        TransportCommand::SetLoop(region) => transport.set_loop(*region),
+        TransportCommand::SetBpm(bpm) => {
+            transport.beat_grid = bpm.filter(|bpm| *bpm > 0.0).map(|bpm| BeatGrid {
+                bpm,
+                ..transport.beat_grid.unwrap_or_default()
+            });
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::tools::transport::observers::transport_command_observer;
+use crate::tools::transport::systems::{advance_transport, transport_shortcuts, update_transport_bar};
+use crate::tools::transport::Transport;
+use crate::ui::keyboard_free;
+use crate::GameState;
+use bevy::prelude::*;
+
+pub struct TransportPlugin;
+
+impl Plugin for TransportPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Transport>()
+            .add_systems(FixedUpdate, advance_transport)
+            .add_systems(
+                Update,
+                (
+                    // Only the spaces showing a transport bar react to its keys
+                    transport_shortcuts.run_if(
+                        keyboard_free.and(in_state(GameState::GridAndMotors).or(in_state(GameState::Flexer))),
+                    ),
+                    update_transport_bar,
+                ),
+            )
+            .add_observer(transport_command_observer);
+    }
+}
@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayState {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+/// Musical grid laid over transport time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeatGrid {
+    pub bpm: f32,
+    pub beats_per_bar: u32,
+}
+
+impl Default for BeatGrid {
+    fn default() -> Self {
+        Self {
+            bpm: 120.0,
+            beats_per_bar: 4,
+        }
+    }
+}
+
+/// Position of the transport on the beat grid, both counted from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarBeat {
+    pub bar: u32,
+    pub beat: u32,
+}
+
+/// Shared clock every motor is evaluated against
+///
+/// Time only moves in `FixedUpdate`, by the fixed timestep scaled by `rate`,
+/// so a document always produces the same frame for the same transport time.
+#[derive(Resource, Debug, Clone)]
+pub struct Transport {
+    pub state: PlayState,
+    /// Seconds since the start of the document
+    pub time: f64,
+    /// Playback speed, 1.0 is real time; negative plays backwards
+    pub rate: f64,
+    /// `(start, end)` in seconds, playback wraps from `end` back to `start`
+    pub loop_region: Option<(f64, f64)>,
+    pub beat_grid: Option<BeatGrid>,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self {
+            state: PlayState::Playing,
+            time: 0.0,
+            rate: 1.0,
+            loop_region: None,
+            beat_grid: None,
+        }
+    }
+}
+
+impl Transport {
+    /// Transport time as used by motor evaluation
+    pub fn seconds(&self) -> f32 {
+        self.time as f32
+    }
+
+    /// Transport time `ahead` real seconds past the last fixed step, for sampling between steps while playing
+    pub fn interpolated(&self, ahead: f64) -> f32 {
+        if !self.is_playing() {
+            return self.seconds();
+        }
+        self.wrap((self.time + ahead * self.rate).max(0.0)) as f32
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state == PlayState::Playing
+    }
+
+    pub fn play(&mut self) {
+        self.state = PlayState::Playing;
+    }
+
+    pub fn pause(&mut self) {
+        if self.state == PlayState::Playing {
+            self.state = PlayState::Paused;
+        }
+    }
+
+    /// Stops playback and rewinds to the loop start, or to zero without a loop
+    pub fn stop(&mut self) {
+        self.state = PlayState::Stopped;
+        self.time = self.loop_region.map_or(0.0, |(start, _)| start);
+    }
+
+    pub fn seek(&mut self, time: f64) {
+        self.time = self.wrap(time.max(0.0));
+    }
+
+    /// Sets the playback rate, ignoring values that aren't finite
+    pub fn set_rate(&mut self, rate: f64) {
+        if rate.is_finite() {
+            self.rate = rate;
+        }
+    }
+
+    pub fn set_loop(&mut self, region: Option<(f64, f64)>) {
+        self.loop_region = region.filter(|(start, end)| end > start && *start >= 0.0);
+        self.time = self.wrap(self.time);
+    }
+
+    /// Moves time forward by `delta` real seconds, scaled by the playback rate
+    pub fn advance(&mut self, delta: f64) {
+        if self.state != PlayState::Playing {
+            return;
+        }
+        self.time = self.wrap((self.time + delta * self.rate).max(0.0));
+    }
+
+    fn wrap(&self, time: f64) -> f64 {
+        match self.loop_region {
+            Some((start, end)) if time >= end || time < start => {
+                start + (time - start).rem_euclid(end - start)
+            }
+            _ => time,
+        }
+    }
+
+    /// Beats elapsed since zero, if a beat grid is set
+    pub fn beats(&self) -> Option<f64> {
+        self.beat_grid.map(|grid| self.time * grid.bpm as f64 / 60.0)
+    }
+
+    pub fn bar_beat(&self) -> Option<BarBeat> {
+        let grid = self.beat_grid?;
+        let beats = self.beats()?.floor() as u64;
+        let per_bar = grid.beats_per_bar.max(1) as u64;
+        Some(BarBeat {
+            bar: (beats / per_bar) as u32 + 1,
+            beat: (beats % per_bar) as u32 + 1,
+        })
+    }
+
+    /// Duration of `beats` beats in seconds, if a beat grid is set
+    pub fn beats_to_seconds(&self, beats: f64) -> Option<f64> {
+        self.beat_grid.map(|grid| beats * 60.0 / grid.bpm as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_scales_by_rate_and_stops_at_zero() {
+        let mut transport = Transport::default();
+        transport.set_rate(2.0);
+        transport.advance(0.5);
+        assert_eq!(transport.time, 1.0);
+
+        transport.set_rate(-1.0);
+        transport.advance(3.0);
+        assert_eq!(transport.time, 0.0);
+
+        transport.pause();
+        transport.seek(2.0);
+        transport.advance(1.0);
+        assert_eq!(transport.time, 2.0);
+    }
+
+    #[test]
+    fn stepping_lands_on_the_same_time_as_seeking() {
+        let mut stepped = Transport::default();
+        stepped.set_loop(Some((0.5, 1.5)));
+        stepped.seek(0.5);
+        let mut sought = stepped.clone();
+        for _ in 0..96 {
+            stepped.advance(1.0 / 64.0);
+        }
+        sought.seek(2.0);
+        assert_eq!(stepped.time, sought.time);
+        assert_eq!(stepped.time, 1.0);
+    }
+
+    #[test]
+    fn loops_wrap_seeks_and_the_current_time() {
+        let mut transport = Transport::default();
+        transport.seek(5.0);
+        transport.set_loop(Some((1.0, 3.0)));
+        assert_eq!(transport.time, 1.0);
+
+        transport.seek(4.5);
+        assert_eq!(transport.time, 2.5);
+        transport.advance(1.0);
+        assert_eq!(transport.time, 1.5);
+        // Times before the loop wrap in from its end, just like times past it
+        transport.seek(0.5);
+        assert_eq!(transport.time, 2.5);
+
+        transport.set_loop(Some((3.0, 1.0)));
+        assert_eq!(transport.loop_region, None);
+    }
+
+    #[test]
+    fn interpolation_runs_ahead_only_while_playing() {
+        let mut transport = Transport::default();
+        transport.set_rate(2.0);
+        transport.seek(1.0);
+        assert_eq!(transport.interpolated(0.25), 1.5);
+
+        transport.set_loop(Some((0.0, 1.25)));
+        transport.seek(1.0);
+        assert_eq!(transport.interpolated(0.25), 0.25);
+
+        transport.pause();
+        assert_eq!(transport.interpolated(0.25), 1.0);
+    }
+
+    #[test]
+    fn rates_that_are_not_finite_are_ignored() {
+        let mut transport = Transport::default();
+        transport.seek(1.0);
+        transport.set_rate(f64::NAN);
+        transport.set_rate(f64::INFINITY);
+        assert_eq!(transport.rate, 1.0);
+        transport.advance(0.5);
+        assert_eq!(transport.time, 1.5);
+    }
+}
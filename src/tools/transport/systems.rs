@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::systems::loading::FontAssets;
+use crate::tools::transport::components::TransportBarText;
+use crate::tools::transport::{PlayState, Transport, TransportCommand};
+use crate::ui::font_utils::text_geist_regular_with_font;
+
+pub fn advance_transport(mut transport: ResMut<Transport>, time: Res<Time<Fixed>>) {
+    if transport.is_playing() {
+        transport.advance(time.delta_secs_f64());
+    }
+}
+
+/// Length in beats of a loop set from one end while the other end is unusable
+const DEFAULT_LOOP_BEATS: f64 = 4.0;
+
+/// Space plays/pauses, Home stops, [ ] halve/double the rate, arrows seek by a beat (a bar with Shift),
+/// , and . set the loop start and end at the playhead, \ clears the loop, - and = change the BPM (by 10 with Shift)
+pub fn transport_shortcuts(mut commands: Commands, keyboard: Res<ButtonInput<KeyCode>>, transport: Res<Transport>) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keyboard.just_pressed(KeyCode::Space) {
+        commands.trigger(TransportCommand::TogglePlay);
+    }
+    if keyboard.just_pressed(KeyCode::Home) {
+        commands.trigger(TransportCommand::Stop);
+    }
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        commands.trigger(TransportCommand::SetRate(transport.rate * 0.5));
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        commands.trigger(TransportCommand::SetRate(transport.rate * 2.0));
+    }
+
+    // Without a beat grid a beat is a second
+    let beats = |count: f64| transport.beats_to_seconds(count).unwrap_or(count);
+    let step = if shift {
+        beats(transport.beat_grid.map_or(4, |grid| grid.beats_per_bar) as f64)
+    } else {
+        beats(1.0)
+    };
+    if keyboard.just_pressed(KeyCode::ArrowLeft) {
+        commands.trigger(TransportCommand::Seek((transport.time - step).max(0.0)));
+    }
+    if keyboard.just_pressed(KeyCode::ArrowRight) {
+        commands.trigger(TransportCommand::Seek(transport.time + step));
+    }
+
+    let now = transport.time;
+    if keyboard.just_pressed(KeyCode::Comma) {
+        let end = transport
+            .loop_region
+            .map(|(_, end)| end)
+            .filter(|end| *end > now)
+            .unwrap_or(now + beats(DEFAULT_LOOP_BEATS));
+        commands.trigger(TransportCommand::SetLoop(Some((now, end))));
+    }
+    if keyboard.just_pressed(KeyCode::Period) {
+        let start = transport
+            .loop_region
+            .map(|(start, _)| start)
+            .filter(|start| *start < now)
+            .unwrap_or_else(|| (now - beats(DEFAULT_LOOP_BEATS)).max(0.0));
+        commands.trigger(TransportCommand::SetLoop(Some((start, now))));
+    }
+    if keyboard.just_pressed(KeyCode::Backslash) {
+        commands.trigger(TransportCommand::SetLoop(None));
+    }
+
+    let bpm_step = if shift { 10.0 } else { 1.0 };
+    let bpm = transport.beat_grid.unwrap_or_default().bpm;
+    if keyboard.just_pressed(KeyCode::Minus) {
+        commands.trigger(TransportCommand::SetBpm(Some((bpm - bpm_step).max(1.0))));
+    }
+    if keyboard.just_pressed(KeyCode::Equal) {
+        commands.trigger(TransportCommand::SetBpm(Some(bpm + bpm_step)));
+    }
+}
+
+/// Spawns the transport readout at the top of the window, returning its entity so the space can clean it up
+pub fn spawn_transport_bar(commands: &mut Commands, fonts: &FontAssets) -> Entity {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                left: Val::Px(120.0),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgb(0.08, 0.08, 0.08)),
+            BorderRadius::all(Val::Px(5.0)),
+        ))
+        .with_children(|bar| {
+            bar.spawn((
+                text_geist_regular_with_font("", 14.0, Color::WHITE, fonts),
+                TransportBarText,
+            ));
+        })
+        .id()
+}
+
+pub fn update_transport_bar(transport: Res<Transport>, mut texts: Query<&mut Text, With<TransportBarText>>) {
+    if !transport.is_changed() {
+        return;
+    }
+    let state = match transport.state {
+        PlayState::Playing => "Playing",
+        PlayState::Paused => "Paused",
+        PlayState::Stopped => "Stopped",
+    };
+    let mut readout = format!("{state} {:.2}s x{}", transport.time, transport.rate);
+    if let (Some(grid), Some(position)) = (transport.beat_grid, transport.bar_beat()) {
+        readout.push_str(&format!(" | {} bpm bar {} beat {}", grid.bpm, position.bar, position.beat));
+    }
+    if let Some((start, end)) = transport.loop_region {
+        readout.push_str(&format!(" | loop {start:.2}-{end:.2}s"));
+    }
+    for mut text in texts.iter_mut() {
+        text.0 = readout.clone();
+    }
+}
@@ -12,10 +12,35 @@ pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, back_button_system);
+        app.init_resource::<KeyboardFocus>()
+            .add_systems(Update, back_button_system);
     }
 }
 
+/// Set while a text field is taking keyboard input, so single-key shortcuts stay quiet
+#[derive(Resource, Default, Debug)]
+pub struct KeyboardFocus {
+    pub owner: Option<&'static str>,
+}
+
+impl KeyboardFocus {
+    pub fn claim(&mut self, owner: &'static str) {
+        self.owner = Some(owner);
+    }
+
+    /// Gives the keyboard back, unless another field has claimed it since
+    pub fn release(&mut self, owner: &'static str) {
+        if self.owner == Some(owner) {
+            self.owner = None;
+        }
+    }
+}
+
+/// Run condition for keyboard shortcuts
+pub fn keyboard_free(focus: Res<KeyboardFocus>) -> bool {
+    focus.owner.is_none()
+}
+
 fn back_button_system(
     mut interaction_query: Query<
         (